@@ -204,10 +204,19 @@ macro_rules! gate_doc { ($($name:ident => $feature:ident)*) => {
                 gate_doc!(
                     cfg => doc_cfg
                     cfg_hide => doc_cfg_hide
+                    macro_signature => doc_macro_signature
                     masked => doc_masked
                     notable_trait => doc_notable_trait
                 );
 
+                if nested_meta.has_name(sym::test)
+                    && let Some(test_metas) = nested_meta.meta_item_list()
+                    && test_metas.iter().any(|test_meta| test_meta.has_name(sym::prelude))
+                {
+                    let msg = "`#![doc(test(prelude))]` is experimental";
+                    gate_feature_post!(self, doc_test_prelude, attr.span, msg);
+                }
+
                 if nested_meta.has_name(sym::keyword) {
                     let msg = "`#[doc(keyword)]` is meant for internal use only";
                     gate_feature_post!(self, rustdoc_internals, attr.span, msg);
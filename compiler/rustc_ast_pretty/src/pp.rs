@@ -203,6 +203,10 @@ enum PrintFrame {
 
 pub struct Printer {
     out: String,
+    /// Target line width
+    margin: isize,
+    /// Space every line is allowed, even if highly indented
+    min_space: isize,
     /// Number of spaces left on line
     space: isize,
     /// Ring-buffer of tokens and calculated sizes
@@ -237,9 +241,17 @@ struct BufEntry {
 
 impl Printer {
     pub fn new() -> Self {
+        Self::with_margin(MARGIN)
+    }
+
+    /// Creates a printer that targets lines of `margin` columns rather than
+    /// the default of 78.
+    pub fn with_margin(margin: isize) -> Self {
         Printer {
             out: String::new(),
-            space: MARGIN,
+            margin,
+            min_space: margin * MIN_SPACE / MARGIN,
+            space: margin,
             buf: RingBuffer::new(),
             left_total: 0,
             right_total: 0,
@@ -251,6 +263,23 @@ pub fn new() -> Self {
         }
     }
 
+    /// Empties the printer so that it prints from scratch with `margin`, as a
+    /// new one would, but with the memory it already allocated.
+    pub fn reset(&mut self, margin: isize) {
+        self.out.clear();
+        self.margin = margin;
+        self.min_space = margin * MIN_SPACE / MARGIN;
+        self.space = margin;
+        self.buf.clear();
+        self.left_total = 0;
+        self.right_total = 0;
+        self.scan_stack.clear();
+        self.print_stack.clear();
+        self.indent = 0;
+        self.pending_indentation = 0;
+        self.last_printed = None;
+    }
+
     pub fn last_token(&self) -> Option<&Token> {
         self.last_token_still_buffered().or_else(|| self.last_printed.as_ref())
     }
@@ -401,7 +430,7 @@ fn print_begin(&mut self, token: BeginToken, size: isize) {
                 IndentStyle::Block { offset } => {
                     usize::try_from(self.indent as isize + offset).unwrap()
                 }
-                IndentStyle::Visual => (MARGIN - self.space) as usize,
+                IndentStyle::Visual => (self.margin - self.space) as usize,
             };
         } else {
             self.print_stack.push(PrintFrame::Fits);
@@ -430,7 +459,7 @@ fn print_break(&mut self, token: BreakToken, size: isize) {
             self.out.push('\n');
             let indent = self.indent as isize + token.offset;
             self.pending_indentation = indent;
-            self.space = cmp::max(MARGIN - indent, MIN_SPACE);
+            self.space = cmp::max(self.margin - indent, self.min_space);
         }
     }
 
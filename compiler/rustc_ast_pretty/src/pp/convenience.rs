@@ -38,6 +38,12 @@ pub fn eof(mut self) -> String {
         self.out
     }
 
+    /// Like `eof`, but keeps the printer, which can be `reset` to print again.
+    pub fn finish(&mut self) -> &str {
+        self.scan_eof();
+        &self.out
+    }
+
     pub fn word<S: Into<Cow<'static, str>>>(&mut self, wrd: S) {
         let string = wrd.into();
         self.scan_string(string)
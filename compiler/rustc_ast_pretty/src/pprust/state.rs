@@ -949,6 +949,11 @@ pub fn new() -> State<'a> {
         State { s: pp::Printer::new(), comments: None, ann: &NoAnn }
     }
 
+    /// Creates a printer that targets lines of `margin` columns.
+    pub fn with_margin(margin: isize) -> State<'a> {
+        State { s: pp::Printer::with_margin(margin), comments: None, ann: &NoAnn }
+    }
+
     pub(crate) fn commasep_cmnt<T, F, G>(
         &mut self,
         b: Breaks,
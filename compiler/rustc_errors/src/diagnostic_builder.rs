@@ -223,9 +223,10 @@ pub(crate) fn new<M: Into<DiagnosticMessage>>(
     }
 
     /// Creates a new `DiagnosticBuilder` with an already constructed
-    /// diagnostic.
+    /// diagnostic, e.g. one that was buffered, so that it can be emitted or
+    /// cancelled.
     #[track_caller]
-    pub(crate) fn new_diagnostic(handler: &'a Handler, diagnostic: Diagnostic) -> Self {
+    pub fn new_diagnostic(handler: &'a Handler, diagnostic: Diagnostic) -> Self {
         debug!("Created new diagnostic");
         Self {
             inner: DiagnosticBuilderInner {
@@ -389,8 +389,12 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, doc_cfg, "1.21.0", Some(43781), None),
     /// Allows `#[doc(cfg_hide(...))]`.
     (active, doc_cfg_hide, "1.57.0", Some(43781), None),
+    /// Allows `#[doc(macro_signature = "...")]`.
+    (active, doc_macro_signature, "CURRENT_RUSTC_VERSION", None, None),
     /// Allows `#[doc(masked)]`.
     (active, doc_masked, "1.21.0", Some(44027), None),
+    /// Allows `#![doc(test(prelude = "..."))]`.
+    (active, doc_test_prelude, "CURRENT_RUSTC_VERSION", None, None),
     /// Allows `dyn* Trait` objects.
     (incomplete, dyn_star, "1.65.0", Some(102425), None),
     /// Allows `X..Y` patterns.
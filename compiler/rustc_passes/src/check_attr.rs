@@ -945,7 +945,7 @@ fn check_test_attr(&self, meta: &NestedMetaItem, hir_id: HirId) -> bool {
         if let Some(metas) = meta.meta_item_list() {
             for i_meta in metas {
                 match i_meta.name_or_empty() {
-                    sym::attr | sym::no_crate_inject => {}
+                    sym::attr | sym::no_crate_inject | sym::prelude => {}
                     _ => {
                         self.tcx.emit_spanned_lint(
                             INVALID_DOC_ATTRIBUTES,
@@ -1076,6 +1076,7 @@ fn check_doc_attrs(
                         | sym::inline
                         | sym::issue_tracker_base_url
                         | sym::keyword
+                        | sym::macro_signature
                         | sym::masked
                         | sym::no_default_passes
                         | sym::no_inline
@@ -634,10 +634,12 @@
         doc_cfg,
         doc_cfg_hide,
         doc_keyword,
+        doc_macro_signature,
         doc_masked,
         doc_notable_trait,
         doc_primitive,
         doc_spotlight,
+        doc_test_prelude,
         doctest,
         document_private_items,
         dotdot: "..",
@@ -911,6 +913,7 @@
         macro_literal_matcher,
         macro_metavar_expr,
         macro_reexport,
+        macro_signature,
         macro_use,
         macro_vis_matcher,
         macros_in_extern,
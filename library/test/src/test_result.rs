@@ -3,7 +3,7 @@
 use super::bench::BenchSamples;
 use super::options::ShouldPanic;
 use super::time;
-use super::types::TestDesc;
+use super::types::{TestDesc, TestType};
 
 pub use self::TestResult::*;
 
@@ -60,6 +60,13 @@ pub fn calc_result<'a>(
         (&ShouldPanic::Yes, Ok(())) | (&ShouldPanic::YesWithMessage(_), Ok(())) => {
             TestResult::TrFailedMsg("test did not panic as expected".to_string())
         }
+        // Doctests tell why they failed through the error returned by their function.
+        (&ShouldPanic::No, Err(err)) if desc.test_type == TestType::DocTest => {
+            match err.downcast_ref::<String>() {
+                Some(msg) => TestResult::TrFailedMsg(msg.clone()),
+                None => TestResult::TrFailed,
+            }
+        }
         _ => TestResult::TrFailed,
     };
 
@@ -211,6 +211,35 @@ fn f() -> Result<(), String> {
     assert_eq!(result, TrOk);
 }
 
+#[test]
+fn doctest_failure_message() {
+    use crate::tests::TrFailedMsg;
+    fn f() -> Result<(), String> {
+        Err("Couldn't compile the test.".to_string())
+    }
+    let desc = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("whatever"),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::DocTest,
+        },
+        testfn: DynTestFn(Box::new(f)),
+    };
+    let (tx, rx) = channel();
+    run_test(&TestOpts::new(), false, TestId(0), desc, RunStrategy::InProcess, tx);
+    let result = rx.recv().unwrap().result;
+    assert_eq!(result, TrFailedMsg("Couldn't compile the test.".to_string()));
+}
+
 // FIXME: Re-enable emscripten once it can catch panics again (introduced by #65251)
 #[test]
 #[cfg(not(target_os = "emscripten"))]
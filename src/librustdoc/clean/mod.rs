@@ -5,7 +5,7 @@
 mod blanket_impl;
 pub(crate) mod cfg;
 pub(crate) mod inline;
-mod render_macro_matchers;
+pub(crate) mod render_macro_matchers;
 mod simplify;
 pub(crate) mod types;
 pub(crate) mod utils;
@@ -2346,9 +2346,8 @@ fn clean_maybe_renamed_item<'tcx>(
             ItemKind::Impl(impl_) => return clean_impl(impl_, item.owner_id.def_id, cx),
             ItemKind::Macro(ref macro_def, MacroKind::Bang) => {
                 let ty_vis = cx.tcx.visibility(def_id);
-                MacroItem(Macro {
-                    source: display_macro_source(cx, name, macro_def, def_id, ty_vis),
-                })
+                let edition = item.span.edition();
+                MacroItem(display_macro_source(cx, name, macro_def, def_id, ty_vis, edition))
             }
             ItemKind::Macro(_, macro_kind) => clean_proc_macro(item, &mut name, macro_kind, cx),
             // proc macros can have a name set by attributes
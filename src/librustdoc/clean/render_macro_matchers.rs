@@ -1,25 +1,1140 @@
-use rustc_ast::token::{self, BinOpToken, Delimiter};
-use rustc_ast::tokenstream::{TokenStream, TokenTree};
+use rustc_ast::token::{self, BinOpToken, CommentKind, Delimiter, Token, TokenKind};
+use rustc_ast::tokenstream::{DelimSpan, RefTokenTreeCursor, Spacing, TokenStream, TokenTree};
+use rustc_ast::util::literal::{escape_byte_str_symbol, escape_char_symbol, escape_string_symbol};
+use rustc_ast::{self as ast, AttrStyle};
 use rustc_ast_pretty::pprust::state::State as Printer;
-use rustc_ast_pretty::pprust::PrintState;
-use rustc_middle::ty::TyCtxt;
+use rustc_ast_pretty::pprust::{self, PrintState};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::{is_dyn_thread_safe, par_map, Lrc};
+use rustc_errors::emitter::EmitterWriter;
+use rustc_errors::{DiagnosticBuilder, FatalErrorMarker, Handler, TerminalUrl};
+use rustc_hir::def_id::DefId;
+use rustc_parse::parser::Parser;
 use rustc_session::parse::ParseSess;
-use rustc_span::source_map::FilePathMapping;
-use rustc_span::symbol::{kw, Ident, Symbol};
-use rustc_span::Span;
+use rustc_session::Session;
+use rustc_span::edition::Edition;
+use rustc_span::hygiene::{ExpnKind, Transparency};
+use rustc_span::source_map::SourceMap;
+use rustc_span::symbol::{kw, sym, Ident, IdentPrinter, Symbol};
+use rustc_span::{BytePos, FileName, InnerSpan, Pos, SourceFile, Span, DUMMY_SP};
+use thin_vec::ThinVec;
+
+use serde::Serialize;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
+use std::iter;
+use std::mem;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use crate::clean::{ItemId, Macro, MacroArm, MacroExport};
+use crate::config::{MacroMatcherStyle, RenderOptions};
+use crate::core::DocContext;
+
+#[cfg(test)]
+mod benches;
+#[cfg(test)]
+mod tests;
+
+/// Indentation of the arms in a rendered macro declaration, which multi-line
+/// matchers are aligned to.
+const RENDERED_ARM_INDENT: &str = "    ";
+
+/// A macro matcher rendered for display.
+#[derive(Clone, Debug)]
+pub(crate) struct RenderedMatcher {
+    pub(crate) text: String,
+    /// `true` if `text` was copied from the source of the matcher, and `false`
+    /// if it was pretty-printed from its tokens.
+    pub(crate) from_source: bool,
+    /// `true` if the source of the matcher parses to other tokens than the ones
+    /// the macro was compiled with, which are shown instead of the source.
+    pub(crate) as_compiled: bool,
+    /// `true` if the matcher has too many tokens to be shown in full, and the
+    /// ones after the first few were left out of `text`.
+    pub(crate) truncated: bool,
+    /// The tokens of the matcher, however `text` shows them. Transcribers are
+    /// not split into tokens, and leave this empty.
+    pub(crate) tokens: Vec<MatcherToken>,
+    /// The metavariables the matcher binds, in order. Empty for transcribers.
+    pub(crate) bindings: Vec<MatcherBinding>,
+    /// The span of the matcher. `DUMMY_SP` for transcribers.
+    pub(crate) span: Span,
+    /// The matcher printed on a single line and cut short, which the arm index
+    /// of a macro with many arms lists it as. `None` for transcribers, and for
+    /// the matchers of macros with too few arms to get an index.
+    pub(crate) summary: Option<String>,
+    /// An example invocation of the macro that the matcher matches, as in
+    /// `m!(NAME, FIELD: TY, /* ... */)`, which is only rendered with
+    /// `--show-macro-invocations`. `None` for transcribers.
+    pub(crate) invocation: Option<String>,
+    /// The matcher printed by `render_macro_matcher_minified`. Empty for
+    /// transcribers.
+    pub(crate) normalized: String,
+}
+
+/// A token of a matcher, with the part it plays in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MatcherToken {
+    pub(crate) kind: MatcherTokenKind,
+    pub(crate) text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MatcherTokenKind {
+    /// A metavariable, as `$x` in `$x:expr`.
+    Metavariable,
+    /// The fragment specifier of a metavariable, as `expr` in `$x:expr`.
+    FragmentSpecifier,
+    /// The `*`, `+` or `?` ending a repetition.
+    RepetitionOperator,
+    /// Any other token, including delimiters, the `$` starting a repetition
+    /// and its separator, and `$crate`.
+    Token,
+}
+
+/// A metavariable bound by a matcher, as `$x:expr`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MatcherBinding {
+    /// The name of the metavariable, without its `$`.
+    pub(crate) name: String,
+    /// The fragment specifier of the metavariable, if it has one.
+    pub(crate) fragment: Option<String>,
+    /// The repetitions the metavariable is nested in, from the outermost one.
+    pub(crate) repetitions: Vec<MatcherRepetition>,
+}
+
+/// A repetition of a matcher, as `$($x:expr),*`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MatcherRepetition {
+    /// The token between the repetitions, as `,` in `$($x:expr),*`.
+    pub(crate) separator: Option<String>,
+    /// `*`, `+` or `?`.
+    pub(crate) operator: String,
+}
+
+impl MatcherToken {
+    fn new(kind: MatcherTokenKind, text: impl Into<String>) -> MatcherToken {
+        MatcherToken { kind, text: text.into() }
+    }
+
+    fn plain(token: &Token) -> MatcherToken {
+        MatcherToken::new(MatcherTokenKind::Token, pprust::token_to_string(token))
+    }
+
+    fn delimiter(kind: TokenKind) -> MatcherToken {
+        MatcherToken::plain(&Token::new(kind, DUMMY_SP))
+    }
+}
+
+impl RenderedMatcher {
+    /// A matcher, or a transcriber, shown as `text`, with none of the details
+    /// only matchers come with.
+    fn new(text: String, from_source: bool) -> RenderedMatcher {
+        RenderedMatcher {
+            text,
+            from_source,
+            as_compiled: false,
+            truncated: false,
+            tokens: Vec::new(),
+            bindings: Vec::new(),
+            span: DUMMY_SP,
+            summary: None,
+            invocation: None,
+            normalized: String::new(),
+        }
+    }
+
+    /// What a matcher, or a transcriber, is documented as when it could not be
+    /// rendered at all.
+    fn unrenderable(part: RulePart) -> RenderedMatcher {
+        let text = match part {
+            RulePart::Matcher => "(/* macro matcher could not be rendered */)",
+            RulePart::Transcriber => "{ /* macro body could not be rendered */ }",
+        };
+        RenderedMatcher::new(text.to_owned(), false)
+    }
+}
+
+/// How matchers are pretty-printed from their tokens.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PrintOptions {
+    /// Number of columns lines are wrapped at.
+    pub(crate) width: usize,
+    pub(crate) style: MacroMatcherStyle,
+    /// Number of token trees, counting delimited groups as one tree on top of
+    /// their contents, after which the rest of the matcher is left out.
+    pub(crate) token_limit: usize,
+    /// Edition of the crate the macro is defined in, which decides whether
+    /// identifiers like `async` or `dyn` are keywords.
+    pub(crate) edition: Edition,
+    pub(crate) part: RulePart,
+    /// If `true`, the matcher is printed on a single line however long it is,
+    /// in the compact style and without its doc comments, and is never cut
+    /// short in the middle of a metavariable.
+    pub(crate) single_line: bool,
+}
+
+impl PrintOptions {
+    fn new(render_options: &RenderOptions, edition: Edition, part: RulePart) -> PrintOptions {
+        PrintOptions {
+            width: render_options.macro_matcher_width,
+            style: render_options.macro_matcher_style,
+            token_limit: render_options.macro_matcher_token_limit,
+            edition,
+            part,
+            single_line: false,
+        }
+    }
+}
+
+/// The part of a macro rule that is rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RulePart {
+    /// The matcher of the rule, as in `($x:expr)`.
+    Matcher,
+    /// What the rule expands to, as in `{ $x + 1 }`, which is only rendered
+    /// with `--document-macro-bodies`.
+    Transcriber,
+}
+
+/// Render the whole declaration of a `macro_rules!` macro, from the matcher and
+/// the transcriber of each of its rules.
+///
+/// `def_id` is the macro, and `edition` the edition of the crate it is defined
+/// in, as for `render_rules`. `export` and `transparency` are recorded as they
+/// were read from its attributes.
+pub(super) fn render_macro_rules(
+    cx: &DocContext<'_>,
+    name: Symbol,
+    arms: &[(TokenTree, TokenTree)],
+    def_id: DefId,
+    edition: Edition,
+    export: MacroExport,
+    transparency: Transparency,
+) -> Macro {
+    let short = render_macro_matcher_short(cx, arms, edition);
+    let (arms, internal_arms) = shown_arms(cx, arms);
+    let (matchers, transcribers) = render_rules(cx, name, &arms, def_id, edition);
+    let prefix = format!("macro_rules! {} {{\n", name);
+    let (rendered_arms, ranges) = render_macro_arms(&matchers, &transcribers, ";", internal_arms);
+    let arms = macro_arms(ranges, prefix.len(), cx.render_options.macro_visible_arms);
+    let source = format!("{prefix}{rendered_arms}}}").into();
+    Macro {
+        source,
+        matchers,
+        arms,
+        short,
+        macro_rules: true,
+        export,
+        transparency,
+        signature: None,
+        generated_by: None,
+    }
+}
+
+/// Render the whole declaration of a macro 2.0, as `render_macro_rules` does
+/// for `macro_rules!` macros. `vis` is the visibility it is declared with,
+/// followed by a space unless it is empty.
+///
+/// A macro with a single rule is shown in the shorthand form, as in
+/// `macro m($x:expr) { ... }`, however it was declared.
+pub(super) fn render_decl_macro(
+    cx: &DocContext<'_>,
+    vis: &str,
+    name: Symbol,
+    arms: &[(TokenTree, TokenTree)],
+    def_id: DefId,
+    edition: Edition,
+    transparency: Transparency,
+) -> Macro {
+    let short = render_macro_matcher_short(cx, arms, edition);
+    let (arms, internal_arms) = shown_arms(cx, arms);
+    let (matchers, transcribers) = render_rules(cx, name, &arms, def_id, edition);
+    let mut arms = ThinVec::new();
+    let source = if matchers.len() <= 1 && internal_arms == 0 {
+        let matcher = matchers.first();
+        let transcriber = transcribers.first();
+        let note = matcher.map_or("", |matcher| arm_note(matcher, transcriber));
+        let body = match transcriber {
+            Some(transcriber) => format!("{}{note}", transcriber.text),
+            None => format!("{{{note}\n    ...\n}}"),
+        };
+        format!("{vis}macro {name}{} {body}", matcher.map_or("", |matcher| &matcher.text[..]))
+    } else {
+        let prefix = format!("{vis}macro {name} {{\n");
+        let (rendered_arms, ranges) =
+            render_macro_arms(&matchers, &transcribers, ",", internal_arms);
+        arms = macro_arms(ranges, prefix.len(), cx.render_options.macro_visible_arms);
+        format!("{prefix}{rendered_arms}}}")
+    };
+    Macro {
+        source: source.into(),
+        matchers,
+        arms,
+        short,
+        macro_rules: false,
+        export: MacroExport::NotExported,
+        transparency,
+        signature: None,
+        generated_by: None,
+    }
+}
+
+/// The macro that generated the macro `def_id`, if any. The matchers of a
+/// generated macro were written in its generator, so their spans point there,
+/// and they are pretty-printed rather than copied from the source.
+pub(super) fn macro_generator(cx: &DocContext<'_>, def_id: DefId) -> Option<DefId> {
+    let expn_data = cx.tcx.def_span(def_id).ctxt().outer_expn_data();
+    match expn_data.kind {
+        ExpnKind::Macro(..) => expn_data.macro_def_id,
+        _ => None,
+    }
+}
+
+/// The signature the macro `def_id` is documented with instead of its
+/// declaration, as given by a `#[doc(macro_signature = "...")]` among `attrs`.
+///
+/// The signature has to lex into balanced token trees. An error is reported
+/// for the ones of local macros that don't, pointing into the attribute, and
+/// the macro is then documented with its declaration as usual.
+pub(super) fn macro_signature(
+    cx: &DocContext<'_>,
+    def_id: DefId,
+    attrs: &[ast::Attribute],
+) -> Option<Symbol> {
+    let meta = attrs
+        .iter()
+        .filter(|attr| attr.has_name(sym::doc))
+        .flat_map(|attr| attr.meta_item_list().unwrap_or_default())
+        .find(|meta| meta.has_name(sym::macro_signature))?;
+    let local = def_id.is_local();
+    let Some(signature) = meta.value_str() else {
+        if local {
+            cx.tcx
+                .sess
+                .struct_span_err(meta.span(), "`macro_signature` expects a string")
+                .help("use `#[doc(macro_signature = \"name!(...)\")]`")
+                .emit();
+        }
+        return None;
+    };
+    let sess = reparse_sess(cx, def_id);
+    let Err(error) = lex_signature(&sess, signature.as_str()) else {
+        return Some(signature);
+    };
+    if local && let Some(lit_span) = meta.name_value_literal_span() {
+        // Only a literal written exactly as its value, without escapes or raw
+        // string hashes, can be pointed into.
+        let source_map = cx.sess().source_map();
+        let written = source_map.span_to_snippet(lit_span).ok();
+        let span = match error {
+            Some(range) if written.as_deref() == Some(&format!("\"{signature}\"")[..]) => {
+                lit_span.from_inner(InnerSpan::new(range.start + 1, range.end + 1))
+            }
+            _ => lit_span,
+        };
+        cx.tcx
+            .sess
+            .struct_span_err(span, "macro signature is not a valid token stream")
+            .span_label(span, "the signature fails to lex here")
+            .note("the macro is documented with its declaration instead")
+            .emit();
+    }
+    None
+}
+
+/// Lexes `signature` in the reparse session. The error is where in it lexing
+/// failed, if the lexer says.
+fn lex_signature(sess: &ParseSess, signature: &str) -> Result<(), Option<Range<usize>>> {
+    reset_reparse_sess(sess);
+    let file_name = reparse_file_name(signature);
+    let lexed = rustc_driver::catch_fatal_errors(|| {
+        match rustc_parse::maybe_new_parser_from_source_str(sess, file_name, signature.to_owned()) {
+            Ok(_) if sess.span_diagnostic.has_errors().is_none() => Ok(()),
+            Ok(_) => Err(None),
+            Err(diagnostics) => {
+                let span =
+                    diagnostics.first().and_then(|diagnostic| diagnostic.span.primary_span());
+                for diagnostic in diagnostics {
+                    DiagnosticBuilder::new_diagnostic(&sess.span_diagnostic, diagnostic).cancel();
+                }
+                Err(span.map(|span| {
+                    let lo = sess.source_map().lookup_byte_offset(span.lo()).pos.to_usize();
+                    let hi = sess.source_map().lookup_byte_offset(span.hi()).pos.to_usize();
+                    lo..hi
+                }))
+            }
+        }
+    });
+    lexed.unwrap_or(Err(None))
+}
+
+/// The arms shown in `ranges` of the arms of a declaration, which start at
+/// `offset` in it. The ones after the first `visible` ranges are hidden.
+fn macro_arms(ranges: Vec<Range<usize>>, offset: usize, visible: usize) -> ThinVec<MacroArm> {
+    let mut shown = 0;
+    let mut previous = None;
+    let mut arms = ThinVec::with_capacity(ranges.len());
+    for range in ranges {
+        if previous.as_ref() != Some(&range) {
+            shown += 1;
+        }
+        let hidden = shown > visible;
+        arms.push(MacroArm { range: range.start + offset..range.end + offset, hidden });
+        previous = Some(range);
+    }
+    arms
+}
+
+/// An arm of a macro, as `--dump-macro-matchers` writes it on a line of its own.
+#[derive(Serialize)]
+pub(crate) struct DumpedMatcher {
+    #[serde(rename = "crate")]
+    krate: String,
+    /// The path of the macro, as in `foo::m`.
+    path: String,
+    /// The index of the arm among the ones shown in the declaration of the
+    /// macro, from 0.
+    arm: usize,
+    matcher: String,
+    /// Whether `matcher` was copied from the source of the arm.
+    from_source: bool,
+}
+
+/// The arms of the macro at `path` in `krate`, rendered as `mac`, to write with
+/// `write_matcher_dump`.
+pub(crate) fn dumped_matchers(krate: Symbol, path: &str, mac: &Macro) -> Vec<DumpedMatcher> {
+    mac.matchers
+        .iter()
+        .enumerate()
+        .map(|(arm, matcher)| DumpedMatcher {
+            krate: krate.to_string(),
+            path: path.to_owned(),
+            arm,
+            matcher: matcher.text.clone(),
+            from_source: matcher.from_source,
+        })
+        .collect()
+}
+
+/// Writes `matchers` to `path`, as one line of JSON each.
+pub(crate) fn write_matcher_dump<'a>(
+    path: &Path,
+    matchers: impl IntoIterator<Item = &'a DumpedMatcher>,
+) -> io::Result<()> {
+    let mut out = io::BufWriter::new(fs::File::create(path)?);
+    for matcher in matchers {
+        serde_json::to_writer(&mut out, matcher)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+/// The arms of a macro to show in its declaration, and how many of its internal
+/// arms were left out of them for `--hide-internal-macro-arms`. The arms of a
+/// macro that only has internal ones are all shown.
+fn shown_arms<'a>(
+    cx: &DocContext<'_>,
+    arms: &'a [(TokenTree, TokenTree)],
+) -> (Vec<&'a (TokenTree, TokenTree)>, usize) {
+    let mut shown: Vec<_> = arms.iter().collect();
+    if cx.render_options.hide_internal_macro_arms {
+        shown.retain(|(matcher, _)| !is_internal_arm(matcher));
+    }
+    if shown.is_empty() {
+        return (arms.iter().collect(), 0);
+    }
+    let internal_arms = arms.len() - shown.len();
+    (shown, internal_arms)
+}
+
+/// Whether the matcher of an arm starts with an `@` followed by an identifier,
+/// as in `(@munch $($rest:tt)*)`, which by convention marks the arm as internal
+/// to the macro, only there for its other arms to recurse into.
+fn is_internal_arm(matcher: &TokenTree) -> bool {
+    let TokenTree::Delimited(_, _, tts) = matcher else { return false };
+    let mut trees = tts.trees();
+    matches!(trees.next(), Some(TokenTree::Token(Token { kind: token::At, .. }, _)))
+        && trees.next().and_then(tree_ident).is_some()
+}
+
+/// Renders the matchers of `arms` of the macro `name`, and their transcribers
+/// if these are to be documented too.
+///
+/// `def_id` is the macro, which lints about its matchers are emitted on, and
+/// `edition` the edition of the crate it is defined in.
+fn render_rules(
+    cx: &DocContext<'_>,
+    name: Symbol,
+    arms: &[&(TokenTree, TokenTree)],
+    def_id: DefId,
+    edition: Edition,
+) -> (ThinVec<RenderedMatcher>, Vec<RenderedMatcher>) {
+    let options = PrintOptions::new(&cx.render_options, edition, RulePart::Matcher);
+    let indexed = arms.len() > cx.render_options.macro_arm_index_threshold;
+    let invocations = cx.render_options.show_macro_invocations;
+    // Looking up the snippets of the matchers and reparsing them takes the
+    // context, so is done one matcher after another. The rest of the work only
+    // takes their tokens, and is done for every matcher in parallel when the
+    // compiler runs on several threads.
+    let snippets: Vec<_> = arms
+        .iter()
+        .map(|&(matcher, _)| {
+            let snippet =
+                catch_render_panic(|| source_rule_part(cx, matcher, def_id, RulePart::Matcher));
+            (matcher, snippet)
+        })
+        .collect();
+    // Every arm printed by a thread is printed with a single printer, which is
+    // only reset in between, rather than one allocating buffers of its own for
+    // each matcher. The arms are only split across printers when there are
+    // several threads to print them.
+    let chunk_len = if is_dyn_thread_safe() { ARMS_PER_PRINTER } else { snippets.len() };
+    let mut snippets = snippets.into_iter().peekable();
+    let chunks: Vec<Vec<_>> = iter::from_fn(|| {
+        snippets.peek()?;
+        Some(snippets.by_ref().take(chunk_len).collect())
+    })
+    .collect();
+    let printed: Vec<Vec<(RenderedMatcher, /* unrenderable */ bool)>> = par_map(chunks, |chunk| {
+        let mut printer = Printer::new();
+        chunk
+            .into_iter()
+            .map(|(matcher, snippet)| {
+                let (mut rendered, unrenderable) =
+                    render_macro_matcher(&mut printer, matcher, snippet, options);
+                if indexed {
+                    rendered.summary = Some(
+                        catch_render_panic(|| summarize_matcher(&mut printer, matcher, options))
+                            .unwrap_or_else(|| rendered.text.clone()),
+                    );
+                }
+                if invocations {
+                    rendered.invocation = catch_render_panic(|| {
+                        print_invocation(&mut printer, name, matcher, options)
+                    });
+                }
+                (rendered, unrenderable)
+            })
+            .collect()
+    });
+    // The warnings are emitted in the order of the arms, whichever thread
+    // printed them.
+    let matchers = printed
+        .into_iter()
+        .flatten()
+        .map(|(rendered, unrenderable)| {
+            if unrenderable {
+                warn_unrenderable(cx, def_id, RulePart::Matcher);
+            }
+            rendered
+        })
+        .collect();
+    let transcribers = if cx.render_options.document_macro_bodies {
+        arms.iter()
+            .map(|(_, transcriber)| render_macro_transcriber(cx, transcriber, def_id, edition))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    (matchers, transcribers)
+}
+
+/// Number of arms each printer prints when `render_rules` prints the arms of a
+/// macro on several threads.
+const ARMS_PER_PRINTER: usize = 64;
 
 /// Render a macro matcher in a format suitable for displaying to the user
+/// as part of an item declaration: as the `snippet` `source_rule_part` found
+/// for it, or printed with `printer` otherwise. Also returns whether it could
+/// be rendered at all, which is for the caller to warn about, as rendering it
+/// doesn't take the context and may happen on any thread.
+pub(super) fn render_macro_matcher(
+    printer: &mut Printer<'_>,
+    matcher: &TokenTree,
+    snippet: Option<Result<RenderedMatcher, /* as_compiled */ bool>>,
+    options: PrintOptions,
+) -> (RenderedMatcher, /* unrenderable */ bool) {
+    let rendered = match snippet {
+        Some(Ok(rendered)) => Some(rendered),
+        Some(Err(as_compiled)) => catch_render_panic(|| RenderedMatcher {
+            as_compiled,
+            ..print_matcher(printer, matcher, options)
+        }),
+        None => None,
+    };
+    let unrenderable = rendered.is_none();
+    let (tokens, bindings) = split_matcher(matcher);
+    let rendered = RenderedMatcher {
+        tokens,
+        bindings,
+        span: matcher.span(),
+        normalized: render_macro_matcher_minified(matcher),
+        ..rendered.unwrap_or_else(|| RenderedMatcher::unrenderable(RulePart::Matcher))
+    };
+    (rendered, unrenderable)
+}
+
+/// Render a sequence of macro arms in a format suitable for displaying to the user
 /// as part of an item declaration.
-pub(super) fn render_macro_matcher(tcx: TyCtxt<'_>, matcher: &TokenTree) -> String {
-    if let Some(snippet) = snippet_equal_to_token(tcx, matcher) {
+///
+/// `transcribers` are the rendered right-hand sides of the arms, if any, which
+/// are elided as `{ ... }` otherwise. Consecutive arms that render the same,
+/// as happens to arms only differing in their elided transcribers, are shown
+/// once, followed by how many of them there are.
+///
+/// `internal_arms` is how many internal arms of the macro were left out of
+/// `matchers`, which a comment after the arms tells.
+///
+/// Also returns the range of the output each arm is shown in, which arms shown
+/// once share.
+fn render_macro_arms(
+    matchers: &[RenderedMatcher],
+    transcribers: &[RenderedMatcher],
+    arm_delim: &str,
+    internal_arms: usize,
+) -> (String, Vec<Range<usize>>) {
+    let mut out = String::new();
+    let mut ranges = Vec::with_capacity(matchers.len());
+    let transcribers = transcribers.iter().map(Some).chain(iter::repeat(None));
+    let mut arms = matchers.iter().zip(transcribers).peekable();
+    while let Some((matcher, transcriber)) = arms.next() {
+        let body = transcriber.map_or("{ ... }", |transcriber| &transcriber.text[..]);
+        let mut identical = 1;
+        while arms
+            .next_if(|(next_matcher, next_transcriber)| {
+                next_matcher.text == matcher.text
+                    && next_transcriber.map(|t| &t.text) == transcriber.map(|t| &t.text)
+            })
+            .is_some()
+        {
+            identical += 1;
+        }
+        out.push_str(RENDERED_ARM_INDENT);
+        let start = out.len();
+        write!(out, "{} => {}{}", matcher.text, body, arm_delim).unwrap();
+        if identical > 1 {
+            write!(out, " /* {identical} identical arms */").unwrap();
+        }
+        out.push_str(arm_note(matcher, transcriber));
+        ranges.extend(iter::repeat(start..out.len()).take(identical));
+        out.push('\n');
+    }
+    if internal_arms > 0 {
+        let s = if internal_arms == 1 { "" } else { "s" };
+        writeln!(out, "{RENDERED_ARM_INDENT}/* {internal_arms} internal arm{s} hidden */").unwrap();
+    }
+    (out, ranges)
+}
+
+/// A comment to add after the arm of a matcher that is not shown as written,
+/// telling that the one shown is the one the macro was compiled with, or that
+/// it is only the start of it. The transcriber shown after it, if any, counts
+/// as part of the matcher.
+fn arm_note(matcher: &RenderedMatcher, transcriber: Option<&RenderedMatcher>) -> &'static str {
+    let as_compiled = matcher.as_compiled || transcriber.map_or(false, |t| t.as_compiled);
+    let truncated = matcher.truncated || transcriber.map_or(false, |t| t.truncated);
+    match (as_compiled, truncated) {
+        (false, false) => "",
+        (true, false) => " // as compiled",
+        (false, true) => " // truncated",
+        (true, true) => " // as compiled, truncated",
+    }
+}
+
+/// Render the transcriber of a macro rule the way `render_macro_matcher`
+/// renders its matcher.
+pub(super) fn render_macro_transcriber(
+    cx: &DocContext<'_>,
+    transcriber: &TokenTree,
+    def_id: DefId,
+    edition: Edition,
+) -> RenderedMatcher {
+    let part = RulePart::Transcriber;
+    // A transcriber the printer can't cope with should cost its macro the
+    // transcriber, not the whole crate its documentation.
+    catch_render_panic(|| {
+        let options = PrintOptions::new(&cx.render_options, edition, part);
+        match source_rule_part(cx, transcriber, def_id, part) {
+            Ok(rendered) => rendered,
+            Err(as_compiled) => {
+                let printed = print_matcher(&mut Printer::new(), transcriber, options);
+                RenderedMatcher { as_compiled, ..printed }
+            }
+        }
+    })
+    .unwrap_or_else(|| warn_unrenderable(cx, def_id, part))
+}
+
+/// Warns that a part of a rule of the macro `def_id` could not be rendered, and
+/// returns the placeholder it is documented as instead.
+fn warn_unrenderable(cx: &DocContext<'_>, def_id: DefId, part: RulePart) -> RenderedMatcher {
+    let msg = match part {
+        RulePart::Matcher => "macro matcher could not be rendered",
+        RulePart::Transcriber => "macro body could not be rendered",
+    };
+    cx.tcx
+        .sess
+        .struct_span_warn(cx.tcx.def_span(def_id), msg)
+        .note("it is documented as a placeholder instead")
+        .emit();
+    RenderedMatcher::unrenderable(part)
+}
+
+/// Runs `render`, or returns `None` if it panicked. Fatal errors are not
+/// panics of the printer, and keep unwinding.
+fn catch_render_panic<T>(render: impl FnOnce() -> T) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(render)) {
+        Ok(rendered) => Some(rendered),
+        Err(payload) if payload.is::<FatalErrorMarker>() => panic::resume_unwind(payload),
+        Err(_) => None,
+    }
+}
+
+/// A part of a rule as it is written in the source. If the matcher is
+/// macro-generated or some other reason the source code snippet is not
+/// available, it is to be nicely rendered from its token trees instead, and
+/// this returns whether these are shown as compiled.
+fn source_rule_part(
+    cx: &DocContext<'_>,
+    tree: &TokenTree,
+    def_id: DefId,
+    part: RulePart,
+) -> Result<RenderedMatcher, /* as_compiled */ bool> {
+    // The span of a macro-generated matcher points into the macro that generated
+    // it, whose source is not the matcher we want to show, so don't even try to
+    // reparse its snippet.
+    let span = tree.span();
+    if cx.render_options.normalize_macro_matchers
+        || cx.render_options.macro_matcher_style != MacroMatcherStyle::Compact
+        || span.from_expansion()
+    {
+        return Err(false);
+    }
+    match snippet_equal_to_token(cx, tree, def_id) {
         // If the original source code is known, we display the matcher exactly
-        // as present in the source code.
-        return snippet;
+        // as present in the source code, up to whitespace.
+        Ok(snippet) => {
+            let text = normalize_snippet(cx, span, &snippet);
+            Ok(RenderedMatcher::new(text, true))
+        }
+        // There is nowhere to point a lint at for matchers made up by the
+        // compiler.
+        Err(_) if span.is_dummy() => Err(false),
+        Err(mismatch) => {
+            let as_compiled = matches!(mismatch, SnippetMismatch::TreeMismatch);
+            // The lint is about matchers, the interface of the macro.
+            if part == RulePart::Matcher {
+                report_reformatted_matcher(cx, def_id, span, mismatch);
+            }
+            Err(as_compiled)
+        }
+    }
+}
+
+/// Pretty-prints the tokens of a matcher, wrapped in the delimiters it was
+/// declared with, as in `[$x:expr]`. An empty matcher is printed as `()`, `[]`
+/// or `{}`.
+fn print_matcher(
+    printer: &mut Printer<'_>,
+    matcher: &TokenTree,
+    options: PrintOptions,
+) -> RenderedMatcher {
+    match matcher {
+        TokenTree::Delimited(_span, delim, tts) => {
+            print_delimited_matcher(printer, *delim, tts, options)
+        }
+        // Matcher which is not a Delimited is unexpected and should've failed
+        // to compile, but we render whatever it is wrapped in parens.
+        TokenTree::Token(..) => {
+            let tts = TokenStream::new(vec![matcher.clone()]);
+            print_delimited_matcher(printer, Delimiter::Parenthesis, &tts, options)
+        }
+    }
+}
+
+/// Number of characters the summary of a matcher takes up at most.
+const MATCHER_SUMMARY_LEN: usize = 80;
+
+/// Prints a matcher on a single line, with as many of its tokens as fit in
+/// `MATCHER_SUMMARY_LEN` characters, as the arm index of a macro lists it.
+fn summarize_matcher(
+    printer: &mut Printer<'_>,
+    matcher: &TokenTree,
+    options: PrintOptions,
+) -> String {
+    let options = PrintOptions { single_line: true, ..options };
+    fit_matcher(printer, matcher, options, MATCHER_SUMMARY_LEN)
+}
+
+/// Prints as many of the tokens of a matcher as fit in `len` characters.
+fn fit_matcher(
+    printer: &mut Printer<'_>,
+    matcher: &TokenTree,
+    options: PrintOptions,
+    len: usize,
+) -> String {
+    let mut print =
+        |token_limit| print_matcher(printer, matcher, PrintOptions { token_limit, ..options }).text;
+    let full = print(options.token_limit);
+    if full.len() <= len {
+        return full;
     }
+    // Every tree takes up at least a character, and printing more of them never
+    // makes the matcher shorter, so the most trees that fit are looked for in
+    // between none of them and as many as there are characters.
+    let (mut fits, mut too_many) = (0, options.token_limit.min(full.len()));
+    let mut summary = print(0);
+    while too_many - fits > 1 {
+        let limit = fits + (too_many - fits) / 2;
+        let text = print(limit);
+        if text.len() <= len {
+            fits = limit;
+            summary = text;
+        } else {
+            too_many = limit;
+        }
+    }
+    summary
+}
+
+/// The matcher of the first arm of a macro that isn't internal to it, printed
+/// on a single line by `print_short_matcher`, as module pages and search
+/// results show the macro. This is empty for a macro with no arms, or one whose
+/// matcher can't be printed.
+fn render_macro_matcher_short(
+    cx: &DocContext<'_>,
+    arms: &[(TokenTree, TokenTree)],
+    edition: Edition,
+) -> Box<str> {
+    let options = PrintOptions::new(&cx.render_options, edition, RulePart::Matcher);
+    catch_render_panic(|| print_short_matcher(arms, options)).unwrap_or_default().into()
+}
+
+/// Number of characters the short form of a macro takes up at most.
+const MATCHER_SHORT_LEN: usize = 60;
 
-    // If the matcher is macro-generated or some other reason the source code
-    // snippet is not available, we attempt to nicely render the token tree.
+/// Prints the matcher of the first arm of `arms` that isn't internal, or of the
+/// first one if they all are, on a single line of at most `MATCHER_SHORT_LEN`
+/// characters.
+///
+/// The contents of the repetitions of a matcher too long for it are elided, as
+/// in `$(…),*`, one after another until it fits. A matcher that is still too
+/// long is then cut short as by `summarize_matcher`.
+fn print_short_matcher(arms: &[(TokenTree, TokenTree)], options: PrintOptions) -> String {
+    let first = arms.iter().find(|(matcher, _)| !is_internal_arm(matcher)).or(arms.first());
+    let Some((matcher, _)) = first else { return String::new() };
+    let options = PrintOptions { single_line: true, ..options };
     let mut printer = Printer::new();
+    let mut short = matcher.clone();
+    let mut elided = 0;
+    while print_matcher(&mut printer, &short, options).text.len() > MATCHER_SHORT_LEN
+        && let Some(fewer) = elide_repetitions(matcher, elided + 1)
+    {
+        short = fewer;
+        elided += 1;
+    }
+    // Literals are printed as they were written, which for a string literal
+    // may span several lines.
+    fit_matcher(&mut printer, &short, options, MATCHER_SHORT_LEN).replace('\n', " ")
+}
+
+/// `matcher` with the contents of its first `count` repetitions that aren't in
+/// another one replaced with a `…`, as in `$(…),*`, or `None` if it has fewer
+/// of them than that.
+fn elide_repetitions(matcher: &TokenTree, mut count: usize) -> Option<TokenTree> {
+    struct Group<'a> {
+        trees: RefTokenTreeCursor<'a>,
+        span: DelimSpan,
+        delim: Delimiter,
+        out: Vec<TokenTree>,
+    }
+
+    let TokenTree::Delimited(span, delim, tts) = matcher else { return None };
+    // The groups are kept on a stack of their own, innermost last, as in
+    // `print_tts`.
+    let mut groups =
+        vec![Group { trees: tts.trees(), span: *span, delim: *delim, out: Vec::new() }];
+    loop {
+        let group = groups.last_mut().unwrap();
+        let Some(tree) = group.trees.next() else {
+            let Group { span, delim, out, .. } = groups.pop().unwrap();
+            let tree = TokenTree::Delimited(span, delim, TokenStream::new(out));
+            match groups.last_mut() {
+                Some(outer) => outer.out.push(tree),
+                None => return (count == 0).then_some(tree),
+            }
+            continue;
+        };
+        let dollar = matches!(tree, TokenTree::Token(Token { kind: token::Dollar, .. }, _));
+        if dollar
+            && count > 0
+            && let Some(TokenTree::Delimited(span, Delimiter::Parenthesis, _)) =
+                group.trees.clone().next()
+        {
+            group.trees.next();
+            count -= 1;
+            let ellipsis = token::Ident(Symbol::intern("…"), /* is_raw */ false);
+            let contents = TokenStream::new(vec![TokenTree::token_alone(ellipsis, DUMMY_SP)]);
+            group.out.push(tree.clone());
+            group.out.push(TokenTree::Delimited(*span, Delimiter::Parenthesis, contents));
+        } else if let TokenTree::Delimited(span, delim, inner) = tree {
+            let (span, delim) = (*span, *delim);
+            groups.push(Group { trees: inner.trees(), span, delim, out: Vec::new() });
+        } else {
+            group.out.push(tree.clone());
+        }
+    }
+}
+
+/// Prints an example invocation of the macro `name` that `matcher` matches,
+/// wrapped in the delimiters of the matcher, as in `m!(NAME, FIELD: TY, /* ... */)`
+/// for `($name:ident, $($field:ident: $ty:ty),*)` or `m! { ... }` for a matcher
+/// in braces.
+fn print_invocation(
+    printer: &mut Printer<'_>,
+    name: Symbol,
+    matcher: &TokenTree,
+    options: PrintOptions,
+) -> String {
+    let invocation = invocation_tokens(matcher);
+    let space =
+        if let TokenTree::Delimited(_, Delimiter::Brace, _) = invocation { " " } else { "" };
+    let options = PrintOptions { style: MacroMatcherStyle::Compact, ..options };
+    format!("{name}!{space}{}", print_matcher(printer, &invocation, options).text)
+}
+
+/// The tokens of an invocation of the macro an arm with `matcher` matches.
+///
+/// Each metavariable is replaced with its name in upper case, as in `NAME` for
+/// `$name:ident`, and each repetition that can be repeated with one of its
+/// elements followed by a `/* ... */`, as in `FIELD: TY, /* ... */`. Optional
+/// repetitions are left out.
+fn invocation_tokens(matcher: &TokenTree) -> TokenTree {
+    enum GroupKind {
+        Delimited(DelimSpan, Delimiter),
+        Repetition { separator: Option<TokenTree> },
+    }
+
+    struct Group<'a> {
+        trees: RefTokenTreeCursor<'a>,
+        kind: GroupKind,
+        out: Vec<TokenTree>,
+    }
+
+    let TokenTree::Delimited(span, delim, tts) = matcher else { return matcher.clone() };
+    // The groups are kept on a stack of their own, innermost last, as in
+    // `print_tts`.
+    let kind = GroupKind::Delimited(*span, *delim);
+    let mut groups = vec![Group { trees: tts.trees(), kind, out: Vec::new() }];
+    loop {
+        let group = groups.last_mut().unwrap();
+        let Some(tree) = group.trees.next() else {
+            let Group { kind, mut out, .. } = groups.pop().unwrap();
+            match kind {
+                GroupKind::Delimited(span, delim) => {
+                    let tree = TokenTree::Delimited(span, delim, TokenStream::new(out));
+                    match groups.last_mut() {
+                        Some(outer) => outer.out.push(tree),
+                        None => return tree,
+                    }
+                }
+                GroupKind::Repetition { separator } => {
+                    out.extend(separator);
+                    let placeholder = token::Ident(Symbol::intern("/* ... */"), false);
+                    out.push(TokenTree::token_alone(placeholder, DUMMY_SP));
+                    // A repetition is never the outermost group.
+                    groups.last_mut().unwrap().out.extend(out);
+                }
+            }
+            continue;
+        };
+        let next = group.trees.clone().next();
+        let dollar = matches!(tree, TokenTree::Token(Token { kind: token::Dollar, .. }, _));
+        if dollar && let Some(TokenTree::Delimited(_, Delimiter::Parenthesis, inner)) = next {
+            group.trees.next();
+            let mut operator = group.trees.next();
+            let mut separator = None;
+            if !is_repetition_operator(operator) {
+                separator = operator.cloned();
+                operator = group.trees.next();
+            }
+            let optional =
+                matches!(operator, Some(TokenTree::Token(Token { kind: token::Question, .. }, _)));
+            if !optional {
+                let kind = GroupKind::Repetition { separator };
+                groups.push(Group { trees: inner.trees(), kind, out: Vec::new() });
+            }
+        } else if dollar && let Some((name, _)) = next.and_then(tree_ident) {
+            group.trees.next();
+            // The fragment specifier, if any.
+            let mut fragment = group.trees.clone();
+            if let Some(TokenTree::Token(Token { kind: token::Colon, .. }, _)) = fragment.next()
+                && fragment.next().and_then(tree_ident).is_some()
+            {
+                group.trees = fragment;
+            }
+            let placeholder = Symbol::intern(&name.as_str().to_uppercase());
+            let token = token::Ident(placeholder, /* is_raw */ false);
+            group.out.push(TokenTree::token_alone(token, name.span));
+        } else if let TokenTree::Delimited(span, delim, inner) = tree {
+            let kind = GroupKind::Delimited(*span, *delim);
+            groups.push(Group { trees: inner.trees(), kind, out: Vec::new() });
+        } else {
+            group.out.push(tree.clone());
+        }
+    }
+}
+
+/// Prints a matcher in a canonical form, which tools comparing matchers, as
+/// across versions of a crate, can tell them apart by. `render_rules` records
+/// it along with the form the matcher is documented in.
+///
+/// Unlike `print_matcher`, this takes no options. The tokens are printed on a
+/// single line, with a space between two of them only where they would be
+/// lexed as other tokens without one, as in `a ::b` or `< <`. Doc comments are
+/// printed as the `#[doc = "..."]` attributes they stand for, and literals as
+/// `normalize_lit` writes them.
+pub(crate) fn render_macro_matcher_minified(matcher: &TokenTree) -> String {
+    let mut out = String::new();
+    // The last token printed, and where its text starts.
+    let mut last: Option<(TokenKind, usize)> = None;
+    for kind in minified_tokens(matcher) {
+        let text = pprust::token_kind_to_string(&kind);
+        if let Some((last_kind, start)) = &last
+            && needs_separator(last_kind, &out[*start..], &kind, &text)
+        {
+            out.push(' ');
+        }
+        let start = out.len();
+        out.push_str(&text);
+        last = Some((kind, start));
+    }
+    out
+}
+
+/// The tokens `render_macro_matcher_minified` prints a matcher as, from its
+/// opening delimiter to its closing one. Fragments spliced in by an outer macro
+/// are replaced with their tokens, and invisible groups with their contents.
+fn minified_tokens(matcher: &TokenTree) -> Vec<TokenKind> {
+    fn push_tree(tree: &TokenTree, out: &mut Vec<TokenKind>) {
+        match tree {
+            &TokenTree::Token(Token { kind: token::DocComment(_, attr_style, data), .. }, _) => {
+                out.push(token::Pound);
+                if attr_style == AttrStyle::Inner {
+                    out.push(token::Not);
+                }
+                let doc = token::Lit::new(token::Str, escape_string_symbol(data), None);
+                out.extend([
+                    token::OpenDelim(Delimiter::Bracket),
+                    token::Ident(sym::doc, false),
+                    token::Eq,
+                    token::Literal(doc),
+                    token::CloseDelim(Delimiter::Bracket),
+                ]);
+            }
+            &TokenTree::Token(Token { kind: token::Literal(lit), .. }, _) => {
+                out.push(token::Literal(normalize_lit(lit)));
+            }
+            TokenTree::Token(token, _) => out.push(token.kind.clone()),
+            TokenTree::Delimited(_, Delimiter::Invisible, tts) => {
+                tts.trees().for_each(|tree| push_tree(tree, out));
+            }
+            TokenTree::Delimited(_, delim, tts) => {
+                out.push(token::OpenDelim(*delim));
+                tts.trees().for_each(|tree| push_tree(tree, out));
+                out.push(token::CloseDelim(*delim));
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    for tree in TokenStream::new(vec![matcher.clone()]).flattened().trees() {
+        push_tree(tree, &mut out);
+    }
+    out
+}
+
+/// A literal of the same value as `lit`, written in a way that only depends on
+/// that value: strings, byte strings and characters escaped as `escape_default`
+/// escapes them, even raw ones, and numbers without underscores, integers in
+/// decimal. Literals that are not well-formed are left as they are.
+fn normalize_lit(lit: token::Lit) -> token::Lit {
+    let (kind, symbol) = match ast::LitKind::from_token_lit(lit) {
+        Ok(ast::LitKind::Str(symbol, _)) => (token::Str, escape_string_symbol(symbol)),
+        Ok(ast::LitKind::ByteStr(bytes, _)) => (token::ByteStr, escape_byte_str_symbol(&bytes)),
+        Ok(ast::LitKind::Byte(byte)) => (token::Byte, escape_byte_str_symbol(&[byte])),
+        Ok(ast::LitKind::Char(ch)) => (token::Char, escape_char_symbol(ch)),
+        Ok(ast::LitKind::Int(value, _)) => (token::Integer, Symbol::intern(&value.to_string())),
+        // An integer with a float suffix, as in `1f32`, is still lexed as an
+        // integer.
+        Ok(ast::LitKind::Float(symbol, _)) => (lit.kind, symbol),
+        _ => return lit,
+    };
+    token::Lit::new(kind, symbol, lit.suffix)
+}
+
+/// Whether the tokens `first` and `second`, printed as `first_text` and
+/// `second_text` right one after the other, would be lexed as other tokens.
+fn needs_separator(
+    first: &TokenKind,
+    first_text: &str,
+    second: &TokenKind,
+    second_text: &str,
+) -> bool {
+    // The lexer splits punctuation into single characters, which the parser then
+    // glues back together one after another, as `<` and `<` into `<<`.
+    let mut second_start = second.clone();
+    while let Some((start, _)) = second_start.break_two_token_op() {
+        second_start = start;
+    }
+    let first = Token::new(first.clone(), DUMMY_SP);
+    if first.glue(&Token::new(second_start, DUMMY_SP)).is_some() {
+        return true;
+    }
+    let lex = |text: &str| {
+        rustc_lexer::tokenize(text).map(|token| (token.kind, token.len)).collect::<Vec<_>>()
+    };
+    let mut apart = lex(first_text);
+    apart.extend(lex(second_text));
+    lex(&format!("{first_text}{second_text}")) != apart
+}
+
+/// Number of columns one level of indentation takes up in rendered macros.
+const INDENT_UNIT: isize = 4;
+
+/// Pretty-prints the tokens inside of a matcher, wrapped in parentheses. This
+/// only depends on the tokens themselves, never on the source they come from.
+pub(crate) fn print_matcher_tokens(tts: &TokenStream, options: PrintOptions) -> RenderedMatcher {
+    print_delimited_matcher(&mut Printer::new(), Delimiter::Parenthesis, tts, options)
+}
+
+/// Pretty-prints the tokens inside of a matcher, wrapped in `delim`. Like the
+/// ones of a block, braces are separated from the tokens by spaces, as in
+/// `{ $x:expr }`.
+///
+/// `printer` is reset first, so that a single printer can print every matcher
+/// of a macro without allocating buffers of its own for each of them.
+fn print_delimited_matcher(
+    printer: &mut Printer<'_>,
+    delim: Delimiter,
+    tts: &TokenStream,
+    options: PrintOptions,
+) -> RenderedMatcher {
+    let (open, close) = match delim {
+        Delimiter::Bracket => ("[", "]"),
+        Delimiter::Brace => ("{", "}"),
+        // An invisible group could not even be told apart from its contents.
+        Delimiter::Parenthesis | Delimiter::Invisible => ("(", ")"),
+    };
+    let padding = if delim == Delimiter::Brace && !tts.is_empty() { 1 } else { 0 };
+    let width = if options.single_line { usize::MAX } else { options.width };
+    printer.s.reset(width.try_into().unwrap_or(isize::MAX));
 
     // If the inner ibox fits on one line, we get:
     //
@@ -35,163 +1150,1644 @@ pub(super) fn render_macro_matcher(tcx: TyCtxt<'_>, matcher: &TokenTree) -> Stri
     //             ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~!
     //         ) => {...};
     //     }
-    printer.cbox(8);
-    printer.word("(");
-    printer.zerobreak();
+    //
+    // The first line is indented by the declaration, but the others aren't, so
+    // the contents of the matcher get indented two levels, and its closing
+    // parenthesis one level, like the arm it starts. Whatever boxes `print_tts`
+    // opens are closed by then, so the closing parenthesis is only relative to
+    // this cbox, however deep the matcher got wrapped.
+    printer.cbox(2 * INDENT_UNIT);
+    printer.word(open);
+    printer.break_offset(padding, 0);
     printer.ibox(0);
-    match matcher {
-        TokenTree::Delimited(_span, _delim, tts) => print_tts(&mut printer, tts),
-        // Matcher which is not a Delimited is unexpected and should've failed
-        // to compile, but we render whatever it is wrapped in parens.
-        TokenTree::Token(..) => print_tt(&mut printer, matcher),
-    }
+    let truncated = print_tts(printer, tts, options);
     printer.end();
-    printer.break_offset_if_not_bol(0, -4);
-    printer.word(")");
+    // A doc comment at the end of the matcher still needs the line break that
+    // keeps the closing parenthesis out of it.
+    let last_tree = tts.trees().last();
+    if let Some(TokenTree::Token(Token { kind: token::DocComment(..), .. }, _)) = last_tree
+        && !options.single_line
+    {
+        printer.hardbreak();
+    }
+    printer.break_offset_if_not_bol(padding, -INDENT_UNIT);
+    printer.word(close);
     printer.end();
-    printer.s.eof()
+    let text = trim_trailing_whitespace(printer.s.finish());
+    RenderedMatcher { truncated, ..RenderedMatcher::new(text, false) }
+}
+
+/// Splits a matcher into its tokens, from its opening delimiter to its closing
+/// one, telling metavariables, fragment specifiers and repetition operators
+/// apart from the tokens the macro matches as they are. Invisible groups are
+/// left out, but not their contents.
+///
+/// Also returns the metavariables the matcher binds, in order.
+pub(crate) fn split_matcher(matcher: &TokenTree) -> (Vec<MatcherToken>, Vec<MatcherBinding>) {
+    struct Group<'a> {
+        trees: RefTokenTreeCursor<'a>,
+        delim: Delimiter,
+        /// For the group of a repetition, as in `$($x:expr),*`, its index in
+        /// `repetitions`.
+        repetition: Option<usize>,
+    }
+
+    let wrapped;
+    let (delim, tts) = match matcher {
+        // Shown as parentheses, like `print_matcher` does.
+        TokenTree::Delimited(_, Delimiter::Invisible, tts) => (Delimiter::Parenthesis, tts),
+        TokenTree::Delimited(_, delim, tts) => (*delim, tts),
+        TokenTree::Token(..) => {
+            wrapped = TokenStream::new(vec![matcher.clone()]);
+            (Delimiter::Parenthesis, &wrapped)
+        }
+    };
+    let mut tokens = vec![MatcherToken::delimiter(token::OpenDelim(delim))];
+    // The repetitions of the matcher, known once their operator is reached,
+    // and the metavariables with the indices of the repetitions they are in.
+    let mut repetitions: Vec<Option<MatcherRepetition>> = Vec::new();
+    let mut bindings: Vec<(String, Option<String>, Vec<usize>)> = Vec::new();
+    // Walk the groups without recursion, like `print_tts` does, for matchers
+    // nested deeper than the stack would allow.
+    let mut groups = vec![Group { trees: tts.trees(), delim, repetition: None }];
+    while let Some(group) = groups.last_mut() {
+        let Some(tree) = group.trees.next() else {
+            let group = groups.pop().unwrap();
+            if group.delim != Delimiter::Invisible {
+                tokens.push(MatcherToken::delimiter(token::CloseDelim(group.delim)));
+            }
+            // The separator and operator of a repetition follow its group.
+            if let Some(index) = group.repetition && let Some(parent) = groups.last_mut() {
+                let trees = &mut parent.trees;
+                let mut separator = None;
+                let next = trees.clone().next();
+                if let Some(TokenTree::Token(token, _)) = next
+                    && !is_repetition_operator(next)
+                    && is_repetition_operator(trees.look_ahead(1))
+                {
+                    trees.next();
+                    let token = MatcherToken::plain(token);
+                    separator = Some(token.text.clone());
+                    tokens.push(token);
+                }
+                let next = trees.clone().next();
+                if let Some(TokenTree::Token(operator, _)) = next
+                    && is_repetition_operator(next)
+                {
+                    trees.next();
+                    let operator = pprust::token_to_string(operator).into_owned();
+                    tokens.push(MatcherToken::new(
+                        MatcherTokenKind::RepetitionOperator,
+                        operator.clone(),
+                    ));
+                    repetitions[index] = Some(MatcherRepetition { separator, operator });
+                }
+            }
+            continue;
+        };
+        let token = match tree {
+            TokenTree::Token(token, _) => token,
+            TokenTree::Delimited(_, delim, tts) => {
+                if *delim != Delimiter::Invisible {
+                    tokens.push(MatcherToken::delimiter(token::OpenDelim(*delim)));
+                }
+                groups.push(Group { trees: tts.trees(), delim: *delim, repetition: None });
+                continue;
+            }
+        };
+        if token.kind != token::Dollar {
+            tokens.push(MatcherToken::plain(&token.uninterpolate()));
+            continue;
+        }
+
+        let trees = &mut group.trees;
+        let next = trees.clone().next();
+        if let Some(TokenTree::Delimited(_, Delimiter::Parenthesis, tts)) = next {
+            trees.next();
+            tokens.push(MatcherToken::plain(token));
+            tokens.push(MatcherToken::delimiter(token::OpenDelim(Delimiter::Parenthesis)));
+            groups.push(Group {
+                trees: tts.trees(),
+                delim: Delimiter::Parenthesis,
+                repetition: Some(repetitions.len()),
+            });
+            repetitions.push(None);
+            continue;
+        }
+        let Some((name, is_raw)) = next.and_then(tree_ident) else {
+            tokens.push(MatcherToken::plain(token));
+            continue;
+        };
+        trees.next();
+        if name.name == kw::Crate && !is_raw {
+            tokens.push(MatcherToken::new(MatcherTokenKind::Token, "$crate"));
+            continue;
+        }
+        let name = IdentPrinter::new(name.name, is_raw, None).to_string();
+        tokens.push(MatcherToken::new(MatcherTokenKind::Metavariable, format!("${name}")));
+        let colon = matches!(
+            trees.look_ahead(0),
+            Some(TokenTree::Token(Token { kind: token::Colon, .. }, _))
+        );
+        let mut fragment = None;
+        if colon && let Some((specifier, _)) = trees.look_ahead(1).and_then(tree_ident) {
+            trees.next();
+            trees.next();
+            tokens.push(MatcherToken::new(MatcherTokenKind::Token, ":"));
+            tokens.push(MatcherToken::new(MatcherTokenKind::FragmentSpecifier, specifier.as_str()));
+            fragment = Some(specifier.name.to_string());
+        }
+        let repetitions_in = groups.iter().filter_map(|group| group.repetition).collect();
+        bindings.push((name, fragment, repetitions_in));
+    }
+
+    let bindings = bindings
+        .into_iter()
+        .map(|(name, fragment, indices)| MatcherBinding {
+            name,
+            fragment,
+            repetitions: indices.into_iter().filter_map(|i| repetitions[i].clone()).collect(),
+        })
+        .collect();
+    (tokens, bindings)
+}
+
+fn tree_ident(tree: &TokenTree) -> Option<(Ident, /* is_raw */ bool)> {
+    match tree {
+        TokenTree::Token(token, _) => token.ident(),
+        TokenTree::Delimited(..) => None,
+    }
+}
+
+fn is_repetition_operator(tree: Option<&TokenTree>) -> bool {
+    matches!(
+        tree,
+        Some(TokenTree::Token(
+            Token { kind: token::BinOp(BinOpToken::Plus | BinOpToken::Star) | token::Question, .. },
+            _,
+        ))
+    )
+}
+
+/// Why the source of a matcher is not displayed, reported by the
+/// `reformatted_macro_matchers` lint.
+#[derive(Clone, Copy, Debug)]
+enum SnippetMismatch {
+    /// The source of the matcher is not available.
+    Unavailable,
+    /// The span of the matcher starts and ends in different files.
+    CrossesFiles,
+    /// The source is larger than `--macro-matcher-snippet-limit`.
+    TooLarge,
+    /// The source does not parse into a single token tree.
+    ParseError,
+    /// The source parses into other tokens than the ones of the matcher.
+    TreeMismatch,
+}
+
+impl SnippetMismatch {
+    fn note(self) -> &'static str {
+        match self {
+            SnippetMismatch::Unavailable => "the source of the matcher is not available",
+            SnippetMismatch::CrossesFiles => "the span of the matcher spreads over several files",
+            SnippetMismatch::TooLarge => {
+                "the source of the matcher is larger than `--macro-matcher-snippet-limit`"
+            }
+            SnippetMismatch::ParseError => "the source of the matcher could not be parsed",
+            SnippetMismatch::TreeMismatch => {
+                "the source of the matcher does not have the same tokens as the matcher"
+            }
+        }
+    }
+}
+
+fn report_reformatted_matcher(
+    cx: &DocContext<'_>,
+    def_id: DefId,
+    span: Span,
+    mismatch: SnippetMismatch,
+) {
+    let Some(hir_id) = DocContext::as_local_hir_id(cx.tcx, ItemId::DefId(def_id)) else {
+        // Matchers of macros from other crates can't be fixed from here anyway.
+        return;
+    };
+    let msg = "matcher is documented pretty-printed instead of as written";
+    cx.tcx.struct_span_lint_hir(
+        crate::lint::REFORMATTED_MACRO_MATCHERS,
+        hir_id,
+        span,
+        msg,
+        |lint| lint.note(mismatch.note()),
+    );
 }
 
 /// Find the source snippet for this token's Span, reparse it, and return the
 /// snippet if the reparsed TokenTree matches the argument TokenTree.
-fn snippet_equal_to_token(tcx: TyCtxt<'_>, matcher: &TokenTree) -> Option<String> {
+fn snippet_equal_to_token(
+    cx: &DocContext<'_>,
+    matcher: &TokenTree,
+    def_id: DefId,
+) -> Result<String, SnippetMismatch> {
     // Find what rustc thinks is the source snippet.
     // This may not actually be anything meaningful if this matcher was itself
     // generated by a macro.
-    let source_map = tcx.sess.source_map();
+    let source_map = cx.tcx.sess.source_map();
     let span = matcher.span();
-    let snippet = source_map.span_to_snippet(span).ok()?;
-
-    // Create a Parser.
-    let sess =
-        ParseSess::new(rustc_driver::DEFAULT_LOCALE_RESOURCES.to_vec(), FilePathMapping::empty());
-    let file_name = source_map.span_to_filename(span);
-    let mut parser =
-        match rustc_parse::maybe_new_parser_from_source_str(&sess, file_name, snippet.clone()) {
+    // Matchers made up by the compiler have dummy or empty spans, whose snippet
+    // is at best some unrelated text at the start of the first file.
+    if !may_have_snippet(span) {
+        return Err(SnippetMismatch::Unavailable);
+    }
+    // Extracting, reparsing and comparing a huge matcher costs about as much as
+    // documenting it in the first place, so leave those to the pretty printer.
+    // This is checked on the span so that not even the snippet gets copied.
+    if (span.hi() - span.lo()).to_usize() > cx.render_options.macro_matcher_snippet_limit {
+        return Err(SnippetMismatch::TooLarge);
+    }
+    // A span put together from the tokens of several files, for instance around
+    // an `include!`, has no snippet that could be the matcher. Worse, the part
+    // of it in one of the files may happen to reparse to a single tree.
+    let file = source_map.lookup_source_file(span.lo());
+    if span.hi() > file.end_pos {
+        return Err(SnippetMismatch::CrossesFiles);
+    }
+    // The source of the files of other crates, which macros inlined from these
+    // crates are written in, is only loaded from disk on demand. Some files have
+    // no source to compare with, like the ones of dependencies whose paths have
+    // been remapped. Don't go as far as creating a parser for those.
+    if !source_map.ensure_source_file_source_present(file.clone())
+        || !source_map.is_span_accessible(span)
+    {
+        return Err(SnippetMismatch::Unavailable);
+    }
+    let snippet = || source_map.span_to_snippet(span).map_err(|_| SnippetMismatch::Unavailable);
+
+    let sess = reparse_sess(cx, def_id);
+
+    // All the arms of a macro, and usually many macros, come from the same file.
+    // Lex each file once and look the matchers up in it by their position
+    // instead of lexing every matcher on its own.
+    let mut reparsed_files = cx.matcher_reparsed_files.borrow_mut();
+    let reparsed_file = reparsed_files
+        .entry(file.start_pos)
+        .or_insert_with(|| ReparsedSourceFile::new(&sess, source_map, &file));
+    if let Some(reparsed_file) = reparsed_file {
+        // No tree spanning exactly the matcher means that its span covers some
+        // other tokens than its own.
+        let reparsed_tree =
+            reparsed_file.delimited_tree(&file, span).ok_or(SnippetMismatch::TreeMismatch)?;
+        return if trees_eq_unspanned(reparsed_tree, matcher) {
+            snippet()
+        } else {
+            Err(SnippetMismatch::TreeMismatch)
+        };
+    }
+
+    // The file as a whole could not be lexed, try the snippet on its own. It is
+    // handed over to the parser, and only copied again if it turns out to be
+    // the matcher.
+    let reparsed_tree = reparse_snippet(&sess, snippet()?).ok_or(SnippetMismatch::ParseError)?;
+
+    // Compare against the original tree.
+    if trees_eq_unspanned(&reparsed_tree, matcher) {
+        snippet()
+    } else {
+        Err(SnippetMismatch::TreeMismatch)
+    }
+}
+
+fn may_have_snippet(span: Span) -> bool {
+    !span.is_dummy() && !span.is_empty()
+}
+
+/// The delimited token trees of a source file containing macro matchers, as
+/// lexed again in the reparse session. They are indexed by the byte range they
+/// span relative to the start of the file, which is the same in the original
+/// and in the reparsed file.
+pub(crate) struct ReparsedSourceFile {
+    delimited_trees: FxHashMap<(u32, u32), TokenTree>,
+}
+
+impl ReparsedSourceFile {
+    fn new(sess: &ParseSess, source_map: &SourceMap, file: &SourceFile) -> Option<Self> {
+        let src =
+            source_map.span_to_snippet(Span::with_root_ctxt(file.start_pos, file.end_pos)).ok()?;
+        let file_name = reparse_file_name(&src);
+        let trees = parse_token_trees(sess, file_name.clone(), src)?;
+        let start_pos = sess.source_map().get_source_file(&file_name)?.start_pos;
+
+        let mut delimited_trees = FxHashMap::default();
+        index_delimited_trees(&trees, start_pos, &mut delimited_trees);
+        Some(ReparsedSourceFile { delimited_trees })
+    }
+
+    /// The reparsed tree spanning exactly what `span` does in `file`.
+    fn delimited_tree(&self, file: &SourceFile, span: Span) -> Option<&TokenTree> {
+        self.delimited_trees.get(&((span.lo() - file.start_pos).0, (span.hi() - file.start_pos).0))
+    }
+}
+
+fn index_delimited_trees<'a>(
+    trees: impl IntoIterator<Item = &'a TokenTree>,
+    start_pos: BytePos,
+    index: &mut FxHashMap<(u32, u32), TokenTree>,
+) {
+    // Machine-generated code can nest delimiters deep enough to overflow the
+    // stack when recursing, so keep track of the groups still to visit instead.
+    let mut pending: Vec<&TokenTree> = trees.into_iter().collect();
+    while let Some(tree) = pending.pop() {
+        if let TokenTree::Delimited(delim_span, _, tts) = tree {
+            let span = delim_span.entire();
+            index.insert(((span.lo() - start_pos).0, (span.hi() - start_pos).0), tree.clone());
+            pending.extend(tts.trees());
+        }
+    }
+}
+
+/// Same as `TokenTree::eq_unspanned`, with two differences. Operators made of
+/// several punctuation characters are compared one character at a time, so
+/// that `>>` and `> >` are equal no matter how each side was lexed or glued.
+/// And the pairs of groups still to compare are kept in a list instead of
+/// recursing into them, which would overflow the stack on deeply nested
+/// matchers.
+fn trees_eq_unspanned(a: &TokenTree, b: &TokenTree) -> bool {
+    let mut pending = vec![(split_trees([a]), split_trees([b]))];
+    while let Some((a, b)) = pending.pop() {
+        if a.len() != b.len() {
+            return false;
+        }
+        for pair in a.into_iter().zip(b) {
+            match pair {
+                (SplitTree::Token(a), SplitTree::Token(b)) => {
+                    if a != b {
+                        return false;
+                    }
+                }
+                (SplitTree::Delimited(a_delim, a_tts), SplitTree::Delimited(b_delim, b_tts)) => {
+                    if a_delim != b_delim {
+                        return false;
+                    }
+                    pending.push((split_trees(a_tts.trees()), split_trees(b_tts.trees())));
+                }
+                _ => return false,
+            }
+        }
+    }
+    true
+}
+
+/// A token tree seen by `trees_eq_unspanned`, with its tokens split into
+/// single punctuation characters.
+enum SplitTree<'a> {
+    Token(TokenKind),
+    Delimited(Delimiter, &'a TokenStream),
+}
+
+fn split_trees<'a>(trees: impl IntoIterator<Item = &'a TokenTree>) -> Vec<SplitTree<'a>> {
+    let mut split = Vec::new();
+    for tree in trees {
+        match tree {
+            TokenTree::Token(token, _) => split_token(token.kind.clone(), &mut split),
+            TokenTree::Delimited(_, delim, tts) => split.push(SplitTree::Delimited(*delim, tts)),
+        }
+    }
+    split
+}
+
+fn split_token(kind: TokenKind, split: &mut Vec<SplitTree<'_>>) {
+    // `break_two_token_op` leaves `..=` alone.
+    let broken = match kind {
+        token::DotDotEq => Some((token::DotDot, token::Eq)),
+        _ => kind.break_two_token_op(),
+    };
+    match broken {
+        Some((first, second)) => {
+            split_token(first, split);
+            split_token(second, split);
+        }
+        None => split.push(SplitTree::Token(kind)),
+    }
+}
+
+/// Reparses a matcher snippet that is not part of a file that could be lexed
+/// as a whole, and returns its token tree if it is made of a single one.
+fn reparse_snippet(sess: &ParseSess, snippet: String) -> Option<TokenTree> {
+    let file_name = reparse_file_name(&snippet);
+    with_reparser(sess, file_name, snippet, |parser| {
+        // Don't bother parsing the rest once a second tree starts, the snippet
+        // can't be the matcher anyway.
+        if parser.token == token::Eof {
+            return None;
+        }
+        let tree = parser.parse_token_tree();
+        if parser.token != token::Eof {
+            return None;
+        }
+        Some(tree)
+    })
+}
+
+/// The name `src` is registered under in the source map of the reparse session.
+///
+/// It is never the name of the real file the source comes from, so that the
+/// reparse session doesn't hold files that look like real ones but don't have
+/// their contents. The source map keeps every file registered with it, and
+/// asking for a file name it already knows returns the existing file instead
+/// of the new source, so the name is derived from the contents: two snippets
+/// from the same real file don't end up being parsed from the first one's text,
+/// while identical sources share a single entry.
+fn reparse_file_name(src: &str) -> FileName {
+    FileName::anon_source_code(src)
+}
+
+/// Lexes `src` into token trees in the reparse session.
+fn parse_token_trees(sess: &ParseSess, file_name: FileName, src: String) -> Option<Vec<TokenTree>> {
+    with_reparser(sess, file_name, src, |parser| match parser.parse_all_token_trees() {
+        Ok(trees) => Some(trees),
+        Err(diagnostic) => {
+            diagnostic.cancel();
+            None
+        }
+    })
+}
+
+/// Runs `f` with a parser of `src` in the reparse session. The session is
+/// shared by all matchers of the crate, so whatever the previous reparse left
+/// behind is cleared first.
+fn with_reparser<T>(
+    sess: &ParseSess,
+    file_name: FileName,
+    src: String,
+    f: impl FnOnce(&mut Parser<'_>) -> Option<T>,
+) -> Option<T> {
+    reset_reparse_sess(sess);
+
+    // Fatal errors, like the ones for unterminated raw strings, must only abort
+    // the snippet path and never the whole run.
+    rustc_driver::catch_fatal_errors(|| {
+        let mut parser = match rustc_parse::maybe_new_parser_from_source_str(sess, file_name, src) {
             Ok(parser) => parser,
             Err(diagnostics) => {
-                drop(diagnostics);
+                // Merely dropping these would leave them neither emitted nor
+                // cancelled, which debug assertions complain about.
+                for diagnostic in diagnostics {
+                    DiagnosticBuilder::new_diagnostic(&sess.span_diagnostic, diagnostic).cancel();
+                }
                 return None;
             }
         };
+        f(&mut parser)
+    })
+    .ok()
+    .flatten()
+}
 
-    // Reparse a single token tree.
-    let mut reparsed_trees = match parser.parse_all_token_trees() {
-        Ok(reparsed_trees) => reparsed_trees,
-        Err(diagnostic) => {
-            diagnostic.cancel();
-            return None;
+/// Normalizes the whitespace of a matcher snippet that compared equal to the
+/// matcher, without changing its tokens: line endings become `\n`, tabs are
+/// expanded, continuation lines are moved to the indentation they get in the
+/// rendered declaration and trailing whitespace is removed. The whitespace
+/// within literals is part of their value, and is kept as it is.
+fn normalize_snippet(cx: &DocContext<'_>, span: Span, snippet: &str) -> String {
+    let snippet = normalize_line_endings(snippet);
+    let literals = literal_ranges(&snippet);
+    let tab_width = cx.render_options.macro_matcher_tab_width;
+    let (snippet, source_indent) = match source_line_prefix(cx.tcx.sess.source_map(), span) {
+        Some(prefix) => {
+            // Tabs in the middle of the first line are expanded relative to the
+            // column the snippet starts at in the source.
+            let prefix = expand_tabs(&prefix, 0, tab_width, &[]);
+            let snippet = expand_tabs(&snippet, prefix.chars().count(), tab_width, &literals);
+            (snippet, prefix.chars().all(char::is_whitespace).then_some(prefix))
         }
+        None => (expand_tabs(&snippet, 0, tab_width, &literals), None),
     };
-    if reparsed_trees.len() != 1 {
-        return None;
+    trim_trailing_whitespace(&reindent_snippet(&snippet, source_indent.as_deref()))
+}
+
+/// Strips trailing spaces and tabs from every line, so that neither rendering
+/// path ever emits a line ending in whitespace. Lines ending within a literal
+/// are kept as they are, since their whitespace is part of its value.
+fn trim_trailing_whitespace(text: &str) -> String {
+    if !text.split('\n').any(|line| line.ends_with([' ', '\t'])) {
+        return text.to_owned();
+    }
+    let literals = literal_ranges(text);
+    let mut pos = 0;
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        let end = pos + line.len();
+        if end > pos && literals.iter().any(|range| range.contains(&(end - 1))) {
+            lines.push(line);
+        } else {
+            lines.push(line.trim_end_matches([' ', '\t']));
+        }
+        pos = end + 1;
+    }
+    lines.join("\n")
+}
+
+/// Turns `\r\n` and lone `\r` line endings into `\n`, so that the rendered
+/// declaration is the same whatever the line endings of the checkout are.
+/// Carriage returns within literals, like raw string literals, are part of
+/// their value and are kept.
+fn normalize_line_endings(snippet: &str) -> String {
+    if !snippet.contains('\r') {
+        return snippet.to_owned();
+    }
+    let verbatim = verbatim_ranges(snippet);
+    let mut out = String::with_capacity(snippet.len());
+    let mut chars = snippet.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        if c != '\r' || verbatim.iter().any(|range| range.contains(&pos)) {
+            out.push(c);
+        } else if !matches!(chars.peek(), Some((_, '\n'))) {
+            out.push('\n');
+        }
     }
-    let reparsed_tree = reparsed_trees.pop().unwrap();
+    out
+}
 
-    // Compare against the original tree.
-    if reparsed_tree.eq_unspanned(matcher) { Some(snippet) } else { None }
+/// The byte ranges of the tokens of `text` whose whitespace is part of their
+/// value: literals, and block comments, which doc comments can be.
+fn verbatim_ranges(text: &str) -> Vec<Range<usize>> {
+    token_ranges(text, |kind| {
+        matches!(
+            kind,
+            rustc_lexer::TokenKind::Literal { .. } | rustc_lexer::TokenKind::BlockComment { .. }
+        )
+    })
 }
 
-fn print_tt(printer: &mut Printer<'_>, tt: &TokenTree) {
-    match tt {
-        TokenTree::Token(token, _) => {
-            let token_str = printer.token_to_string(token);
-            printer.word(token_str);
-            if let token::DocComment(..) = token.kind {
-                printer.hardbreak()
-            }
+/// The byte ranges of the literals of `text`.
+fn literal_ranges(text: &str) -> Vec<Range<usize>> {
+    token_ranges(text, |kind| matches!(kind, rustc_lexer::TokenKind::Literal { .. }))
+}
+
+/// The byte ranges of the tokens of `text` whose kind is `wanted`.
+fn token_ranges(text: &str, wanted: impl Fn(&rustc_lexer::TokenKind) -> bool) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(text) {
+        let len = token.len as usize;
+        if wanted(&token.kind) {
+            ranges.push(pos..pos + len);
         }
-        TokenTree::Delimited(_span, delim, tts) => {
-            let open_delim = printer.token_kind_to_string(&token::OpenDelim(*delim));
-            printer.word(open_delim);
-            if !tts.is_empty() {
-                if *delim == Delimiter::Brace {
-                    printer.space();
-                }
-                print_tts(printer, tts);
-                if *delim == Delimiter::Brace {
-                    printer.space();
+        pos += len;
+    }
+    ranges
+}
+
+/// Returns the text preceding `span` on the source line it starts on.
+fn source_line_prefix(source_map: &SourceMap, span: Span) -> Option<String> {
+    let loc = source_map.lookup_char_pos(span.lo());
+    let line = loc.file.get_line(loc.line - 1)?;
+    Some(line.chars().take(loc.col.0).collect())
+}
+
+/// Replaces the tabs of `text` with spaces up to the next multiple of
+/// `tab_width`, as `<pre>` blocks would otherwise render them 8 columns wide.
+/// The first line of `text` is assumed to start at `start_column`. Tabs within
+/// `literals` are part of their value and are kept, though they still count
+/// towards the columns of the rest of the line.
+fn expand_tabs(
+    text: &str,
+    start_column: usize,
+    tab_width: usize,
+    literals: &[Range<usize>],
+) -> String {
+    if !text.contains('\t') {
+        return text.to_owned();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut column = start_column;
+    for (pos, c) in text.char_indices() {
+        match c {
+            '\t' => {
+                let width = tab_width - column % tab_width;
+                if literals.iter().any(|range| range.contains(&pos)) {
+                    out.push(c);
+                } else {
+                    out.extend(std::iter::repeat(' ').take(width));
                 }
+                column += width;
+            }
+            '\n' => {
+                out.push(c);
+                column = 0;
+            }
+            _ => {
+                out.push(c);
+                column += 1;
             }
-            let close_delim = printer.token_kind_to_string(&token::CloseDelim(*delim));
-            printer.word(close_delim);
         }
     }
+    out
 }
 
-fn print_tts(printer: &mut Printer<'_>, tts: &TokenStream) {
-    #[derive(Copy, Clone, PartialEq)]
-    enum State {
-        Start,
-        Dollar,
-        DollarIdent,
-        DollarIdentColon,
-        DollarParen,
-        DollarParenSep,
-        Pound,
-        PoundBang,
-        Ident,
-        Other,
+/// Moves the continuation lines of a multi-line matcher snippet from the
+/// indentation the matcher has in its source file to the one it has once
+/// rendered as an arm of the declaration, keeping their relative indentation.
+///
+/// `source_indent` is the indentation of the line the snippet starts on. When
+/// the snippet starts in the middle of a line and that is unknown, the common
+/// indentation of the continuation lines takes its place.
+fn reindent_snippet(snippet: &str, source_indent: Option<&str>) -> String {
+    let mut lines = snippet.split('\n');
+    let first_line = lines.next().unwrap_or_default();
+    if snippet.len() == first_line.len() {
+        return snippet.to_owned();
     }
+    // The lines starting within a literal are part of its value, and are kept
+    // as they are.
+    let literals = literal_ranges(snippet);
+    let mut pos = first_line.len() + 1;
+    let lines: Vec<(&str, bool)> = lines
+        .map(|line| {
+            let verbatim = literals.iter().any(|range| range.contains(&(pos - 1)));
+            pos += line.len() + 1;
+            (line, verbatim)
+        })
+        .collect();
 
+    // Blank lines don't count, they're emptied anyway.
+    let mut common_indent: Option<&str> = None;
+    for &(line, verbatim) in &lines {
+        if verbatim || line.trim().is_empty() {
+            continue;
+        }
+        let line_indent = &line[..line.len() - line.trim_start().len()];
+        common_indent = Some(match common_indent {
+            Some(common_indent) => common_prefix(common_indent, line_indent),
+            None => line_indent,
+        });
+    }
+    let common_indent = common_indent.unwrap_or_default();
+    // Only ever remove whitespace that all the continuation lines have, even if
+    // some of them are less indented than the first one, or are indented with a
+    // different mix of tabs and spaces.
+    let strip = match source_indent {
+        Some(source_indent) => common_prefix(source_indent, common_indent),
+        None => common_indent,
+    };
+
+    let mut out = String::with_capacity(snippet.len());
+    out.push_str(first_line);
+    for (line, verbatim) in lines {
+        out.push('\n');
+        if verbatim {
+            out.push_str(line);
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        out.push_str(RENDERED_ARM_INDENT);
+        out.push_str(&line[strip.len()..]);
+    }
+    out
+}
+
+/// The longest common prefix of `a` and `b`, which are both whitespace.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|&((_, a), b)| a != b)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i);
+    &a[..len]
+}
+
+/// The session the matchers of the macro `def_id` are reparsed in, see
+/// `new_reparse_sess`. Only one is created per edition, and shared by all the
+/// macros of the crates of that edition, see `DocContext::matcher_reparse_sess`.
+fn reparse_sess(cx: &DocContext<'_>, def_id: DefId) -> Lrc<ParseSess> {
+    // The crate the macro is inlined from may be of another edition than the
+    // documented one.
+    let edition =
+        if def_id.is_local() { cx.sess().edition() } else { cx.tcx.crate_edition(def_id.krate) };
+    cx.matcher_reparse_sess
+        .borrow_mut()
+        .entry(edition)
+        .or_insert_with(|| Lrc::new(new_reparse_sess(cx.sess(), edition)))
+        .clone()
+}
+
+/// Creates the session `snippet_equal_to_token` reparses snippets of `edition`
+/// in.
+///
+/// The snippet has to be parsed the way the crate defining the macro was, so
+/// that edition-dependent tokens (`async` used as an identifier in 2015,
+/// reserved prefixes in 2021, ...) come out of the reparse as they did out of
+/// the original parse. The lexer itself takes the edition from the spans of the
+/// tokens, which are root spans of the edition of the documented crate, but it
+/// only decides there whether reserved prefixes are reported, not which tokens
+/// they make.
+///
+/// The reparse is purely speculative, so everything it emits, fatal errors
+/// included, is sent into a sink instead of stderr.
+fn new_reparse_sess(sess: &Session, edition: Edition) -> ParseSess {
+    let fallback_bundle = rustc_errors::fallback_fluent_bundle(
+        rustc_driver::DEFAULT_LOCALE_RESOURCES.to_vec(),
+        false,
+    );
+    let emitter = EmitterWriter::new(
+        Box::new(io::sink()),
+        None,
+        None,
+        fallback_bundle,
+        false,
+        false,
+        false,
+        None,
+        false,
+        false,
+        TerminalUrl::No,
+    );
+    let handler = Handler::with_emitter(false, None, Box::new(emitter));
+    // Remap the paths of the reparsed files the way the ones of the documented
+    // crate are, so that nothing depends on where the crate was built.
+    let sm = Lrc::new(SourceMap::new(sess.source_map().path_mapping().clone()));
+    let mut reparse_sess = ParseSess::with_span_handler(handler, sm);
+    reparse_sess.edition = edition;
+    reparse_sess.unstable_features = sess.parse_sess.unstable_features;
+    reparse_sess
+}
+
+/// Drains the state that reparsing a matcher may have accumulated in the shared
+/// session, so that errors and lints buffered while reparsing one matcher never
+/// leak into the next one.
+fn reset_reparse_sess(sess: &ParseSess) {
+    sess.span_diagnostic.reset_err_count();
+    sess.buffered_lints.lock().clear();
+    sess.ambiguous_block_expr_parse.lock().clear();
+    sess.bad_unicode_identifiers.lock().clear();
+    sess.gated_spans.spans.lock().clear();
+    sess.symbol_gallery.symbols.lock().clear();
+    sess.reached_eof.store(false, Ordering::Relaxed);
+}
+
+fn print_token(printer: &mut Printer<'_>, token: &Token) {
+    if let token::DocComment(comment_kind, attr_style, data) = token.kind {
+        print_doc_comment(printer, comment_kind, attr_style, data);
+        return;
+    }
+    if let Some(token_str) = static_token_str(token) {
+        printer.word(token_str);
+        return;
+    }
+    let token_str = printer.token_to_string(token);
+    // Spliced in items and blocks are pretty-printed on several lines, which
+    // are printed one by one, so that the printer knows where they end.
+    if token_str.contains('\n') {
+        let mut lines = token_str.split('\n');
+        printer.word(lines.next().unwrap_or_default().to_owned());
+        for line in lines {
+            printer.hardbreak();
+            printer.word(line.to_owned());
+        }
+        return;
+    }
+    printer.word(token_str);
+}
+
+/// The text of an identifier that matchers are full of, like a fragment
+/// specifier or a keyword, which is printed without allocating a string for
+/// it. `None` for any other token, which the printer turns into a string of its
+/// own, if only to check whether it is an identifier to print raw.
+fn static_token_str(token: &Token) -> Option<&'static str> {
+    let token::Ident(symbol, /* is_raw */ false) = token.kind else { return None };
+    let text = match symbol {
+        sym::block => "block",
+        sym::expr => "expr",
+        sym::ident => "ident",
+        sym::item => "item",
+        sym::lifetime => "lifetime",
+        sym::literal => "literal",
+        sym::meta => "meta",
+        sym::pat => "pat",
+        sym::pat_param => "pat_param",
+        sym::path => "path",
+        sym::stmt => "stmt",
+        sym::tt => "tt",
+        sym::ty => "ty",
+        sym::vis => "vis",
+        kw::As => "as",
+        kw::Const => "const",
+        kw::Crate => "crate",
+        kw::Enum => "enum",
+        kw::Fn => "fn",
+        kw::For => "for",
+        kw::Impl => "impl",
+        kw::In => "in",
+        kw::Let => "let",
+        kw::Mut => "mut",
+        kw::Pub => "pub",
+        kw::Ref => "ref",
+        kw::SelfLower => "self",
+        kw::SelfUpper => "Self",
+        kw::Static => "static",
+        kw::Struct => "struct",
+        kw::Trait => "trait",
+        kw::Type => "type",
+        kw::Unsafe => "unsafe",
+        kw::Where => "where",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Prints a doc comment the way it was written, as in `/// Docs` or `/*! Docs */`.
+/// The lines of block comments are printed one by
+/// one, so that the printer knows where they end.
+fn print_doc_comment(
+    printer: &mut Printer<'_>,
+    comment_kind: CommentKind,
+    attr_style: AttrStyle,
+    data: Symbol,
+) {
+    let marker = match attr_style {
+        AttrStyle::Outer => '/',
+        AttrStyle::Inner => '!',
+    };
+    match comment_kind {
+        CommentKind::Line => printer.word(format!("//{marker}{data}")),
+        CommentKind::Block => {
+            let mut lines = data.as_str().split('\n');
+            let first = lines.next().unwrap_or_default();
+            printer.word(format!("/*{marker}{first}"));
+            for line in lines {
+                printer.hardbreak();
+                printer.word(line.to_owned());
+            }
+            printer.word("*/");
+        }
+    }
+}
+
+/// The states `print_tts` leaves a group in after printing a tree, which tell
+/// how the next one is spaced.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum State {
+    Start,
+    Dollar,
+    DollarIdent,
+    DollarIdentColon,
+    DollarParen,
+    DollarParenSep,
+    Pound,
+    PoundBang,
+    Ampersand,
+    GenericKeyword,
+    GenericOpen,
+    GenericClose,
+    PathSegment,
+    PathSep,
+    Dot,
+    RangePrefix,
+    Unary,
+    Repetition,
+    LineStart,
+    DocComment,
+    ClosureOpen,
+    Star,
+    MacroBang,
+    Lifetime,
+    Ident,
+    Other,
+}
+
+/// The classes of tokens `token_transition` tells apart. Some depend on the
+/// trees printed around the token, and not only on the token itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum TokenClass {
+    DocComment,
+    Ident(IdentClass),
+    Lifetime,
+    Colon,
+    /// `::`.
+    ModSep,
+    Dot,
+    /// `..`, `..=` or `...` without an operand before it, as in `..=x`.
+    RangeStart,
+    /// A range between two operands, as in `a..b`.
+    RangeBetween,
+    /// A range after its only operand, as in `x..`.
+    RangeEnd,
+    /// A `|` opening the parameters of a closure, as in `move |x|`.
+    ClosureOpen,
+    /// The `|` closing the parameters of a closure.
+    ClosureClose,
+    /// Any other `|`.
+    Or,
+    /// A `-`, `&` or `&&` without an operand before it, as in `-1` or `&mut $x`.
+    Prefix,
+    /// A `*` without an operand before it, as in `*const T`.
+    PrefixStar,
+    /// A `!` without an operand before it, as in `!$cond`.
+    PrefixNot,
+    /// A `?` without an operand before it, as in `?Sized`.
+    PrefixQuestion,
+    Not,
+    Star,
+    /// An `&` or `&&` after an operand.
+    And,
+    Question,
+    Plus,
+    /// A `<` outside of generic arguments.
+    Lt,
+    /// A `<` inside of generic arguments.
+    LtInGenerics,
+    /// A `>` outside of generic arguments.
+    Gt,
+    /// A `>` closing generic arguments, which were opened tight against what
+    /// they are the arguments of or not.
+    GtClosing {
+        tight: bool,
+    },
+    /// A `>>` outside of generic arguments.
+    Shr,
+    /// A `>>` closing two generic argument lists at once, like `GtClosing`.
+    ShrClosing {
+        tight: bool,
+    },
+    /// `,` or `;`.
+    Separator,
+    Dollar,
+    /// A `#` followed by `!` or `[`, which starts an attribute.
+    AttrPound,
+    Pound,
+    /// A fragment spliced in by an outer macro, which is a path or not.
+    Interpolated {
+        path: bool,
+    },
+    Other,
+}
+
+/// The classes of identifiers `token_transition` tells apart.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum IdentClass {
+    /// `$crate`, which macro-generated matchers have as a single identifier.
+    DollarCrate,
+    Crate,
+    Super,
+    Mut,
+    Const,
+    /// `impl` or `for`, which generic parameters may follow.
+    GenericKeyword,
+    /// A raw identifier, or one that is no keyword followed by a space before
+    /// open delimiters.
+    Name,
+    /// Any other keyword.
+    Keyword,
+}
+
+/// What printing a token changes in its group besides the state.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Effect {
+    /// Opens generic arguments, tight against what they are the arguments of
+    /// or not.
+    OpenAngle {
+        tight: bool,
+    },
+    /// Closes that many generic argument lists.
+    CloseAngles(usize),
+    OpenClosureParams,
+    CloseClosureParams,
+}
+
+/// Classifies an identifier for `token_transition`. Keywords are told apart in
+/// `edition`, unless `span` comes from a macro expansion, as for
+/// `usually_needs_space_between_keyword_and_open_delim`.
+fn classify_ident(symbol: Symbol, is_raw: bool, span: Span, edition: Edition) -> IdentClass {
+    // Raw identifiers are never keywords, whatever they are spelled like.
+    if is_raw {
+        return IdentClass::Name;
+    }
+    match symbol {
+        kw::DollarCrate => IdentClass::DollarCrate,
+        kw::Crate => IdentClass::Crate,
+        kw::Super => IdentClass::Super,
+        kw::Mut => IdentClass::Mut,
+        kw::Const => IdentClass::Const,
+        kw::Impl | kw::For => IdentClass::GenericKeyword,
+        _ if usually_needs_space_between_keyword_and_open_delim(symbol, span, edition) => {
+            IdentClass::Keyword
+        }
+        _ => IdentClass::Name,
+    }
+}
+
+/// The transition table of the state machine `print_tts` spaces tokens with:
+/// whether a token of `class` printed in `state` is spaced out from what comes
+/// before it, the state it leaves its group in, and what else it changes.
+///
+/// The first arm a pair matches wins.
+fn token_transition(state: State, class: TokenClass) -> (bool, State, Option<Effect>) {
+    use IdentClass as I;
     use State::*;
+    use TokenClass as T;
 
-    let mut state = Start;
-    for tt in tts.trees() {
-        let (needs_space, next_state) = match &tt {
-            TokenTree::Token(tt, _) => match (state, &tt.kind) {
-                (Dollar, token::Ident(..)) => (false, DollarIdent),
-                (DollarIdent, token::Colon) => (false, DollarIdentColon),
-                (DollarIdentColon, token::Ident(..)) => (false, Other),
-                (
-                    DollarParen,
-                    token::BinOp(BinOpToken::Plus | BinOpToken::Star) | token::Question,
-                ) => (false, Other),
-                (DollarParen, _) => (false, DollarParenSep),
-                (DollarParenSep, token::BinOp(BinOpToken::Plus | BinOpToken::Star)) => {
-                    (false, Other)
-                }
-                (Pound, token::Not) => (false, PoundBang),
-                (_, token::Ident(symbol, /* is_raw */ false))
-                    if !usually_needs_space_between_keyword_and_open_delim(*symbol, tt.span) =>
-                {
-                    (true, Ident)
+    match (state, class) {
+        (_, T::DocComment) => (true, DocComment, None),
+        // `$crate`, and the path it starts.
+        (Dollar, T::Ident(I::Crate)) => (false, PathSegment, None),
+        (_, T::Ident(I::DollarCrate)) => (true, PathSegment, None),
+        (Dollar, T::Ident(_)) => (false, DollarIdent, None),
+        (DollarIdent, T::Colon) => (false, DollarIdentColon, None),
+        (DollarIdentColon, T::Ident(_)) => (false, Other, None),
+        // A fragment specifier spliced in by an outer macro, as in `$x:$frag`.
+        (DollarIdentColon, T::Dollar) => (false, Dollar, None),
+        (
+            DollarParen | DollarParenSep,
+            T::Plus | T::Star | T::PrefixStar | T::Question | T::PrefixQuestion,
+        ) => (false, Repetition, None),
+        (DollarParen, _) => (false, DollarParenSep, None),
+        // Paths, as in `std::mem::take` or `::core::option::Option`. A `::` is
+        // only tight against what could be the previous segment of its path,
+        // so that a leading `::` stays apart from what comes before it, and
+        // against what continues its path, so that it stays apart from any
+        // other token after it too.
+        (Ident | PathSegment | DollarIdent | GenericClose, T::ModSep) => (false, PathSep, None),
+        (_, T::ModSep) => (true, PathSep, None),
+        (PathSep, T::Ident(_)) => (false, PathSegment, None),
+        (PathSep, T::Dollar) => (false, Dollar, None),
+        (PathSep, T::Interpolated { path: true }) => (false, Ident, None),
+        // A turbofish, as in `collect::<Vec<_>>()`.
+        (PathSep, T::Lt | T::LtInGenerics) => {
+            (false, GenericOpen, Some(Effect::OpenAngle { tight: true }))
+        }
+        // A glob import, as in `use std::*;`.
+        (PathSep, T::Star | T::PrefixStar) => (false, Other, None),
+        (_, T::Ident(I::Super | I::Crate)) => (true, PathSegment, None),
+        // Field accesses and method calls, as in `self.0` or `$x.len()`.
+        (_, T::Dot) => (false, Dot, None),
+        (Dot, T::Ident(_)) => (false, Ident, None),
+        (Dot, T::Dollar) => (false, Dollar, None),
+        (Dot, _) => (false, Other, None),
+        // Ranges are only spaced out when they have operands on both sides, and
+        // are tight against their only operand otherwise, as in `x..` or
+        // `..=x`. The rest of a pattern, as in `[a, ..]`, has no operand at all.
+        (_, T::RangeStart) => (true, RangePrefix, None),
+        (_, T::RangeBetween) => (true, Other, None),
+        (_, T::RangeEnd) => (false, Other, None),
+        (_, T::ClosureClose) => (false, Other, Some(Effect::CloseClosureParams)),
+        (_, T::ClosureOpen) => (true, ClosureOpen, Some(Effect::OpenClosureParams)),
+        (Pound, T::Not | T::PrefixNot) => (false, PoundBang, None),
+        // Macro calls, as in `vec![]` or `$crate::m!()`.
+        (Ident | PathSegment | DollarIdent, T::Not | T::PrefixNot) => (false, MacroBang, None),
+        // An operator without an operand before it applies to what comes after
+        // it, as in `-1`, `!$cond`, `*const T`, `&mut $x` or `T: ?Sized`.
+        // Kleene operators have been taken care of above already.
+        (_, T::Prefix | T::PrefixStar | T::PrefixNot | T::PrefixQuestion) => (true, Unary, None),
+        // The try operator, as in `f()?`.
+        (_, T::Question) => (false, Other, None),
+        // Raw pointer types in other positions.
+        (Star, T::Ident(I::Const | I::Mut)) => (false, Other, None),
+        (_, T::Star) => (true, Star, None),
+        // `$$`, and `$` for a metavariable expression like `${count(x)}`.
+        (Dollar, T::Dollar) => (false, Dollar, None),
+        (Ampersand, T::Lifetime | T::Ident(I::Mut)) => (false, Other, None),
+        (_, T::And) => (true, Ampersand, None),
+        // Labels and bounds, as in `'outer: loop` or `T: 'a`. Unlike in
+        // `$x:expr`, the colon is followed by a space.
+        (_, T::Lifetime) => (true, Lifetime, None),
+        (Ident | Lifetime, T::Colon) => (false, Other, None),
+        // A `<` right after a name, as in `impl<'a>` or `Vec<$t>`, opens
+        // generic arguments rather than comparing anything, unlike the one in
+        // `$a < $b`. Any `<` inside of those does too.
+        (Ident | GenericKeyword | PathSegment, T::Lt | T::LtInGenerics) => {
+            (false, GenericOpen, Some(Effect::OpenAngle { tight: true }))
+        }
+        (_, T::LtInGenerics) => (true, Other, Some(Effect::OpenAngle { tight: false })),
+        (_, T::GtClosing { tight }) => {
+            (!tight, if tight { GenericClose } else { Other }, Some(Effect::CloseAngles(1)))
+        }
+        // The `>>` closing two generic argument lists at once, as in
+        // `Vec<Vec<T>>`.
+        (_, T::ShrClosing { tight }) => {
+            (!tight, if tight { GenericClose } else { Other }, Some(Effect::CloseAngles(2)))
+        }
+        (_, T::Ident(I::GenericKeyword)) => (true, GenericKeyword, None),
+        (_, T::Ident(I::Name)) => (true, Ident, None),
+        (_, T::Separator) => (false, Other, None),
+        (_, T::Dollar) => (true, Dollar, None),
+        (_, T::AttrPound) => (true, Pound, None),
+        // The other fragments spliced in by an outer macro, printed the way the
+        // AST they were parsed into is. A path is followed by its arguments
+        // like an identifier is, as in `$p()`.
+        (_, T::Interpolated { path: true }) => (true, Ident, None),
+        (_, _) => (true, Other, None),
+    }
+}
+
+/// Like `token_transition`, for a group in `delim` printed in `state`, which
+/// changes nothing else.
+fn delimited_transition(state: State, delim: Delimiter) -> (bool, State) {
+    use State::*;
+
+    match (state, delim) {
+        (Dollar, Delimiter::Parenthesis) => (false, DollarParen),
+        (Dollar, Delimiter::Brace) => (false, Other),
+        // A nested import, as in `use std::{mem, ptr};`.
+        (PathSep, Delimiter::Brace) => (false, Other),
+        (Dot, _) => (false, Other),
+        (MacroBang, Delimiter::Parenthesis | Delimiter::Bracket) => (false, Other),
+        (GenericClose, Delimiter::Parenthesis) => (false, Other),
+        (Pound | PoundBang, Delimiter::Bracket) => (false, Other),
+        (Ident | PathSegment, Delimiter::Parenthesis | Delimiter::Bracket) => (false, Other),
+        (_, _) => (true, Other),
+    }
+}
+
+/// Returns whether some of the tokens were left out, for there were more of
+/// them than the `token_limit` of the `options`.
+fn print_tts(printer: &mut Printer<'_>, tts: &TokenStream, options: PrintOptions) -> bool {
+    use State::*;
+
+    /// A token stream being printed, and the delimiters it is enclosed in.
+    struct Group<'a> {
+        trees: RefTokenTreeCursor<'a>,
+        delim: Option<Delimiter>,
+        state: State,
+        /// Whether the last token printed in this group was immediately
+        /// followed by an operator token in the source.
+        joint: bool,
+        /// Whether the last tree printed in this group could be the end of an
+        /// operand, like an identifier or a literal.
+        operand: bool,
+        /// Whether the last `|` printed in this group opened the parameters of a
+        /// closure, which the next `|` closes.
+        closure_params: bool,
+        /// Whether the delimiters of this group are separated from its
+        /// contents by spaces, as in `{ a }`.
+        padded: bool,
+        /// Whether this group is a block of `;`-terminated statements, which
+        /// are put on lines of their own if the block does not fit on one.
+        statements: bool,
+        /// Whether this group is a whole matcher made of `,`-separated
+        /// parameters, which lines are broken between rather than within.
+        parameters: bool,
+        /// Whether this group is a whole matcher whose parameters and
+        /// repetitions, or a whole transcriber whose statements, are always
+        /// put on lines of their own.
+        expanded: bool,
+        /// Whether a repetition, as in `$($x:expr),*`, is being printed in a
+        /// box of its own, so that it wraps as a whole.
+        repetition: bool,
+        /// Whether this group is the brackets of an inner attribute at the start
+        /// of a block, which is followed by a line break.
+        hardbreak_after: bool,
+        /// The `<` printed in this group that are not closed yet, innermost
+        /// last, and whether each of them was printed tight against what it
+        /// opens the generic arguments of.
+        angles: Vec<bool>,
+        /// Whether this group is an invisible one, whose contents are printed
+        /// as if they were part of the enclosing group.
+        transparent: bool,
+        /// Whether the trees of this group count towards the `token_limit`,
+        /// which those of a repetition or a metavariable expression don't, as
+        /// they are part of a single unit.
+        counted: bool,
+    }
+
+    /// Classifies `token`, about to be printed in `group`, for `token_transition`.
+    fn classify_token(token: &Token, group: &Group<'_>, edition: Edition) -> TokenClass {
+        // Identifiers and lifetimes spliced in by an outer macro, as in a
+        // matcher written with the `$name:ident` the outer macro was called
+        // with, are spaced like the tokens they stand for.
+        let span = token.span;
+        let token = token.uninterpolate();
+        let prefix = !group.operand;
+        match &token.kind {
+            token::DocComment(..) => TokenClass::DocComment,
+            &token::Ident(symbol, is_raw) => {
+                TokenClass::Ident(classify_ident(symbol, is_raw, span, edition))
+            }
+            token::Lifetime(..) => TokenClass::Lifetime,
+            token::Colon => TokenClass::Colon,
+            token::ModSep => TokenClass::ModSep,
+            token::Dot => TokenClass::Dot,
+            token::DotDot | token::DotDotEq | token::DotDotDot => {
+                if prefix {
+                    TokenClass::RangeStart
+                } else if group.trees.look_ahead(0).is_some_and(starts_operand) {
+                    TokenClass::RangeBetween
+                } else {
+                    TokenClass::RangeEnd
                 }
-                (_, token::Comma | token::Semi) => (false, Other),
-                (_, token::Dollar) => (true, Dollar),
-                (_, token::Pound) => (true, Pound),
-                (_, _) => (true, Other),
+            }
+            // A `|` without an operand before it opens the parameters of a
+            // closure, as in `move |x, y|`, rather than being an "or".
+            token::BinOp(BinOpToken::Or) if group.closure_params => TokenClass::ClosureClose,
+            token::BinOp(BinOpToken::Or) if prefix => TokenClass::ClosureOpen,
+            token::BinOp(BinOpToken::Minus | BinOpToken::And) | token::AndAnd if prefix => {
+                TokenClass::Prefix
+            }
+            token::BinOp(BinOpToken::Star) if prefix => TokenClass::PrefixStar,
+            token::Not if prefix => TokenClass::PrefixNot,
+            token::Question if prefix => TokenClass::PrefixQuestion,
+            token::BinOp(BinOpToken::And) | token::AndAnd => TokenClass::And,
+            token::BinOp(BinOpToken::Star) => TokenClass::Star,
+            token::BinOp(BinOpToken::Plus) => TokenClass::Plus,
+            token::Not => TokenClass::Not,
+            token::Question => TokenClass::Question,
+            token::Lt if group.angles.is_empty() => TokenClass::Lt,
+            token::Lt => TokenClass::LtInGenerics,
+            token::Gt => match group.angles.last() {
+                Some(&tight) => TokenClass::GtClosing { tight },
+                None => TokenClass::Gt,
+            },
+            token::BinOp(BinOpToken::Shr) => match group.angles.last() {
+                Some(&tight) => TokenClass::ShrClosing { tight },
+                None => TokenClass::Shr,
             },
-            TokenTree::Delimited(_, delim, _) => match (state, delim) {
-                (Dollar, Delimiter::Parenthesis) => (false, DollarParen),
-                (Pound | PoundBang, Delimiter::Bracket) => (false, Other),
-                (Ident, Delimiter::Parenthesis | Delimiter::Bracket) => (false, Other),
-                (_, _) => (true, Other),
+            token::Comma | token::Semi => TokenClass::Separator,
+            token::Dollar => TokenClass::Dollar,
+            // Only a `#` followed by `!` or `[` starts an attribute. Some macros
+            // use other ones as markers in their matchers.
+            token::Pound => match group.trees.look_ahead(0) {
+                Some(
+                    TokenTree::Token(Token { kind: token::Not, .. }, _)
+                    | TokenTree::Delimited(_, Delimiter::Bracket, _),
+                ) => TokenClass::AttrPound,
+                _ => TokenClass::Pound,
             },
+            token::Interpolated(nt) => {
+                TokenClass::Interpolated { path: matches!(**nt, token::NtPath(..)) }
+            }
+            _ => TokenClass::Other,
+        }
+    }
+
+    // The groups being printed are kept on a stack of their own, innermost
+    // last, rather than recursing into them, as matchers may nest delimiters
+    // deeper than the call stack allows.
+    // A matcher is taken for a list of parameters, as in `($a:expr, $b:expr)`,
+    // when it has commas and no semicolons of its own, unless its parameters
+    // are asked for on lines of their own anyway. The statements of a
+    // transcriber, as in `{ let x = $e; x + 1 }`, always are on lines of their
+    // own, like the ones of a function body.
+    let has_token = |kind: TokenKind| {
+        tts.trees().any(|tt| matches!(tt, TokenTree::Token(token, _) if token.kind == kind))
+    };
+    let (parameters, statements, expanded) = match options.part {
+        RulePart::Matcher => {
+            let expanded = options.style == MacroMatcherStyle::Expanded && !options.single_line;
+            let parameters = has_token(token::Comma) && (expanded || !has_token(token::Semi));
+            (parameters, false, expanded)
+        }
+        RulePart::Transcriber => {
+            let statements = has_token(token::Semi);
+            (false, statements, statements)
+        }
+    };
+    if parameters || statements {
+        printer.ibox(0);
+    }
+    let mut groups = vec![Group {
+        trees: tts.trees(),
+        delim: None,
+        state: Start,
+        joint: false,
+        operand: false,
+        closure_params: false,
+        padded: false,
+        statements,
+        parameters,
+        expanded,
+        repetition: false,
+        hardbreak_after: false,
+        angles: Vec::new(),
+        transparent: false,
+        counted: true,
+    }];
+    let mut tokens_left = options.token_limit;
+    let mut truncated = false;
+    while let Some(group) = groups.last_mut() {
+        if group.repetition && !matches!(group.state, Dollar | DollarParen | DollarParenSep) {
+            printer.end();
+            group.repetition = false;
+        }
+        // Once the matcher got truncated, the groups it was in the middle of
+        // are closed without printing the rest of their contents.
+        let next = if truncated { None } else { group.trees.next() };
+        let Some(tt) = next else {
+            if group.repetition {
+                printer.end();
+            }
+            if let Some(delim) = group.delim {
+                if group.statements {
+                    printer.end();
+                    if group.state == DocComment {
+                        printer.hardbreak();
+                    }
+                    printer.break_offset_if_not_bol(1, -INDENT_UNIT);
+                    printer.end();
+                } else if group.state == DocComment {
+                    printer.hardbreak();
+                } else if group.padded && group.state != LineStart {
+                    printer.space();
+                }
+                let close_delim = printer.token_kind_to_string(&token::CloseDelim(delim));
+                printer.word(close_delim);
+            }
+            if group.hardbreak_after {
+                printer.hardbreak();
+            }
+            if group.delim.is_none() && (group.parameters || group.statements) {
+                printer.end();
+            }
+            if let Some(Group { transparent: true, state, joint, operand, angles, .. }) =
+                groups.pop()
+            {
+                // An invisible group is never the outermost one.
+                let outer = groups.last_mut().unwrap();
+                outer.state = state;
+                outer.joint = joint;
+                outer.operand = operand;
+                outer.angles = angles;
+            }
+            continue;
         };
-        if state != Start && needs_space {
+        // Invisible groups, which an outer macro wraps the fragments it splices
+        // in with, are printed as just their contents, spaced as if they were
+        // not in a group at all. They don't count towards the `token_limit`.
+        if let TokenTree::Delimited(_, Delimiter::Invisible, inner) = tt {
+            let transparent = Group {
+                trees: inner.trees(),
+                delim: None,
+                state: group.state,
+                joint: group.joint,
+                operand: group.operand,
+                closure_params: false,
+                padded: false,
+                statements: false,
+                parameters: false,
+                expanded: false,
+                repetition: false,
+                hardbreak_after: false,
+                angles: mem::take(&mut group.angles),
+                transparent: true,
+                counted: group.counted,
+            };
+            groups.push(transparent);
+            continue;
+        }
+        if options.single_line
+            && let TokenTree::Token(Token { kind: token::DocComment(..), .. }, _) = tt
+        {
+            continue;
+        }
+
+        let (needs_space, next_state) = match tt {
+            TokenTree::Token(token, _) => {
+                let class = classify_token(token, group, options.edition);
+                let (needs_space, next_state, effect) = token_transition(group.state, class);
+                match effect {
+                    None => {}
+                    Some(Effect::OpenAngle { tight }) => group.angles.push(tight),
+                    Some(Effect::CloseAngles(count)) => {
+                        for _ in 0..count {
+                            group.angles.pop();
+                        }
+                    }
+                    Some(Effect::OpenClosureParams) => group.closure_params = true,
+                    Some(Effect::CloseClosureParams) => group.closure_params = false,
+                }
+                (needs_space, next_state)
+            }
+            TokenTree::Delimited(_, delim, _) => delimited_transition(group.state, *delim),
+        };
+        // Operators that were glued together where they come from, like the two
+        // halves of a `>>` split by the parser or the `:` `:` of a proc macro,
+        // are printed glued together again, whatever the state machine thinks.
+        let follows_joint = group.joint && matches!(tt, TokenTree::Token(..));
+        // Doc comments are followed by a line break, which is only printed
+        // once something follows them, so that a doc comment at the end of a
+        // group is not followed by an empty line.
+        if group.state == DocComment {
+            printer.hardbreak();
+        }
+        // Nothing is ever spaced out from what these states were entered by.
+        let tight_after = matches!(
+            group.state,
+            Start | LineStart | DocComment | GenericOpen | RangePrefix | Unary | ClosureOpen
+        );
+        // Whether `tt` continues a metavariable, as in `$x:expr`.
+        let in_metavariable = match group.state {
+            Dollar | DollarIdentColon => true,
+            DollarIdent => matches!(tt, TokenTree::Token(Token { kind: token::Colon, .. }, _)),
+            _ => false,
+        };
+        // Lines are never broken within a metavariable, nor within the opening
+        // of an attribute, as in `#[` or `#![`: `$x` and `:expr` on lines of
+        // their own no longer read as one binding.
+        let glued = in_metavariable
+            || match group.state {
+                Pound => true,
+                PoundBang => matches!(tt, TokenTree::Delimited(_, Delimiter::Bracket, _)),
+                _ => false,
+            };
+        // A metavariable, as in `$x:expr`, or a repetition, as in `$($x:expr),*`,
+        // is a single unit of the `token_limit`, which is never cut short.
+        let continues_unit = in_metavariable || matches!(group.state, DollarParen | DollarParenSep);
+        let counted = group.counted && !continues_unit;
+        if tokens_left == 0 && counted {
+            if !tight_after {
+                printer.space();
+            }
+            printer.word("...");
+            truncated = true;
+            continue;
+        }
+        if counted {
+            tokens_left -= 1;
+        }
+        let starts_repetition =
+            matches!(tt, TokenTree::Token(Token { kind: token::Dollar, .. }, _))
+                && matches!(
+                    group.trees.look_ahead(0),
+                    Some(TokenTree::Delimited(_, Delimiter::Parenthesis, _))
+                );
+        if glued {
+            if !tight_after && needs_space && !follows_joint {
+                printer.nbsp();
+            }
+        } else if group.expanded && starts_repetition && !tight_after {
+            printer.hardbreak();
+        } else if !tight_after && needs_space && !follows_joint {
             printer.space();
+        } else if group.state != Start && next_state == PathSep {
+            // Very long paths may still be wrapped before one of their `::`.
+            printer.zerobreak();
+        }
+        // The braces of a metavariable expression are not padded, unlike the
+        // ones of a block.
+        let follows_dollar = group.state == Dollar;
+        // Inner attributes of a block, as in `{ #![allow(unused)] fn f() {} }`,
+        // are put on lines of their own.
+        let inner_attr = group.state == PoundBang
+            && group.delim == Some(Delimiter::Brace)
+            && matches!(tt, TokenTree::Delimited(_, Delimiter::Bracket, _));
+        group.state = if inner_attr { LineStart } else { next_state };
+        group.joint = matches!(tt, TokenTree::Token(_, Spacing::Joint));
+        group.operand = match tt {
+            // A whole repetition, as in `$($x:expr),*`, takes the place of an
+            // operand.
+            _ if group.state == Repetition => true,
+            // Keywords like `return` or `in` are followed by an operand rather
+            // than ending one.
+            TokenTree::Token(token, _) => {
+                let token = token.uninterpolate();
+                match token.kind {
+                    token::Ident(symbol, is_raw) => {
+                        is_raw
+                            || !usually_needs_space_between_keyword_and_open_delim(
+                                symbol,
+                                token.span,
+                                options.edition,
+                            )
+                    }
+                    _ => matches!(
+                        token.kind,
+                        token::Literal(..) | token::Lifetime(..) | token::Interpolated(..)
+                    ),
+                }
+            }
+            TokenTree::Delimited(..) => true,
+        };
+
+        match tt {
+            TokenTree::Token(token, _) => {
+                if starts_repetition && group.state == Dollar {
+                    printer.ibox(0);
+                    group.repetition = true;
+                }
+                print_token(printer, token);
+                // Each statement of a block and each parameter of a matcher
+                // is in a box of its own, so that lines are broken between
+                // them rather than within them. The separator of a
+                // repetition, as in `$($s:stmt);*`, ends neither.
+                let ends_item = match token.kind {
+                    token::Semi => group.statements,
+                    token::Comma => group.parameters,
+                    _ => false,
+                };
+                if ends_item && group.state != DollarParenSep && group.trees.look_ahead(0).is_some()
+                {
+                    printer.end();
+                    if group.expanded {
+                        printer.hardbreak();
+                    } else {
+                        printer.space();
+                    }
+                    printer.ibox(0);
+                    group.state = LineStart;
+                }
+            }
+            TokenTree::Delimited(_span, delim, tts) => {
+                let open_delim = printer.token_kind_to_string(&token::OpenDelim(*delim));
+                printer.word(open_delim);
+                if tts.is_empty() {
+                    let close_delim = printer.token_kind_to_string(&token::CloseDelim(*delim));
+                    printer.word(close_delim);
+                    if inner_attr {
+                        printer.hardbreak();
+                    }
+                } else {
+                    let padded = *delim == Delimiter::Brace && !follows_dollar;
+                    let statements = padded
+                        && tts.trees().any(|tt| {
+                            matches!(tt, TokenTree::Token(Token { kind: token::Semi, .. }, _))
+                        });
+                    if statements {
+                        printer.cbox(INDENT_UNIT);
+                        printer.space();
+                        printer.ibox(0);
+                    } else if padded {
+                        printer.space();
+                    }
+                    let counted = group.counted && !follows_dollar;
+                    groups.push(Group {
+                        trees: tts.trees(),
+                        delim: Some(*delim),
+                        state: Start,
+                        joint: false,
+                        operand: false,
+                        closure_params: false,
+                        padded,
+                        statements,
+                        parameters: false,
+                        expanded: false,
+                        repetition: false,
+                        hardbreak_after: inner_attr,
+                        angles: Vec::new(),
+                        transparent: false,
+                        counted,
+                    });
+                }
+            }
         }
-        print_tt(printer, tt);
-        state = next_state;
     }
+    truncated
 }
 
-fn usually_needs_space_between_keyword_and_open_delim(symbol: Symbol, span: Span) -> bool {
-    let ident = Ident { name: symbol, span };
-    let is_keyword = ident.is_used_keyword() || ident.is_unused_keyword();
+/// Whether `tree` could be the start of an operand, like an identifier or a
+/// metavariable.
+fn starts_operand(tree: &TokenTree) -> bool {
+    match tree {
+        TokenTree::Token(token, _) => matches!(
+            token.kind,
+            token::Ident(..)
+                | token::Literal(..)
+                | token::Lifetime(..)
+                | token::Interpolated(..)
+                | token::Dollar
+                | token::ModSep
+                | token::Not
+                | token::BinOp(BinOpToken::Minus)
+        ),
+        TokenTree::Delimited(..) => true,
+    }
+}
+
+fn usually_needs_space_between_keyword_and_open_delim(
+    symbol: Symbol,
+    span: Span,
+    edition: Edition,
+) -> bool {
+    // Tokens produced by a macro expansion are keywords or not according to
+    // the edition of that macro, which their span knows. All the others were
+    // written in the crate defining the matcher. Their spans can't tell, since
+    // spans decoded from another crate report the edition of the crate being
+    // documented.
+    let edition = if span.from_expansion() { span.edition() } else { edition };
+    let is_keyword = symbol.is_reserved(|| edition) && !Ident::with_dummy_span(symbol).is_special();
     if !is_keyword {
         // An identifier that is not a keyword usually does not need a space
-        // before an open delim. For example: `f(0)` or `f[0]`.
+        // before an open delim. For example: `f(0)` or `f[0]`. This includes
+        // the weak keywords, like `union` or `default`, which are only
+        // keywords where no open delim can follow them, and are plain
+        // identifiers everywhere else, as in `Default::default()`.
         return false;
     }
+    // Keywords missing from the table get a space, which is what most of them
+    // need, but the tests make sure there aren't any.
+    keyword_needs_space_before_open_delim(symbol).unwrap_or(true)
+}
 
-    match symbol {
+/// Whether the keyword `symbol` is usually followed by a space before an open
+/// delim, or `None` if it is no keyword.
+fn keyword_needs_space_before_open_delim(symbol: Symbol) -> Option<bool> {
+    let needs_space = match symbol {
         // No space after keywords that are syntactically an expression. For
         // example: a tuple struct created with `let _ = Self(0, 0)`, or if
         // someone has `impl Index<MyStruct> for bool` then `true[MyStruct]`.
         kw::False | kw::SelfLower | kw::SelfUpper | kw::True => false,
 
+        // No space, like the path segments they are, as in `super::f()`.
+        kw::Crate | kw::Super => false,
+
         // No space, as in `let _: fn();`
         kw::Fn => false,
 
@@ -202,6 +2798,9 @@ fn usually_needs_space_between_keyword_and_open_delim(symbol: Symbol, span: Span
         // where fut's Output type is `fn()`.
         kw::Await => false,
 
+        // No space, as in `typeof(x)`, the syntax `typeof` is reserved for.
+        kw::Typeof => false,
+
         // Otherwise space after keyword. Some examples:
         //
         // `expr as [T; 2]`
@@ -234,6 +2833,51 @@ fn usually_needs_space_between_keyword_and_open_delim(symbol: Symbol, span: Span
         //       ^
         // `yield [];`
         //       ^
-        _ => true,
-    }
+        // `try { x? }`
+        //     ^
+        kw::As
+        | kw::Break
+        | kw::Const
+        | kw::Continue
+        | kw::Else
+        | kw::Enum
+        | kw::Extern
+        | kw::For
+        | kw::If
+        | kw::Impl
+        | kw::In
+        | kw::Let
+        | kw::Loop
+        | kw::Match
+        | kw::Mod
+        | kw::Move
+        | kw::Mut
+        | kw::Ref
+        | kw::Return
+        | kw::Static
+        | kw::Struct
+        | kw::Trait
+        | kw::Type
+        | kw::Unsafe
+        | kw::Use
+        | kw::Where
+        | kw::While
+        | kw::Abstract
+        | kw::Become
+        | kw::Box
+        | kw::Do
+        | kw::Final
+        | kw::Macro
+        | kw::Override
+        | kw::Priv
+        | kw::Unsized
+        | kw::Virtual
+        | kw::Yield
+        | kw::Async
+        | kw::Dyn
+        | kw::Try => true,
+
+        _ => return None,
+    };
+    Some(needs_space)
 }
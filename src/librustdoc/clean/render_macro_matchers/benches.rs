@@ -0,0 +1,174 @@
+//! Benchmarks of the rendering of macro matchers, on the matchers of a
+//! macro-heavy crate as generated by `macro_heavy_fixture`. They measure the
+//! printer, the comparison of matchers with their reparsed snippets, and
+//! documenting such a crate as a whole, which is the scenario to watch for
+//! regressions in the clean pass.
+
+use super::tests::{lex, DEFAULT_OPTIONS};
+use super::*;
+
+use rustc_span::create_default_session_globals_then;
+
+/// The matchers of a macro-heavy crate, with `arms` arms in all, of the shapes
+/// DSL crates are made of: internal arms munching tokens, function-like arms
+/// with repeated parameters, item definitions and key-value lists.
+fn macro_heavy_fixture(arms: usize) -> Vec<String> {
+    (0..arms)
+        .map(|i| match i % 4 {
+            0 => format!("(@munch{i} [$($done:tt)*] $next:tt $($rest:tt)*)"),
+            1 => format!("(arm{i} $name:ident, $($field:ident: $ty:ty),* $(,)? => $body:block)"),
+            2 => format!(
+                "{{ $(#[$attr:meta])* $vis:vis struct $name:ident<$($lt:lifetime),*> \
+                    where $($bound:tt)+ {{ $($field:ident: $ty:ty,)* }} /* arm{i} */ }}"
+            ),
+            _ => format!("[$($key:expr => $value:expr);+ $(;)? ; arm{i}]"),
+        })
+        .collect()
+}
+
+/// Lexes the matchers of `macro_heavy_fixture`.
+fn lex_fixture(arms: usize) -> Vec<(String, TokenTree)> {
+    macro_heavy_fixture(arms)
+        .into_iter()
+        .map(|src| {
+            let matcher = lex(&src).trees().next().unwrap().clone();
+            (src, matcher)
+        })
+        .collect()
+}
+
+/// Measures how long documenting the matchers of a macro-heavy crate takes,
+/// short of looking their snippets up in the source map: one arm in three is
+/// printed from its tokens, as the matchers that don't match their snippet are,
+/// and the others are reparsed and compared with their tokens, as the ones shown
+/// the way they were written are.
+#[bench]
+fn bench_render_macro_heavy_fixture(b: &mut test::Bencher) {
+    create_default_session_globals_then(|| {
+        let sess = ParseSess::with_silent_emitter(None);
+        let matchers = lex_fixture(3000);
+        let mut printer = Printer::new();
+        b.iter(|| {
+            for (i, (src, matcher)) in matchers.iter().enumerate() {
+                if i % 3 == 0 {
+                    test::black_box(print_matcher(&mut printer, matcher, DEFAULT_OPTIONS));
+                } else {
+                    let reparsed = reparse_snippet(&sess, src.clone()).unwrap();
+                    assert!(trees_eq_unspanned(&reparsed, matcher));
+                }
+            }
+        })
+    })
+}
+
+/// Measures how long printing the matchers of a macro-heavy crate from their
+/// tokens takes, as with `--normalize-macro-matchers`.
+#[bench]
+fn bench_print_macro_heavy_fixture(b: &mut test::Bencher) {
+    create_default_session_globals_then(|| {
+        let matchers = lex_fixture(3000);
+        let mut printer = Printer::new();
+        b.iter(|| {
+            for (_, matcher) in &matchers {
+                test::black_box(print_matcher(&mut printer, matcher, DEFAULT_OPTIONS));
+            }
+        })
+    })
+}
+
+/// The matchers of a macro with a thousand arms.
+fn thousand_arms() -> Vec<TokenTree> {
+    (0..1000)
+        .map(|i| {
+            let fields = "$($field:ident: $ty:ty),* $(,)?";
+            let src = format!("(@arm{i} $name:ident, {fields} => $body:block)");
+            lex(&src).trees().next().unwrap().clone()
+        })
+        .collect()
+}
+
+/// Measures how long printing the arms of a macro takes with a single printer,
+/// as `render_rules` does.
+#[bench]
+fn bench_print_thousand_arms(b: &mut test::Bencher) {
+    create_default_session_globals_then(|| {
+        let matchers = thousand_arms();
+        let mut printer = Printer::new();
+        b.iter(|| {
+            for matcher in &matchers {
+                test::black_box(print_matcher(&mut printer, matcher, DEFAULT_OPTIONS));
+            }
+        })
+    })
+}
+
+/// Like `bench_print_thousand_arms`, but with a new printer for every arm, to
+/// tell how much reusing one saves.
+#[bench]
+fn bench_print_thousand_arms_fresh_printers(b: &mut test::Bencher) {
+    create_default_session_globals_then(|| {
+        let matchers = thousand_arms();
+        b.iter(|| {
+            for matcher in &matchers {
+                test::black_box(print_matcher(&mut Printer::new(), matcher, DEFAULT_OPTIONS));
+            }
+        })
+    })
+}
+
+/// Measures how long comparing the matchers of a macro-heavy crate with their
+/// reparsed snippets takes, as `snippet_equal_to_token` does.
+#[bench]
+fn bench_compare_macro_heavy_fixture(b: &mut test::Bencher) {
+    create_default_session_globals_then(|| {
+        let sess = ParseSess::with_silent_emitter(None);
+        let matchers = lex_fixture(3000);
+        let reparsed: Vec<_> =
+            matchers.iter().map(|(src, _)| reparse_snippet(&sess, src.clone()).unwrap()).collect();
+        b.iter(|| {
+            for ((_, matcher), reparsed) in iter::zip(&matchers, &reparsed) {
+                assert!(trees_eq_unspanned(reparsed, matcher));
+            }
+        })
+    })
+}
+
+/// The source of a crate documenting the matchers of `macro_heavy_fixture`,
+/// 50 arms to a macro. One macro in three is generated by another one, so that
+/// the matchers are printed from their tokens instead of shown as written.
+fn macro_heavy_crate(arms: usize) -> String {
+    let mut src = String::from("#![feature(no_core)]\n#![no_core]\n");
+    for (i, arms) in macro_heavy_fixture(arms).chunks(50).enumerate() {
+        if i % 3 == 0 {
+            // `$d` stands for the `$` of the generated macro.
+            let arms: String =
+                arms.iter().map(|arm| format!("{} => {{}};\n", arm.replace('$', "$d "))).collect();
+            src.push_str(&format!(
+                "macro_rules! generate_m{i} {{\n($d:tt) => {{\n\
+                    /// A generated macro.\n#[macro_export]\nmacro_rules! m{i} {{\n{arms}}}\n\
+                 }};\n}}\ngenerate_m{i}!($);\n"
+            ));
+        } else {
+            let arms: String = arms.iter().map(|arm| format!("    {arm} => {{}};\n")).collect();
+            src.push_str(&format!(
+                "/// A macro.\n#[macro_export]\nmacro_rules! m{i} {{\n{arms}}}\n"
+            ));
+        }
+    }
+    src
+}
+
+/// Measures how long documenting a macro-heavy crate takes, from its parse to
+/// the end of the clean pass: the matchers of its macros are rendered there, by
+/// looking their snippets up and comparing them with their tokens, or by
+/// printing their tokens when the macros are generated. Nothing is rendered to
+/// HTML, as with `--check`.
+#[bench]
+fn bench_document_macro_heavy_crate(b: &mut test::Bencher) {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("macro_heavy.rs");
+    std::fs::write(&input, macro_heavy_crate(3000)).unwrap();
+    let args = ["rustdoc", "-Zunstable-options", "--check", input.to_str().unwrap()];
+    let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+    b.iter(|| assert!(crate::main_args(&args).is_ok()))
+}
@@ -0,0 +1,2727 @@
+use super::*;
+
+use expect_test::{expect, Expect};
+use rustc_ast::tokenstream::DelimSpan;
+use rustc_errors::FatalError;
+use rustc_parse::parse_stream_from_source_str;
+use rustc_span::{create_default_session_globals_then, edition::Edition, DUMMY_SP};
+
+use crate::config::MacroMatcherStyle;
+
+pub(super) const DEFAULT_OPTIONS: PrintOptions = PrintOptions {
+    width: 78,
+    style: MacroMatcherStyle::Compact,
+    token_limit: usize::MAX,
+    edition: Edition::Edition2015,
+    part: RulePart::Matcher,
+    single_line: false,
+};
+
+fn ident(name: &str) -> TokenTree {
+    TokenTree::token_alone(token::Ident(Symbol::intern(name), false), DUMMY_SP)
+}
+
+fn delimited(delim: Delimiter, trees: Vec<TokenTree>) -> TokenTree {
+    TokenTree::Delimited(DelimSpan::dummy(), delim, TokenStream::new(trees))
+}
+
+#[test]
+fn dummy_spans_have_no_snippet() {
+    create_default_session_globals_then(|| {
+        assert!(!may_have_snippet(DUMMY_SP));
+        assert!(!may_have_snippet(Span::with_root_ctxt(BytePos(8), BytePos(8))));
+        assert!(may_have_snippet(Span::with_root_ctxt(BytePos(8), BytePos(10))));
+    })
+}
+
+#[test]
+fn empty_matcher_with_dummy_span() {
+    create_default_session_globals_then(|| {
+        let print = |delim| {
+            print_matcher(&mut Printer::new(), &delimited(delim, vec![]), DEFAULT_OPTIONS).text
+        };
+        assert_eq!(print(Delimiter::Parenthesis), "()");
+        assert_eq!(print(Delimiter::Brace), "{}");
+    })
+}
+
+#[test]
+fn matcher_with_dummy_span() {
+    create_default_session_globals_then(|| {
+        let matcher = delimited(
+            Delimiter::Bracket,
+            vec![
+                TokenTree::token_alone(token::Dollar, DUMMY_SP),
+                ident("x"),
+                TokenTree::token_alone(token::Colon, DUMMY_SP),
+                ident("ident"),
+            ],
+        );
+        let printed = print_matcher(&mut Printer::new(), &matcher, DEFAULT_OPTIONS);
+        assert_eq!(printed.text, "[$x:ident]");
+    })
+}
+
+#[test]
+fn print_matcher_delimiters() {
+    create_default_session_globals_then(|| {
+        let print = |delim, src, options| {
+            let matcher = TokenTree::Delimited(DelimSpan::dummy(), delim, lex(src));
+            print_matcher(&mut Printer::new(), &matcher, options).text
+        };
+        assert_eq!(print(Delimiter::Parenthesis, "$x:expr", DEFAULT_OPTIONS), "($x:expr)");
+        assert_eq!(print(Delimiter::Bracket, "$x:expr", DEFAULT_OPTIONS), "[$x:expr]");
+        assert_eq!(print(Delimiter::Brace, "$x:expr", DEFAULT_OPTIONS), "{ $x:expr }");
+        let narrow = PrintOptions { width: 40, ..DEFAULT_OPTIONS };
+        expect![[r#"
+            {
+                    $a:expr, $b:expr, $c:expr,
+                    $d:expr, $e:expr
+                }"#]]
+        .assert_eq(&print(Delimiter::Brace, "$a:expr, $b:expr, $c:expr, $d:expr, $e:expr", narrow));
+    })
+}
+
+#[test]
+fn normalize_line_endings_keeps_literals() {
+    assert_eq!(normalize_line_endings("($a:expr ,\r\n $b:expr)\r"), "($a:expr ,\n $b:expr)\n");
+    assert_eq!(
+        normalize_line_endings("(\r\n r\"a\r\nb\" \"c\rd\" /* e\r */\r\n)"),
+        "(\n r\"a\r\nb\" \"c\rd\" /* e\r */\n)",
+    );
+}
+
+#[test]
+fn trim_trailing_whitespace_keeps_literals() {
+    assert_eq!(trim_trailing_whitespace("($a:expr ,  \n $b:expr)\t"), "($a:expr ,\n $b:expr)");
+    assert_eq!(
+        trim_trailing_whitespace("(\"a  \nb\"  \nr#\"c \t\n\"# /* d  \n */)"),
+        "(\"a  \nb\"\nr#\"c \t\n\"# /* d\n */)",
+    );
+}
+
+#[test]
+fn expand_tabs_keeps_literals() {
+    assert_eq!(expand_tabs("($a:expr,\t$b:expr)", 0, 4, &[]), "($a:expr,   $b:expr)");
+    let text = "(\"\t\"\t$a:expr)";
+    assert_eq!(expand_tabs(text, 0, 4, &literal_ranges(text)), "(\"\t\"   $a:expr)");
+}
+
+#[test]
+fn reindent_snippet_keeps_literals() {
+    assert_eq!(
+        reindent_snippet("(\n            \"a\n  b\"\n            $a:expr)", Some("        ")),
+        "(\n        \"a\n  b\"\n        $a:expr)",
+    );
+    assert_eq!(
+        reindent_snippet("(\n            $a:expr\n            r\"\n\n\")", Some("        ")),
+        "(\n        $a:expr\n        r\"\n\n\")",
+    );
+}
+
+/// Checks how the tokens lexed from `src` are pretty-printed as the inside of
+/// a matcher.
+fn check_printed(src: &str, expect: Expect) {
+    check_printed_with(src, DEFAULT_OPTIONS, expect)
+}
+
+/// Checks how the tokens lexed from `src` are pretty-printed as the inside of
+/// a matcher with the given `options`.
+fn check_printed_with(src: &str, options: PrintOptions, expect: Expect) {
+    create_default_session_globals_then(|| {
+        expect.assert_eq(&print_matcher_tokens(&lex(src), options).text);
+    })
+}
+
+pub(super) fn lex(src: &str) -> TokenStream {
+    let sess = ParseSess::with_silent_emitter(None);
+    let file_name = FileName::anon_source_code(src);
+    parse_stream_from_source_str(file_name, src.to_owned(), &sess, None)
+}
+
+#[test]
+fn print_fragments() {
+    check_printed("$name:ident $body:block", expect!["($name:ident $body:block)"]);
+    check_printed("$a:expr, $b:expr; $c:tt", expect!["($a:expr, $b:expr; $c:tt)"]);
+    check_printed("$a:ident >>= $b:expr", expect!["($a:ident >>= $b:expr)"]);
+}
+
+#[test]
+fn print_repetitions() {
+    check_printed("$($x:expr),*", expect!["($($x:expr),*)"]);
+    check_printed("$($x:tt)+", expect!["($($x:tt)+)"]);
+    check_printed("$($x:ident)?", expect!["($($x:ident)?)"]);
+    check_printed("$($x:expr)|+", expect!["($($x:expr)|+)"]);
+    check_printed("$($x:expr),?", expect!["($($x:expr),?)"]);
+    check_printed("$($x:expr),+ $(,)?", expect!["($($x:expr),+ $(,)?)"]);
+    check_printed("$(,)? => $x:tt", expect!["($(,)? => $x:tt)"]);
+    check_printed("$(,)?, $x:tt", expect!["($(,)?, $x:tt)"]);
+    check_printed("$($k:ident => $v:expr);* $(;)?", expect!["($($k:ident => $v:expr);* $(;)?)"]);
+}
+
+#[test]
+fn print_attributes() {
+    check_printed("#[$m:meta] struct $name:ident", expect!["(#[$m:meta] struct $name:ident)"]);
+    check_printed("#![doc = $doc:expr]", expect!["(#![doc = $doc:expr])"]);
+}
+
+#[test]
+fn print_pound_markers() {
+    check_printed("# $rest:tt", expect!["(# $rest:tt)"]);
+    check_printed("# #", expect!["(# #)"]);
+    check_printed("# ($x:tt)", expect!["(# ($x:tt))"]);
+    check_printed("#[$m:meta] # $x:tt #", expect!["(#[$m:meta] # $x:tt #)"]);
+}
+
+#[test]
+fn print_doc_comments() {
+    check_printed(
+        "/// Docs\n$x:ident",
+        expect![[r#"
+            (
+                    /// Docs
+                    $x:ident
+                )"#]],
+    );
+}
+
+#[test]
+fn print_trailing_doc_comments() {
+    check_printed(
+        "$x:ident /// Docs",
+        expect![[r#"
+            (
+                    $x:ident /// Docs
+                )"#]],
+    );
+    check_printed(
+        "{ $x:ident /// Docs } $y:ident",
+        expect![[r#"
+            (
+                    { $x:ident /// Docs
+                    } $y:ident
+                )"#]],
+    );
+}
+
+#[test]
+fn print_doc_comment_kinds() {
+    check_printed(
+        "//! Inner\n/*! Inner block */ /** Outer block */ $x:ident",
+        expect![[r#"
+            (
+                    //! Inner
+                    /*! Inner block */
+                    /** Outer block */
+                    $x:ident
+                )"#]],
+    );
+    check_printed(
+        "/**\n * Line one\n * Line two\n */\n$x:ident",
+        expect![[r#"
+            (
+                    /**
+                     * Line one
+                     * Line two
+                     */
+                    $x:ident
+                )"#]],
+    );
+}
+
+#[test]
+fn print_inner_attributes() {
+    check_printed(
+        "{ #![allow(dead_code)] #![allow(unused)] fn $name:ident() {} }",
+        expect![[r#"
+            (
+                    { #![allow(dead_code)]
+                    #![allow(unused)]
+                    fn $name:ident () {} }
+                )"#]],
+    );
+    check_printed("#![no_std] $x:tt", expect!["(#![no_std] $x:tt)"]);
+}
+
+#[test]
+fn print_statements() {
+    check_printed(
+        "{ $a:ident; $b:ident; $c:ident; }",
+        expect!["({ $a:ident; $b:ident; $c:ident; })"],
+    );
+    check_printed("{ $($s:stmt);* }", expect!["({ $($s:stmt);* })"]);
+    check_printed(
+        "{ let $first:ident = $x:expr; let $second:ident = $y:expr; let $third:ident = $z:expr; }",
+        expect![[r#"
+            (
+                    {
+                        let $first:ident = $x:expr;
+                        let $second:ident = $y:expr;
+                        let $third:ident = $z:expr;
+                    }
+                )"#]],
+    );
+    // Only `;` directly inside of braces start new lines.
+    check_printed(
+        "($first:ident; $second:ident; $third:ident; $fourth:ident; $fifth:ident; $sixth:ident)",
+        expect![[r#"
+            (
+                    ($first:ident; $second:ident; $third:ident; $fourth:ident;
+                    $fifth:ident; $sixth:ident)
+                )"#]],
+    );
+}
+
+#[test]
+fn print_paths() {
+    check_printed("std::marker::PhantomData", expect!["(std::marker::PhantomData)"]);
+    check_printed("::core::option::Option", expect!["(::core::option::Option)"]);
+    check_printed("$t:ty = ::core::option::Option", expect!["($t:ty = ::core::option::Option)"]);
+    check_printed("super::f(crate::x)", expect!["(super::f(crate::x))"]);
+    check_printed("[$a:ident: std::vec::Vec]", expect!["([$a:ident : std::vec::Vec])"]);
+    check_printed("$a:ident::$b:ident", expect!["($a:ident ::$b:ident)"]);
+    check_printed("use $m:ident::*", expect!["(use $m:ident ::*)"]);
+    // Only what continues a path is tight against a `::`.
+    check_printed("@ :: >>= 'static", expect!["(@ :: >>= 'static)"]);
+}
+
+#[test]
+fn print_dots() {
+    check_printed("self.field", expect!["(self.field)"]);
+    check_printed("self . 0", expect!["(self.0)"]);
+    check_printed("$x.len()", expect!["($x.len())"]);
+    check_printed("a.b().c.await", expect!["(a.b().c.await)"]);
+    check_printed("x.0.1", expect!["(x.0.1)"]);
+    check_printed("1. foo", expect!["(1. foo)"]);
+    check_printed("$a:expr .. $b:expr", expect!["($a:expr .. $b:expr)"]);
+}
+
+#[test]
+fn print_ranges() {
+    check_printed("$lo:expr .. $hi:expr", expect!["($lo:expr .. $hi:expr)"]);
+    check_printed("$lo:tt ..= $hi:tt", expect!["($lo:tt ..= $hi:tt)"]);
+    check_printed("0 ... 9", expect!["(0 ... 9)"]);
+    check_printed(".. $hi:expr", expect!["(..$hi:expr)"]);
+    check_printed("= ..= $hi:expr", expect!["(= ..=$hi:expr)"]);
+    check_printed("x ..", expect!["(x..)"]);
+    check_printed("x .., y", expect!["(x.., y)"]);
+    check_printed("Foo { a, .. }", expect!["(Foo { a, .. })"]);
+    check_printed("[a, ..]", expect!["([a, ..])"]);
+    check_printed("(..)", expect!["((..))"]);
+}
+
+#[test]
+fn print_negations() {
+    check_printed("-1", expect!["(-1)"]);
+    check_printed("(-1)", expect!["((-1))"]);
+    check_printed("[-1, -2]", expect!["([-1, -2])"]);
+    check_printed("-$x", expect!["(-$x)"]);
+    check_printed("= -42", expect!["(= -42)"]);
+    check_printed("=> - 1", expect!["(=> -1)"]);
+    check_printed("return -1", expect!["(return -1)"]);
+    check_printed("x - 1", expect!["(x - 1)"]);
+    check_printed("$a - $b", expect!["($a - $b)"]);
+}
+
+#[test]
+fn print_raw_identifiers() {
+    check_printed("r#match($x:expr)", expect!["(r#match($x:expr))"]);
+    check_printed("r#fn [$i:expr]", expect!["(r#fn[$i:expr])"]);
+    check_printed("r#try!($e:expr)", expect!["(r#try!($e:expr))"]);
+    check_printed("match ($x:expr)", expect!["(match ($x:expr))"]);
+}
+
+#[test]
+fn print_macro_calls() {
+    check_printed("vec![$($x:expr),*]", expect!["(vec![$($x:expr),*])"]);
+    check_printed("vec ! [ $($x:expr),* ]", expect!["(vec![$($x:expr),*])"]);
+    check_printed("concat!($a:expr)", expect!["(concat!($a:expr))"]);
+    check_printed("thread_local! { $t:tt }", expect!["(thread_local! { $t:tt })"]);
+    check_printed("$crate::private::mac!($x:tt)", expect!["($crate::private::mac!($x:tt))"]);
+    check_printed("$m!()", expect!["($m!())"]);
+}
+
+#[test]
+fn print_dollar_crate() {
+    check_printed("$crate::path::to::item", expect!["($crate::path::to::item)"]);
+    check_printed("$crate :: $name", expect!["($crate::$name)"]);
+    check_printed("$crate::f($x:expr)", expect!["($crate::f($x:expr))"]);
+    check_printed("($crate::internal::helper)", expect!["(($crate::internal::helper))"]);
+}
+
+#[test]
+fn print_dollar_crate_ident() {
+    // Macro-generated matchers have `$crate` as a single identifier.
+    let path_sep = TokenTree::token_alone(token::ModSep, DUMMY_SP);
+    let trees = vec![ident("$crate"), path_sep, ident("item")];
+    check_printed_trees(trees, expect!["($crate::item)"]);
+}
+
+#[test]
+fn print_metavariable_expressions() {
+    check_printed("$$", expect!["($$)"]);
+    check_printed("$$x", expect!["($$x)"]);
+    check_printed("${count($x)}", expect!["(${count($x)})"]);
+    check_printed("${count(x, 1)}", expect!["(${count(x, 1)})"]);
+    check_printed("${index()}", expect!["(${index()})"]);
+    check_printed("${ignore($x)}", expect!["(${ignore($x)})"]);
+    check_printed("$($${ignore($x)})*", expect!["($($${ignore($x)})*)"]);
+    check_printed("{ $x:tt }", expect!["({ $x:tt })"]);
+}
+
+#[test]
+fn print_references() {
+    check_printed("&'a $t:ty", expect!["(&'a $t:ty)"]);
+    check_printed("&'a mut $t:ty", expect!["(&'a mut $t:ty)"]);
+    check_printed("&mut $t:ty", expect!["(&mut $t:ty)"]);
+    check_printed("&&'a str", expect!["(&&'a str)"]);
+    check_printed("&'_ str", expect!["(&'_ str)"]);
+    check_printed("&'static str", expect!["(&'static str)"]);
+}
+
+#[test]
+fn print_lifetime_generics() {
+    check_printed("impl<$lt:lifetime> Trait<$lt>", expect!["(impl<$lt:lifetime> Trait<$lt>)"]);
+    check_printed("for<'a> Fn(&'a $t:ty)", expect!["(for<'a> Fn(&'a $t:ty))"]);
+    check_printed("Foo<'a, $t:ty + 'a>", expect!["(Foo<'a, $t:ty + 'a>)"]);
+    check_printed("$t:ty + 'a", expect!["($t:ty + 'a)"]);
+    check_printed("$a:expr < $b:expr", expect!["($a:expr < $b:expr)"]);
+}
+
+#[test]
+fn print_generics() {
+    check_printed("Option < $t:ty >", expect!["(Option<$t:ty>)"]);
+    check_printed("HashMap < K , V >", expect!["(HashMap<K, V>)"]);
+    check_printed("Vec<Vec<$t:ty>>", expect!["(Vec<Vec<$t:ty>>)"]);
+    check_printed("Box < dyn Fn ( $($a:ty),* ) >", expect!["(Box<dyn Fn($($a:ty),*)>)"]);
+    check_printed("Vec<$t:ty>::new", expect!["(Vec<$t:ty>::new)"]);
+    check_printed("HashMap<Vec<K>, V>", expect!["(HashMap<Vec<K>, V>)"]);
+    check_printed("$a < $b", expect!["($a < $b)"]);
+    check_printed("$a:expr < $b:expr > $c:expr", expect!["($a:expr < $b:expr > $c:expr)"]);
+}
+
+#[test]
+fn print_turbofish() {
+    check_printed("Vec :: < $t:ty > :: new ( )", expect!["(Vec::<$t:ty>::new())"]);
+    check_printed("$it.collect::<Vec<_>>()", expect!["($it.collect::<Vec<_>>())"]);
+    check_printed("size_of::<$t>()", expect!["(size_of::<$t>())"]);
+    check_printed("f::<'a, Option<$t:ty>>", expect!["(f::<'a, Option<$t:ty>>)"]);
+    check_printed("fn f<T>(x: T)", expect!["(fn f<T>(x: T))"]);
+}
+
+#[test]
+fn print_colons() {
+    check_printed("'outer: loop { break 'outer; }", expect!["('outer: loop { break 'outer; })"]);
+    check_printed("where T : 'a + Send", expect!["(where T: 'a + Send)"]);
+    check_printed(
+        "$l:lifetime 'outer: loop {} where T: 'a + Send",
+        expect!["($l:lifetime 'outer: loop {} where T: 'a + Send)"],
+    );
+}
+
+#[test]
+fn print_closures() {
+    check_printed("| x | x + 1", expect!["(|x| x + 1)"]);
+    check_printed("|| $body:expr", expect!["(|| $body:expr)"]);
+    check_printed("move |x, y| x", expect!["(move |x, y| x)"]);
+    check_printed(
+        "$f:expr => |$($arg:ident),*| $body:expr",
+        expect!["($f:expr => |$($arg:ident),*| $body:expr)"],
+    );
+    check_printed("Some(_) | None", expect!["(Some(_) | None)"]);
+    check_printed("$a | $b", expect!["($a | $b)"]);
+}
+
+#[test]
+fn print_raw_pointers() {
+    check_printed(
+        "p: * const $t:ty, q: *mut u8, $a * $b, $($x:tt)*",
+        expect!["(p: *const $t:ty, q: *mut u8, $a * $b, $($x:tt)*)"],
+    );
+}
+
+#[test]
+fn print_unary_operators() {
+    check_printed("! $cond:expr", expect!["(!$cond:expr)"]);
+    check_printed("* $ptr", expect!["(*$ptr)"]);
+    check_printed("& $val", expect!["(&$val)"]);
+    check_printed("&& $val", expect!["(&&$val)"]);
+    check_printed("- $x", expect!["(-$x)"]);
+    check_printed("= * $p", expect!["(= *$p)"]);
+    check_printed("(* $p, & * $q)", expect!["((*$p, &*$q))"]);
+    check_printed("$a && ! $b", expect!["($a && !$b)"]);
+}
+
+#[test]
+fn print_question_marks() {
+    check_printed("impl<T: ?Sized + 'a> $(,)?", expect!["(impl<T: ?Sized + 'a> $(,)?)"]);
+    check_printed("$t:ident : ? Sized", expect!["($t:ident : ?Sized)"]);
+    check_printed("$($x:expr),? $e ?", expect!["($($x:expr),? $e?)"]);
+    check_printed("f()?", expect!["(f()?)"]);
+}
+
+#[test]
+fn print_binary_operators() {
+    check_printed("$a * $b", expect!["($a * $b)"]);
+    check_printed("$a & $b", expect!["($a & $b)"]);
+    check_printed("$a && $b", expect!["($a && $b)"]);
+    check_printed("$a - $b", expect!["($a - $b)"]);
+    check_printed("$($x:expr),* - $y", expect!["($($x:expr),* - $y)"]);
+    check_printed("$(@)+ * $(@)* +", expect!["($(@)+ * $(@)* +)"]);
+}
+
+#[test]
+fn print_nested_delimiters() {
+    check_printed("[$a:tt { $b:tt ($c:tt) }]", expect!["([$a:tt { $b:tt ($c:tt) }])"]);
+    check_printed("() [] {}", expect!["(() [] {})"]);
+    check_printed("f($x:expr)", expect!["(f($x:expr))"]);
+    check_printed("return ($x:expr)", expect!["(return ($x:expr))"]);
+}
+
+#[test]
+fn print_deeply_nested_matcher() {
+    // Machine-generated matchers can nest delimiters deeper than the printer
+    // could recurse into them.
+    create_default_session_globals_then(|| {
+        let mut matcher = ident("x");
+        for _ in 0..10_000 {
+            matcher = delimited(Delimiter::Parenthesis, vec![matcher]);
+        }
+        let options = PrintOptions { token_limit: 100, ..DEFAULT_OPTIONS };
+        let printed = print_matcher(&mut Printer::new(), &matcher, options);
+        assert!(printed.truncated);
+        assert!(printed.text.contains(&format!("{}...{}", "(".repeat(100), ")".repeat(100))));
+        // Dropping the matcher recurses as deep as it is nested, which is not
+        // what this test is about.
+        mem::forget(matcher);
+    })
+}
+
+/// All the symbols that are keywords in the 2021 edition.
+fn keywords() -> Vec<Symbol> {
+    (0..)
+        .map(Symbol::new_from_decoded)
+        .take_while(|symbol| symbol.is_preinterned())
+        .filter(|&symbol| {
+            symbol.is_reserved(|| Edition::Edition2021)
+                && !Ident::with_dummy_span(symbol).is_special()
+        })
+        .collect()
+}
+
+#[test]
+fn every_keyword_is_in_spacing_table() {
+    create_default_session_globals_then(|| {
+        let missing: Vec<_> = keywords()
+            .into_iter()
+            .filter(|&symbol| keyword_needs_space_before_open_delim(symbol).is_none())
+            .collect();
+        assert!(missing.is_empty(), "keywords missing from the spacing table: {missing:?}");
+    })
+}
+
+#[test]
+fn print_keywords_before_delimiters() {
+    create_default_session_globals_then(|| {
+        let options = PrintOptions { edition: Edition::Edition2021, ..DEFAULT_OPTIONS };
+        let printed: Vec<_> = keywords()
+            .into_iter()
+            .map(|keyword| {
+                print_matcher_tokens(
+                    &lex(&format!("{keyword}() {keyword}[] {keyword}{{}}")),
+                    options,
+                )
+                .text
+            })
+            .collect();
+        expect![[r#"
+            (as () as [] as {})
+            (break () break [] break {})
+            (const () const [] const {})
+            (continue () continue [] continue {})
+            (crate() crate[] crate {})
+            (else () else [] else {})
+            (enum () enum [] enum {})
+            (extern () extern [] extern {})
+            (false() false[] false {})
+            (fn() fn[] fn {})
+            (for () for [] for {})
+            (if () if [] if {})
+            (impl () impl [] impl {})
+            (in () in [] in {})
+            (let () let [] let {})
+            (loop () loop [] loop {})
+            (match () match [] match {})
+            (mod () mod [] mod {})
+            (move () move [] move {})
+            (mut () mut [] mut {})
+            (pub() pub[] pub {})
+            (ref () ref [] ref {})
+            (return () return [] return {})
+            (self() self[] self {})
+            (Self() Self[] Self {})
+            (static () static [] static {})
+            (struct () struct [] struct {})
+            (super() super[] super {})
+            (trait () trait [] trait {})
+            (true() true[] true {})
+            (type () type [] type {})
+            (unsafe () unsafe [] unsafe {})
+            (use () use [] use {})
+            (where () where [] where {})
+            (while () while [] while {})
+            (abstract () abstract [] abstract {})
+            (become () become [] become {})
+            (box () box [] box {})
+            (do () do [] do {})
+            (final () final [] final {})
+            (macro () macro [] macro {})
+            (override () override [] override {})
+            (priv () priv [] priv {})
+            (typeof() typeof[] typeof {})
+            (unsized () unsized [] unsized {})
+            (virtual () virtual [] virtual {})
+            (yield () yield [] yield {})
+            (async () async [] async {})
+            (await() await[] await {})
+            (dyn () dyn [] dyn {})
+            (try () try [] try {})"#]]
+        .assert_eq(&printed.join("\n"));
+    })
+}
+
+#[test]
+fn print_edition_dependent_keywords() {
+    let src = "async($a:ident) async { $b:expr } dyn($t:ty) try($e:expr)";
+    check_printed_with(
+        src,
+        PrintOptions { edition: Edition::Edition2015, ..DEFAULT_OPTIONS },
+        expect!["(async($a:ident) async { $b:expr } dyn($t:ty) try($e:expr))"],
+    );
+    check_printed_with(
+        src,
+        PrintOptions { edition: Edition::Edition2018, ..DEFAULT_OPTIONS },
+        expect!["(async ($a:ident) async { $b:expr } dyn ($t:ty) try ($e:expr))"],
+    );
+}
+
+#[test]
+fn print_long_matcher() {
+    check_printed(
+        "<= 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 =>",
+        expect![[r#"
+            (
+                    <= 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25
+                    26 27 28 =>
+                )"#]],
+    );
+}
+
+#[test]
+fn print_nested_statements() {
+    check_printed(
+        "{ let $first:ident = $a:expr; \
+         { let $second:ident = $($b:expr),*; let $third:ident = $third_value:expr; } }",
+        expect![[r#"
+            (
+                    {
+                        let $first:ident = $a:expr;
+                        {
+                            let $second:ident = $($b:expr),*;
+                            let $third:ident = $third_value:expr;
+                        }
+                    }
+                )"#]],
+    );
+}
+
+#[test]
+fn print_long_parameters() {
+    check_printed(
+        "$first:ident => $a:expr, $second:ident => $b:expr, $third:ident => $c:expr, \
+         $fourth:ident => $d:expr",
+        expect![[r#"
+            (
+                    $first:ident => $a:expr, $second:ident => $b:expr,
+                    $third:ident => $c:expr, $fourth:ident => $d:expr
+                )"#]],
+    );
+    check_printed(
+        "$name:ident => $value:expr, ($first:ident => $a:expr, $second:ident => $b:expr), \
+         $third:ident => $c:expr",
+        expect![[r#"
+            (
+                    $name:ident => $value:expr,
+                    ($first:ident => $a:expr, $second:ident => $b:expr),
+                    $third:ident => $c:expr
+                )"#]],
+    );
+}
+
+#[test]
+fn print_long_repetitions() {
+    check_printed(
+        "$($first_key:ident => $first_value:expr),* $($second_key:ident => $second_value:expr);* \
+         $($third_key:ident => $third_value:expr)|*",
+        expect![[r#"
+            (
+                    $($first_key:ident => $first_value:expr),*
+                    $($second_key:ident => $second_value:expr);*
+                    $($third_key:ident => $third_value:expr)|*
+                )"#]],
+    );
+}
+
+#[test]
+fn print_with_width() {
+    let src = "$first:ident => $a:expr, $second:ident => $b:expr";
+    check_printed(src, expect!["($first:ident => $a:expr, $second:ident => $b:expr)"]);
+    check_printed_with(
+        src,
+        PrintOptions { width: 40, ..DEFAULT_OPTIONS },
+        expect![[r#"
+            (
+                    $first:ident => $a:expr,
+                    $second:ident => $b:expr
+                )"#]],
+    );
+}
+
+#[test]
+fn print_with_width_keeps_binders_whole() {
+    let narrow = PrintOptions { width: 20, ..DEFAULT_OPTIONS };
+    // `$second:` used to end the second line, right where the matcher is as
+    // wide as it can be.
+    check_printed_with(
+        "$first:ident $second:$frag",
+        narrow,
+        expect![[r#"
+            (
+                    $first:ident
+                    $second:$frag
+                )"#]],
+    );
+    check_printed_with(
+        "$first:ident #[$meta:meta] #![$inner:meta]",
+        narrow,
+        expect![[r#"
+            (
+                    $first:ident
+                    #[$meta:meta]
+                    #![$inner:meta]
+                )"#]],
+    );
+}
+
+/// Every state `print_tts` leaves a group in.
+const STATES: [State; 26] = {
+    use State as S;
+    [
+        S::Start,
+        S::Dollar,
+        S::DollarIdent,
+        S::DollarIdentColon,
+        S::DollarParen,
+        S::DollarParenSep,
+        S::Pound,
+        S::PoundBang,
+        S::Ampersand,
+        S::GenericKeyword,
+        S::GenericOpen,
+        S::GenericClose,
+        S::PathSegment,
+        S::PathSep,
+        S::Dot,
+        S::RangePrefix,
+        S::Unary,
+        S::Repetition,
+        S::LineStart,
+        S::DocComment,
+        S::ClosureOpen,
+        S::Star,
+        S::MacroBang,
+        S::Lifetime,
+        S::Ident,
+        S::Other,
+    ]
+};
+
+/// A token of every class, with every value of the fields of the classes that
+/// have some.
+const TOKEN_CLASSES: [TokenClass; 43] = {
+    use IdentClass as I;
+    use TokenClass as T;
+    [
+        T::DocComment,
+        T::Ident(I::DollarCrate),
+        T::Ident(I::Crate),
+        T::Ident(I::Super),
+        T::Ident(I::Mut),
+        T::Ident(I::Const),
+        T::Ident(I::GenericKeyword),
+        T::Ident(I::Name),
+        T::Ident(I::Keyword),
+        T::Lifetime,
+        T::Colon,
+        T::ModSep,
+        T::Dot,
+        T::RangeStart,
+        T::RangeBetween,
+        T::RangeEnd,
+        T::ClosureOpen,
+        T::ClosureClose,
+        T::Or,
+        T::Prefix,
+        T::PrefixStar,
+        T::PrefixNot,
+        T::PrefixQuestion,
+        T::Not,
+        T::Star,
+        T::And,
+        T::Question,
+        T::Plus,
+        T::Lt,
+        T::LtInGenerics,
+        T::Gt,
+        T::GtClosing { tight: true },
+        T::GtClosing { tight: false },
+        T::Shr,
+        T::ShrClosing { tight: true },
+        T::ShrClosing { tight: false },
+        T::Separator,
+        T::Dollar,
+        T::AttrPound,
+        T::Pound,
+        T::Interpolated { path: true },
+        T::Interpolated { path: false },
+        T::Other,
+    ]
+};
+
+/// Has no wildcard, so that a state or class that is added cannot be left out of
+/// `STATES` or `TOKEN_CLASSES` without noticing.
+#[allow(dead_code)]
+fn all_listed(state: State, class: TokenClass, ident: IdentClass) {
+    use IdentClass as I;
+    use State as S;
+    use TokenClass as T;
+    match state {
+        S::Start
+        | S::Dollar
+        | S::DollarIdent
+        | S::DollarIdentColon
+        | S::DollarParen
+        | S::DollarParenSep
+        | S::Pound
+        | S::PoundBang
+        | S::Ampersand
+        | S::GenericKeyword
+        | S::GenericOpen
+        | S::GenericClose
+        | S::PathSegment
+        | S::PathSep
+        | S::Dot
+        | S::RangePrefix
+        | S::Unary
+        | S::Repetition
+        | S::LineStart
+        | S::DocComment
+        | S::ClosureOpen
+        | S::Star
+        | S::MacroBang
+        | S::Lifetime
+        | S::Ident
+        | S::Other => {}
+    }
+    match class {
+        T::DocComment
+        | T::Lifetime
+        | T::Colon
+        | T::ModSep
+        | T::Dot
+        | T::RangeStart
+        | T::RangeBetween
+        | T::RangeEnd
+        | T::ClosureOpen
+        | T::ClosureClose
+        | T::Or
+        | T::Prefix
+        | T::PrefixStar
+        | T::PrefixNot
+        | T::PrefixQuestion
+        | T::Not
+        | T::Star
+        | T::And
+        | T::Question
+        | T::Plus
+        | T::Lt
+        | T::LtInGenerics
+        | T::Gt
+        | T::GtClosing { .. }
+        | T::Shr
+        | T::ShrClosing { .. }
+        | T::Separator
+        | T::Dollar
+        | T::AttrPound
+        | T::Pound
+        | T::Interpolated { .. }
+        | T::Other
+        | T::Ident(_) => {}
+    }
+    match ident {
+        I::DollarCrate
+        | I::Crate
+        | I::Super
+        | I::Mut
+        | I::Const
+        | I::GenericKeyword
+        | I::Name
+        | I::Keyword => {}
+    }
+}
+
+/// Snapshots the whole transition table, so that any change to how some token
+/// is spaced shows up here. The outcomes of every state are listed in the order
+/// the classes first lead to them.
+#[test]
+fn spacing_table() {
+    let mut table: Vec<String> = Vec::new();
+    for state in STATES {
+        let mut outcomes: Vec<(String, Vec<String>)> = Vec::new();
+        let mut add = |(space, next, effect): (bool, State, Option<Effect>), class: String| {
+            let space = if space { "spaced" } else { "tight" };
+            let outcome = match effect {
+                Some(effect) => format!("{space} {next:?}, then {effect:?}"),
+                None => format!("{space} {next:?}"),
+            };
+            match outcomes.iter_mut().find(|(o, _)| *o == outcome) {
+                Some((_, classes)) => classes.push(class),
+                None => outcomes.push((outcome, vec![class])),
+            }
+        };
+        for class in TOKEN_CLASSES {
+            add(token_transition(state, class), format!("{class:?}"));
+        }
+        for delim in [Delimiter::Parenthesis, Delimiter::Bracket, Delimiter::Brace] {
+            let (space, next) = delimited_transition(state, delim);
+            add((space, next, None), format!("Delimited({delim:?})"));
+        }
+        table.push(format!("{state:?}"));
+        for (outcome, classes) in outcomes {
+            table.push(format!("    {outcome}:"));
+            let mut line = String::new();
+            for class in classes {
+                if !line.is_empty() && line.len() + 1 + class.len() + 1 > 72 {
+                    table.push(format!("        {line}"));
+                    line.clear();
+                } else if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&class);
+                line.push(',');
+            }
+            table.push(format!("        {line}"));
+        }
+    }
+    expect![[r#"
+            Start
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Dollar
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate),
+                tight PathSegment:
+                    Ident(Crate),
+                tight DollarIdent:
+                    Ident(Super), Ident(Mut), Ident(Const), Ident(GenericKeyword),
+                    Ident(Name), Ident(Keyword),
+                spaced Lifetime:
+                    Lifetime,
+                spaced Other:
+                    Colon, RangeBetween, Or, Not, Plus, Lt, Gt, Shr, Pound,
+                    Interpolated { path: false }, Other, Delimited(Bracket),
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Brace),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                tight Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+                spaced Ident:
+                    Interpolated { path: true },
+                tight DollarParen:
+                    Delimited(Parenthesis),
+            DollarIdent
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), RangeBetween, Or, Plus, Lt,
+                    Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                tight DollarIdentColon:
+                    Colon,
+                tight PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixQuestion,
+                tight MacroBang:
+                    PrefixNot, Not,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            DollarIdentColon
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate),
+                tight Other:
+                    Ident(Crate), Ident(Super), Ident(Mut), Ident(Const),
+                    Ident(GenericKeyword), Ident(Name), Ident(Keyword), RangeEnd, Question,
+                    Separator,
+                spaced Lifetime:
+                    Lifetime,
+                spaced Other:
+                    Colon, RangeBetween, Or, Not, Plus, Lt, Gt, Shr, Pound,
+                    Interpolated { path: false }, Other, Delimited(Parenthesis),
+                    Delimited(Bracket), Delimited(Brace),
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                tight Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+                spaced Ident:
+                    Interpolated { path: true },
+            DollarParen
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate),
+                tight DollarParenSep:
+                    Ident(Crate), Ident(Super), Ident(Mut), Ident(Const),
+                    Ident(GenericKeyword), Ident(Name), Ident(Keyword), Lifetime, Colon,
+                    ModSep, Dot, RangeStart, RangeBetween, RangeEnd, ClosureOpen,
+                    ClosureClose, Or, Prefix, PrefixNot, Not, And, Lt, LtInGenerics, Gt,
+                    GtClosing { tight: true }, GtClosing { tight: false }, Shr,
+                    ShrClosing { tight: true }, ShrClosing { tight: false }, Separator,
+                    Dollar, AttrPound, Pound, Interpolated { path: true },
+                    Interpolated { path: false }, Other,
+                tight Repetition:
+                    PrefixStar, PrefixQuestion, Star, Question, Plus,
+                spaced Other:
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+            DollarParenSep
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixNot,
+                tight Repetition:
+                    PrefixStar, PrefixQuestion, Star, Question, Plus,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Pound
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Plus,
+                    Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Bracket),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixQuestion,
+                tight PoundBang:
+                    PrefixNot, Not,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            PoundBang
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Bracket),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Ampersand
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                tight Other:
+                    Ident(Mut), Lifetime, RangeEnd, Question, Separator,
+                spaced Other:
+                    Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not, Plus, Lt,
+                    Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            GenericKeyword
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                tight GenericOpen, then OpenAngle { tight: true }:
+                    Lt, LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            GenericOpen
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            GenericClose
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                tight PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Parenthesis),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            PathSegment
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Plus,
+                    Gt, Shr, Pound, Interpolated { path: false }, Other, Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                tight PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Parenthesis),
+                    Delimited(Bracket),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixQuestion,
+                tight MacroBang:
+                    PrefixNot, Not,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                tight GenericOpen, then OpenAngle { tight: true }:
+                    Lt, LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            PathSep
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate),
+                tight PathSegment:
+                    Ident(Crate), Ident(Super), Ident(Mut), Ident(Const),
+                    Ident(GenericKeyword), Ident(Name), Ident(Keyword),
+                spaced Lifetime:
+                    Lifetime,
+                spaced Other:
+                    Colon, RangeBetween, Or, Not, Plus, Gt, Shr, Pound,
+                    Interpolated { path: false }, Other, Delimited(Parenthesis),
+                    Delimited(Bracket),
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, PrefixStar, Star, Question, Separator, Delimited(Brace),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixNot, PrefixQuestion,
+                spaced Ampersand:
+                    And,
+                tight GenericOpen, then OpenAngle { tight: true }:
+                    Lt, LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                tight Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+                tight Ident:
+                    Interpolated { path: true },
+            Dot
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                tight Ident:
+                    Ident(Mut), Ident(Const), Ident(GenericKeyword), Ident(Name),
+                    Ident(Keyword),
+                tight Other:
+                    Lifetime, Colon, RangeStart, RangeBetween, RangeEnd, ClosureOpen,
+                    ClosureClose, Or, Prefix, PrefixStar, PrefixNot, PrefixQuestion, Not,
+                    Star, And, Question, Plus, Lt, LtInGenerics, Gt,
+                    GtClosing { tight: true }, GtClosing { tight: false }, Shr,
+                    ShrClosing { tight: true }, ShrClosing { tight: false }, Separator,
+                    AttrPound, Pound, Interpolated { path: true },
+                    Interpolated { path: false }, Other, Delimited(Parenthesis),
+                    Delimited(Bracket), Delimited(Brace),
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                tight Dollar:
+                    Dollar,
+            RangePrefix
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Unary
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Repetition
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            LineStart
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            DocComment
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            ClosureOpen
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Star
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                tight Other:
+                    Ident(Mut), Ident(Const), RangeEnd, Question, Separator,
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Other:
+                    Ident(Keyword), Colon, RangeBetween, Or, Not, Plus, Lt, Gt, Shr, Pound,
+                    Interpolated { path: false }, Other, Delimited(Parenthesis),
+                    Delimited(Bracket), Delimited(Brace),
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            MacroBang
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator, Delimited(Parenthesis),
+                    Delimited(Bracket),
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Lifetime
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), RangeBetween, Or, Not, Plus,
+                    Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                tight Other:
+                    Colon, RangeEnd, Question, Separator,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Ident
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), RangeBetween, Or, Plus, Gt,
+                    Shr, Pound, Interpolated { path: false }, Other, Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                tight Other:
+                    Colon, RangeEnd, Question, Separator, Delimited(Parenthesis),
+                    Delimited(Bracket),
+                tight PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixQuestion,
+                tight MacroBang:
+                    PrefixNot, Not,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                tight GenericOpen, then OpenAngle { tight: true }:
+                    Lt, LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,
+            Other
+                spaced DocComment:
+                    DocComment,
+                spaced PathSegment:
+                    Ident(DollarCrate), Ident(Crate), Ident(Super),
+                spaced Other:
+                    Ident(Mut), Ident(Const), Ident(Keyword), Colon, RangeBetween, Or, Not,
+                    Plus, Lt, Gt, Shr, Pound, Interpolated { path: false }, Other,
+                    Delimited(Parenthesis), Delimited(Bracket), Delimited(Brace),
+                spaced GenericKeyword:
+                    Ident(GenericKeyword),
+                spaced Ident:
+                    Ident(Name), Interpolated { path: true },
+                spaced Lifetime:
+                    Lifetime,
+                spaced PathSep:
+                    ModSep,
+                tight Dot:
+                    Dot,
+                spaced RangePrefix:
+                    RangeStart,
+                tight Other:
+                    RangeEnd, Question, Separator,
+                spaced ClosureOpen, then OpenClosureParams:
+                    ClosureOpen,
+                tight Other, then CloseClosureParams:
+                    ClosureClose,
+                spaced Unary:
+                    Prefix, PrefixStar, PrefixNot, PrefixQuestion,
+                spaced Star:
+                    Star,
+                spaced Ampersand:
+                    And,
+                spaced Other, then OpenAngle { tight: false }:
+                    LtInGenerics,
+                tight GenericClose, then CloseAngles(1):
+                    GtClosing { tight: true },
+                spaced Other, then CloseAngles(1):
+                    GtClosing { tight: false },
+                tight GenericClose, then CloseAngles(2):
+                    ShrClosing { tight: true },
+                spaced Other, then CloseAngles(2):
+                    ShrClosing { tight: false },
+                spaced Dollar:
+                    Dollar,
+                spaced Pound:
+                    AttrPound,"#]]
+    .assert_eq(&table.join("\n"));
+}
+
+#[test]
+fn print_expanded() {
+    let src = "$name:ident, $vis:vis, $($field:ident = $value:expr),* $(,)?";
+    check_printed(src, expect!["($name:ident, $vis:vis, $($field:ident = $value:expr),* $(,)?)"]);
+    let expanded = PrintOptions { style: MacroMatcherStyle::Expanded, ..DEFAULT_OPTIONS };
+    check_printed_with(
+        src,
+        expanded,
+        expect![[r#"
+            (
+                    $name:ident,
+                    $vis:vis,
+                    $($field:ident = $value:expr),*
+                    $(,)?
+                )"#]],
+    );
+    // Only the parameters of the matcher itself are put on lines of their own.
+    check_printed_with(
+        "$name:ident { $a:ident, $b:ident }",
+        expanded,
+        expect!["($name:ident { $a:ident, $b:ident })"],
+    );
+}
+
+#[test]
+fn print_truncated() {
+    let options = PrintOptions { token_limit: 3, ..DEFAULT_OPTIONS };
+    check_printed_with(
+        "$a:ident ($b:ident $c:ident) $d:ident",
+        options,
+        expect!["($a:ident ($b:ident ...))"],
+    );
+    check_printed_with(
+        "$a:ident { $b:ident $c:ident }",
+        options,
+        expect!["($a:ident { $b:ident ... })"],
+    );
+    // Metavariables and repetitions each count as a single token, and are never
+    // cut short.
+    check_printed_with(
+        "$a:ident $b:ident $c:ident $d:ident",
+        options,
+        expect!["($a:ident $b:ident $c:ident ...)"],
+    );
+    check_printed_with(
+        "$($x:expr),* $y:ident $z:ident $w:ident",
+        options,
+        expect!["($($x:expr),* $y:ident $z:ident ...)"],
+    );
+}
+
+#[test]
+fn print_single_line() {
+    let options = PrintOptions { single_line: true, ..DEFAULT_OPTIONS };
+    let src = (0..20).map(|i| format!("$a{i}:expr")).collect::<Vec<_>>().join(", ");
+    create_default_session_globals_then(|| {
+        let printed = print_matcher_tokens(&lex(&src), options);
+        assert_eq!(printed.text, format!("({src})"));
+    });
+    check_printed_with("/// Docs\n$x:ident", options, expect!["($x:ident)"]);
+    // A metavariable is not cut short.
+    let options = PrintOptions { token_limit: 2, ..options };
+    check_printed_with("$a:ident $b:ident $c:ident", options, expect!["($a:ident $b:ident ...)"]);
+}
+
+#[test]
+fn summarize_long_matcher() {
+    let src = (0..20).map(|i| format!("$a{i}:expr")).collect::<Vec<_>>().join(", ");
+    create_default_session_globals_then(|| {
+        let matcher = TokenTree::Delimited(DelimSpan::dummy(), Delimiter::Parenthesis, lex(&src));
+        expect!["($a0:expr, $a1:expr, $a2:expr, $a3:expr, $a4:expr, $a5:expr, $a6:expr, ...)"]
+            .assert_eq(&summarize_matcher(&mut Printer::new(), &matcher, DEFAULT_OPTIONS));
+        let matcher =
+            TokenTree::Delimited(DelimSpan::dummy(), Delimiter::Parenthesis, lex("$x:expr"));
+        let summary = summarize_matcher(&mut Printer::new(), &matcher, DEFAULT_OPTIONS);
+        expect!["($x:expr)"].assert_eq(&summary);
+    })
+}
+
+#[test]
+fn print_huge_matcher() {
+    let fragments: Vec<_> = (0..1000).map(|i| format!("$t{i}:tt")).collect();
+    let src = format!("{{ [{}] }}", fragments.join(" "));
+    create_default_session_globals_then(|| {
+        let options = PrintOptions { token_limit: 100, ..DEFAULT_OPTIONS };
+        let printed = print_matcher_tokens(&lex(&src), options);
+        assert!(printed.truncated);
+        // The brace group and the bracket group take up two of the tokens, and
+        // the fragments one each.
+        assert!(printed.text.contains("$t97:tt"), "{}", printed.text);
+        assert!(!printed.text.contains("$t98"), "{}", printed.text);
+        assert!(printed.text.ends_with("...] }\n    )"), "{}", printed.text);
+
+        let printed = print_matcher_tokens(&lex(&src), DEFAULT_OPTIONS);
+        assert!(!printed.truncated);
+        assert!(printed.text.contains("$t999:tt"));
+    })
+}
+
+#[test]
+fn reused_printer_prints_like_a_new_one() {
+    let corpus = [
+        "($x:ident)",
+        "{ $($key:expr => $value:expr),* $(,)? }",
+        "[$first:ident => $a:expr, $second:ident => $b:expr, $third:ident => $c:expr]",
+        "($x:ident /// Docs\n)",
+    ];
+    create_default_session_globals_then(|| {
+        let mut printer = Printer::new();
+        for width in [20, 78, 40] {
+            for single_line in [false, true] {
+                let options = PrintOptions { width, single_line, ..DEFAULT_OPTIONS };
+                for src in corpus {
+                    let matcher = lex(src).trees().next().unwrap().clone();
+                    assert_eq!(
+                        print_matcher(&mut printer, &matcher, options).text,
+                        print_matcher(&mut Printer::new(), &matcher, options).text,
+                        "{src:?} at width {width}",
+                    );
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn printed_lines_never_end_in_whitespace() {
+    let corpus = [
+        "$x:ident",
+        "/// Docs",
+        "//! Docs   \n$x:ident",
+        "$x:ident /** Docs   \n   */",
+        "{ $x:ident /// Docs\n}",
+        "{ #![allow(unused)] }",
+        "{ $a:ident; $b:ident; $c:ident; }",
+        "$($key:expr => $value:expr),* $(,)?",
+        "$first:ident => $a:expr, $second:ident => $b:expr, $third:ident => $c:expr",
+        "<= 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 =>",
+    ];
+    create_default_session_globals_then(|| {
+        for src in corpus {
+            for width in [20, 40, 78] {
+                let options = PrintOptions { width, ..DEFAULT_OPTIONS };
+                let printed = print_matcher_tokens(&lex(src), options).text;
+                assert!(
+                    !printed.ends_with('\n') && printed.lines().all(|line| line.trim_end() == line),
+                    "{src:?} is printed with trailing whitespace at width {width}: {printed:?}",
+                );
+            }
+        }
+    })
+}
+
+/// Checks how `trees` are pretty-printed as the inside of a matcher, for token
+/// streams that could not come out of the lexer as they are.
+fn check_printed_trees(trees: Vec<TokenTree>, expect: Expect) {
+    create_default_session_globals_then(|| {
+        expect.assert_eq(&print_matcher_tokens(&TokenStream::new(trees), DEFAULT_OPTIONS).text);
+    })
+}
+
+fn fragment(name: &str, kind: &str) -> Vec<TokenTree> {
+    vec![
+        TokenTree::token_alone(token::Dollar, DUMMY_SP),
+        ident(name),
+        TokenTree::token_alone(token::Colon, DUMMY_SP),
+        ident(kind),
+    ]
+}
+
+/// `$a:expr`, then the operator made up of `first` and `second`, glued
+/// together, then `$b:expr`.
+fn split_operator(first: TokenKind, second: TokenKind) -> Vec<TokenTree> {
+    let mut trees = fragment("a", "expr");
+    trees.push(TokenTree::token_joint(first, DUMMY_SP));
+    trees.push(TokenTree::token_alone(second, DUMMY_SP));
+    trees.extend(fragment("b", "expr"));
+    trees
+}
+
+#[test]
+fn print_joint_operators() {
+    let shr = split_operator(token::Gt, token::Gt);
+    check_printed_trees(shr, expect!["($a:expr >> $b:expr)"]);
+    let shl = split_operator(token::Lt, token::Lt);
+    check_printed_trees(shl, expect!["($a:expr << $b:expr)"]);
+    let plus_eq = split_operator(token::BinOp(BinOpToken::Plus), token::Eq);
+    check_printed_trees(plus_eq, expect!["($a:expr += $b:expr)"]);
+    let range = split_operator(token::DotDot, token::Eq);
+    check_printed_trees(range, expect!["($a:expr ..= $b:expr)"]);
+    let path = split_operator(token::Colon, token::Colon);
+    check_printed_trees(path, expect!["($a:expr :: $b:expr)"]);
+}
+
+#[test]
+fn print_joint_closing_angle_brackets() {
+    // `Vec<Vec<$t>>`, with the `>>` split in two as the parser does.
+    let mut trees = vec![ident("Vec"), TokenTree::token_alone(token::Lt, DUMMY_SP)];
+    trees.push(ident("Vec"));
+    trees.push(TokenTree::token_alone(token::Lt, DUMMY_SP));
+    trees.extend(fragment("t", "ty"));
+    trees.push(TokenTree::token_joint(token::Gt, DUMMY_SP));
+    trees.push(TokenTree::token_alone(token::Gt, DUMMY_SP));
+    check_printed_trees(trees, expect!["(Vec<Vec<$t:ty>>)"]);
+}
+
+#[test]
+fn print_alone_operators() {
+    let mut path = fragment("a", "expr");
+    path.push(TokenTree::token_alone(token::Colon, DUMMY_SP));
+    path.push(TokenTree::token_alone(token::Colon, DUMMY_SP));
+    path.extend(fragment("b", "expr"));
+    check_printed_trees(path, expect!["($a:expr : : $b:expr)"]);
+}
+
+fn interpolated(nt: token::Nonterminal) -> TokenTree {
+    TokenTree::token_alone(token::Interpolated(Lrc::new(nt)), DUMMY_SP)
+}
+
+#[test]
+fn print_interpolated_idents() {
+    // `$f() &$a str`, as transcribed by an outer macro called with `f` and `'a`.
+    let name = interpolated(token::NtIdent(Ident::from_str("f"), false));
+    let lifetime = interpolated(token::NtLifetime(Ident::from_str("'a")));
+    let trees = vec![
+        name,
+        delimited(Delimiter::Parenthesis, vec![]),
+        TokenTree::token_alone(token::BinOp(BinOpToken::And), DUMMY_SP),
+        lifetime,
+        ident("str"),
+    ];
+    check_printed_trees(trees, expect!["(f() &'a str)"]);
+}
+
+fn invisible(trees: Vec<TokenTree>) -> TokenTree {
+    delimited(Delimiter::Invisible, trees)
+}
+
+#[test]
+fn print_invisible_groups() {
+    // A `$x` in a group of its own still glues to the `:` after it.
+    let dollar = TokenTree::token_alone(token::Dollar, DUMMY_SP);
+    let colon = TokenTree::token_alone(token::Colon, DUMMY_SP);
+    let trees = vec![invisible(vec![dollar, ident("x")]), colon, ident("ident")];
+    check_printed_trees(trees, expect!["($x:ident)"]);
+
+    let call =
+        vec![invisible(vec![ident("f")]), delimited(Delimiter::Parenthesis, vec![ident("a")])];
+    check_printed_trees(call, expect!["(f(a))"]);
+
+    let empty = vec![ident("a"), invisible(vec![]), ident("b")];
+    check_printed_trees(empty, expect!["(a b)"]);
+}
+
+#[test]
+fn catch_printer_panics() {
+    let rendered = catch_render_panic(|| panic!("unexpected token"));
+    assert!(rendered.is_none());
+    // Whatever panicked, the build goes on with a placeholder.
+    let placeholder = RenderedMatcher::unrenderable(RulePart::Matcher);
+    assert_eq!(placeholder.text, "(/* macro matcher could not be rendered */)");
+    // Fatal errors still stop the build.
+    let fatal = panic::catch_unwind(|| catch_render_panic(|| FatalError.raise()));
+    assert!(fatal.unwrap_err().is::<FatalErrorMarker>());
+    let rendered = create_default_session_globals_then(|| {
+        catch_render_panic(|| print_matcher_tokens(&lex("$x:ident"), DEFAULT_OPTIONS))
+    });
+    assert_eq!(rendered.unwrap().text, "($x:ident)");
+}
+
+#[test]
+fn print_transcribers() {
+    let transcriber = PrintOptions { part: RulePart::Transcriber, ..DEFAULT_OPTIONS };
+    let print = |src| {
+        let body = TokenTree::Delimited(DelimSpan::dummy(), Delimiter::Brace, lex(src));
+        print_matcher(&mut Printer::new(), &body, transcriber).text
+    };
+    create_default_session_globals_then(|| {
+        assert_eq!(print("$x * $x"), "{ $x * $x }");
+        assert_eq!(print(""), "{}");
+        // Unlike the ones of a matcher, the commas of a transcriber don't
+        // separate parameters.
+        assert_eq!(print("f($a, $b), $c"), "{ f($a, $b), $c }");
+        expect![[r#"
+            {
+                    let x = $e;
+                    $(x.push($y);)*;
+                    x
+                }"#]]
+        .assert_eq(&print("let x = $e; $(x.push($y);)*; x"));
+    })
+}
+
+#[test]
+fn split_matcher_tokens() {
+    create_default_session_globals_then(|| {
+        let matcher = TokenTree::Delimited(
+            DelimSpan::dummy(),
+            Delimiter::Parenthesis,
+            lex("$($x:expr),+ $crate [$y $(;)*]"),
+        );
+        let tokens: Vec<_> = split_matcher(&matcher)
+            .0
+            .into_iter()
+            .map(|token| format!("{:?} {}", token.kind, token.text))
+            .collect();
+        expect![[r#"
+            Token (
+            Token $
+            Token (
+            Metavariable $x
+            Token :
+            FragmentSpecifier expr
+            Token )
+            Token ,
+            RepetitionOperator +
+            Token $crate
+            Token [
+            Metavariable $y
+            Token $
+            Token (
+            Token ;
+            Token )
+            RepetitionOperator *
+            Token ]
+            Token )"#]]
+        .assert_eq(&tokens.join("\n"));
+    })
+}
+
+#[test]
+fn split_matcher_bindings() {
+    create_default_session_globals_then(|| {
+        let matcher = TokenTree::Delimited(
+            DelimSpan::dummy(),
+            Delimiter::Parenthesis,
+            lex("$a:ident $($b:expr $(; $c:tt)+),* $[$d] $($e)?"),
+        );
+        let bindings: Vec<_> = split_matcher(&matcher)
+            .1
+            .into_iter()
+            .map(|binding| {
+                let repetitions: Vec<_> = binding
+                    .repetitions
+                    .iter()
+                    .map(|rep| {
+                        format!("{}{}", rep.separator.as_deref().unwrap_or(""), rep.operator)
+                    })
+                    .collect();
+                format!("{} {:?} [{}]", binding.name, binding.fragment, repetitions.join(" "))
+            })
+            .collect();
+        expect![[r#"
+            a Some("ident") []
+            b Some("expr") [,*]
+            c Some("tt") [,* +]
+            d None []
+            e None [?]"#]]
+        .assert_eq(&bindings.join("\n"));
+    })
+}
+
+#[test]
+fn render_identical_arms() {
+    let rendered = |text: &str| RenderedMatcher::new(text.to_owned(), true);
+    let matchers =
+        [rendered("($x:expr)"), rendered("($x:expr)"), rendered("($x:expr)"), rendered("()")];
+    let (text, ranges) = render_macro_arms(&matchers, &[], ";", 0);
+    assert_eq!(text, "    ($x:expr) => { ... }; /* 3 identical arms */\n    () => { ... };\n");
+    assert_eq!(ranges, [4..48, 4..48, 4..48, 53..67]);
+    // Arms whose transcribers are shown are only collapsed if these are the same too.
+    let transcribers = [rendered("{ 1 }"), rendered("{ 1 }"), rendered("{ 2 }"), rendered("{}")];
+    assert_eq!(
+        render_macro_arms(&matchers, &transcribers, ";", 0).0,
+        "    ($x:expr) => { 1 }; /* 2 identical arms */\n    ($x:expr) => { 2 };\n    () => {};\n"
+    );
+    // Internal arms left out are told about after the others.
+    assert_eq!(
+        render_macro_arms(&matchers[3..], &[], ";", 2).0,
+        "    () => { ... };\n    /* 2 internal arms hidden */\n"
+    );
+}
+
+#[test]
+fn internal_arms() {
+    let is_internal = |src: &str| {
+        create_default_session_globals_then(|| {
+            let matcher =
+                TokenTree::Delimited(DelimSpan::dummy(), Delimiter::Parenthesis, lex(src));
+            is_internal_arm(&matcher)
+        })
+    };
+    assert!(is_internal("@munch $($rest:tt)*"));
+    assert!(is_internal("@type $t:ty"));
+    assert!(!is_internal("$x:expr @munch"));
+    assert!(!is_internal("@ $x:expr"));
+    assert!(!is_internal("@"));
+    assert!(!is_internal(""));
+}
+
+/// Checks the example invocation of a macro `m` printed for the arm whose
+/// matcher is `src`, delimiters included.
+fn check_invocation(src: &str, expect: Expect) {
+    create_default_session_globals_then(|| {
+        let matcher = lex(src).trees().next().unwrap().clone();
+        let name = Symbol::intern("m");
+        expect.assert_eq(&print_invocation(&mut Printer::new(), name, &matcher, DEFAULT_OPTIONS));
+    })
+}
+
+#[test]
+fn print_invocations() {
+    check_invocation(
+        "($name:ident, $($field:ident: $ty:ty),* $(,)?)",
+        expect!["m!(NAME, FIELD: TY, /* ... */)"],
+    );
+    check_invocation("[$($x:expr),* $(,)?]", expect!["m![X, /* ... */]"]);
+    check_invocation("($($t:ty)+)", expect!["m!(T /* ... */)"]);
+    check_invocation("($lit:literal => $($rest:tt)*)", expect!["m!(LIT => REST /* ... */)"]);
+    // Optional repetitions are left out.
+    check_invocation("($(pub)? struct $name:ident)", expect!["m!(struct NAME)"]);
+    // Arms without any metavariables are called as they are matched.
+    check_invocation("(clear)", expect!["m!(clear)"]);
+    check_invocation("()", expect!["m!()"]);
+}
+
+#[test]
+fn print_nested_invocations() {
+    check_invocation(
+        "{ $($name:ident { $($field:ident: $ty:ty),* })* }",
+        expect!["m! { NAME { FIELD: TY, /* ... */ } /* ... */ }"],
+    );
+    check_invocation(
+        "($($key:expr => [$($value:expr);+]),*)",
+        expect!["m!(KEY => [VALUE; /* ... */], /* ... */)"],
+    );
+}
+
+/// Prints the short form of a macro whose arms have the matchers `srcs`,
+/// delimiters included.
+fn print_short(srcs: &[&str]) -> String {
+    create_default_session_globals_then(|| {
+        let arms: Vec<_> = srcs
+            .iter()
+            .map(|src| {
+                let matcher = lex(src).trees().next().unwrap().clone();
+                (matcher, delimited(Delimiter::Brace, vec![]))
+            })
+            .collect();
+        print_short_matcher(&arms, DEFAULT_OPTIONS)
+    })
+}
+
+#[test]
+fn print_short_matchers() {
+    expect!["($x:expr)"].assert_eq(&print_short(&["($x:expr)", "($x:expr, $y:expr)"]));
+    expect!["($($t:tt)*)"].assert_eq(&print_short(&["(@munch $($t:tt)*)", "($($t:tt)*)"]));
+    expect!["(@munch $t:tt)"].assert_eq(&print_short(&["(@munch $t:tt)"]));
+    expect![""].assert_eq(&print_short(&[]));
+}
+
+#[test]
+fn short_matchers_elide_repetitions() {
+    let src = "($name:ident, $($field:ident: $ty:ty = $default:expr),* => $($extra:tt)*)";
+    let short = print_short(&[src]);
+    expect!["($name:ident, $(…),* => $($extra:tt)*)"].assert_eq(&short);
+
+    let src = (0..20).map(|i| format!("$a{i}:expr")).collect::<Vec<_>>().join(", ");
+    let short = print_short(&[&format!("({src})")]);
+    assert!(short.len() <= MATCHER_SHORT_LEN, "{short}");
+    assert!(short.ends_with(", ...)"), "{short}");
+}
+
+#[test]
+fn short_matchers_are_one_line() {
+    let corpus = [
+        "($x:expr)",
+        "(/// Docs\n$x:expr /** More\n docs */)",
+        "($x:expr, \"two\nlines\")",
+        "({ $($name:ident { $($field:ident: $ty:ty),* })* })",
+        "(@munch\n$($rest:tt)*)",
+    ];
+    for src in corpus {
+        let short = print_short(&[src]);
+        assert!(!short.contains('\n'), "{short:?}");
+        assert!(short.len() <= MATCHER_SHORT_LEN, "{short:?}");
+    }
+}
+
+#[test]
+fn static_token_strs() {
+    create_default_session_globals_then(|| {
+        let src = "block expr ident item lifetime literal meta pat pat_param path stmt tt ty vis \
+            as const crate enum fn for impl in let mut pub ref self Self static struct trait type \
+            unsafe where";
+        for tree in lex(src).trees() {
+            let TokenTree::Token(token, _) = tree else { unreachable!() };
+            assert_eq!(static_token_str(token), Some(&*pprust::token_to_string(token)));
+        }
+        for tree in lex("r#fn r#expr x 1 , $ 'a").trees() {
+            let TokenTree::Token(token, _) = tree else { continue };
+            assert_eq!(static_token_str(token), None, "{token:?}");
+        }
+    })
+}
+
+#[test]
+fn printed_matchers_reparse_to_their_tokens() {
+    let corpus = [
+        "$x:expr .. = $y:expr",
+        "$a:expr ..= $b:expr ... $c:expr .. $d:expr",
+        "<<= >>= :: -> => <- ; < < - > > = =",
+        "a < -b, x - -y, - -z, & &w, &&v, | |u, ||t",
+        "$x:tt$y:tt $($z:tt)$*",
+        r###"r#"raw " string"# r"x" br##"y"## "s" b"t" 'c' b'x' 1u8 1.0 1e10 0x1F"###,
+        "'a 'static $lt:lifetime &'a mut $t:ty &$l:lifetime T",
+        "$crate::inner!($($t:tt)*) $crate :: x",
+        "/// Docs\n$x:ident /** Block */ $y:ident //! Inner\n",
+        "$($($x:expr),+);* $($(,)?)* $(a $(b $(c)*)+)?",
+        "#![inner] #[outer = \"x\"] $(#[$meta:meta])* $x:tt",
+        "$first:ident $second:$frag $($rest:$frag),*",
+        "$x:ident! { $($body:tt)* } $y:path!($($args:tt)*);",
+        "r#fn r#type r#match $r#x:ident",
+        "::std::vec::Vec<$t:ty> <$a as $b>::C<'a, { $n }>",
+        "{ let $p:pat = $e:expr; $($s:stmt;)* } [$($i:literal),* ,]",
+    ];
+    let expanded = MacroMatcherStyle::Expanded;
+    let all_options = [
+        DEFAULT_OPTIONS,
+        PrintOptions { width: 20, ..DEFAULT_OPTIONS },
+        PrintOptions { style: expanded, ..DEFAULT_OPTIONS },
+        PrintOptions { style: expanded, width: 20, ..DEFAULT_OPTIONS },
+        PrintOptions { single_line: true, ..DEFAULT_OPTIONS },
+    ];
+    create_default_session_globals_then(|| {
+        for src in corpus {
+            let tts = lex(src);
+            for options in all_options {
+                let printed = print_matcher_tokens(&tts, options).text;
+                let reparsed = lex(&printed);
+                let mut trees = reparsed.trees();
+                let reparsed_tts = match (trees.next(), trees.next()) {
+                    (Some(TokenTree::Delimited(_, Delimiter::Parenthesis, tts)), None) => tts,
+                    _ => panic!("{src:?} is printed as more than a matcher: {printed:?}"),
+                };
+                // Doc comments are left out of matchers printed on a single line.
+                let expected = if options.single_line {
+                    TokenStream::new(
+                        tts.trees()
+                            .filter(|tree| {
+                                !matches!(
+                                    tree,
+                                    TokenTree::Token(Token { kind: token::DocComment(..), .. }, _)
+                                )
+                            })
+                            .cloned()
+                            .collect(),
+                    )
+                } else {
+                    tts.clone()
+                };
+                assert!(
+                    reparsed_tts.eq_unspanned(&expected),
+                    "{src:?} is printed as different tokens with {options:?}: {printed:?}",
+                );
+            }
+            let matcher = delimited(Delimiter::Parenthesis, tts.trees().cloned().collect());
+            let minified = render_macro_matcher_minified(&matcher);
+            assert!(!minified.contains('\n') && !minified.contains("  "), "{minified:?}");
+            let reparsed = lex(&minified);
+            let mut trees = reparsed.trees();
+            let (Some(reparsed_matcher), None) = (trees.next(), trees.next()) else {
+                panic!("{src:?} is minified as more than a matcher: {minified:?}");
+            };
+            assert_eq!(
+                minified_tokens(reparsed_matcher),
+                minified_tokens(&matcher),
+                "{src:?} is minified as different tokens: {minified:?}",
+            );
+            // Every space is needed to lex the tokens back.
+            for (i, _) in minified.match_indices(' ') {
+                let unspaced = format!("{}{}", &minified[..i], &minified[i + 1..]);
+                assert!(
+                    !lex(&unspaced).eq_unspanned(&reparsed),
+                    "{src:?} is minified with a needless space: {minified:?}",
+                );
+            }
+        }
+    })
+}
+
+#[test]
+fn print_minified() {
+    let minified = |src: &str| {
+        create_default_session_globals_then(|| {
+            let tts = lex(src);
+            render_macro_matcher_minified(&delimited(
+                Delimiter::Parenthesis,
+                tts.trees().cloned().collect(),
+            ))
+        })
+    };
+    assert_eq!(minified("$x:expr , $($y:ident),* $(,)?"), "($x:expr,$($y:ident),*$(,)?)");
+    assert_eq!(minified("$a:ident :: $b:ident $c:ident ::d"), "($a:ident::$b:ident$c:ident::d)");
+    assert_eq!(minified("< < - > = > . .. & &&"), "(< < - > = >. ..& &&)");
+    assert_eq!(minified("'a 'b x 1 . 0 \"s\" y"), "('a 'b x 1 .0\"s\" y)");
+    assert_eq!(
+        minified("/// Docs\n$x:ident //! Inner\n"),
+        "(#[doc=\" Docs\"]$x:ident #![doc=\" Inner\"])",
+    );
+    // Literals of the same value are written the same.
+    assert_eq!(minified("0x1F 1_000u8 1_2.5 1f32"), "(31 1000u8 12.5 1f32)");
+    assert_eq!(
+        minified(r##"r"a\b" r#"q"q"# "\u{41}" b'\x41' br"\" '\''"##),
+        minified(r#""a\\b" "q\"q" "A" b'A' b"\\" '\''"#),
+    );
+}
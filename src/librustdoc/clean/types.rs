@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::hash::Hash;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -26,7 +27,7 @@
 use rustc_middle::ty::{self, TyCtxt, Visibility};
 use rustc_resolve::rustdoc::{add_doc_fragment, attrs_to_doc_fragments, inner_docs, DocFragment};
 use rustc_session::Session;
-use rustc_span::hygiene::MacroKind;
+use rustc_span::hygiene::{MacroKind, Transparency};
 use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_span::{self, FileName, Loc};
 use rustc_target::abi::VariantIdx;
@@ -35,6 +36,7 @@
 use crate::clean::cfg::Cfg;
 use crate::clean::external_path;
 use crate::clean::inline::{self, print_inlined_const};
+use crate::clean::render_macro_matchers::RenderedMatcher;
 use crate::clean::utils::{is_literal_expr, print_const_expr, print_evaluated_const};
 use crate::core::DocContext;
 use crate::formats::cache::Cache;
@@ -2427,7 +2429,49 @@ pub(crate) struct ImportSource {
 
 #[derive(Clone, Debug)]
 pub(crate) struct Macro {
-    pub(crate) source: String,
+    pub(crate) source: Box<str>,
+    /// The matchers of the macro, in the order of its arms.
+    pub(crate) matchers: ThinVec<RenderedMatcher>,
+    /// Where in `source` each arm is shown, in order. A macro 2.0 with a single
+    /// arm has none.
+    pub(crate) arms: ThinVec<MacroArm>,
+    /// The matcher of the first arm of the macro that isn't internal to it, on
+    /// a single line, as module pages and search results show the macro.
+    pub(crate) short: Box<str>,
+    /// Whether the macro is a `macro_rules!` one rather than a macro 2.0.
+    pub(crate) macro_rules: bool,
+    pub(crate) export: MacroExport,
+    /// How the identifiers the macro expands to are resolved.
+    pub(crate) transparency: Transparency,
+    /// The signature given with `#[doc(macro_signature = "...")]`, which is shown
+    /// instead of the declaration.
+    pub(crate) signature: Option<Symbol>,
+    /// The macro this one was generated by, as `make_macros!` generates `a` and
+    /// `b` in `make_macros!(a, b)`.
+    pub(crate) generated_by: Option<DefId>,
+}
+
+/// Whether a `macro_rules!` macro is exported at the root of its crate, which
+/// macros 2.0 never are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MacroExport {
+    NotExported,
+    /// `#[macro_export]`.
+    Exported,
+    /// `#[macro_export(local_inner_macros)]`, with which the macros the macro
+    /// calls are looked up in its own crate.
+    LocalInnerMacros,
+}
+
+/// An arm of a macro, as shown in its rendered declaration.
+#[derive(Clone, Debug)]
+pub(crate) struct MacroArm {
+    /// The range of the source of the declaration the arm is shown in, which
+    /// identical arms shown once share.
+    pub(crate) range: Range<usize>,
+    /// Whether the arm is one of the ones after the first few, which are only
+    /// shown once asked for.
+    pub(crate) hidden: bool,
 }
 
 #[derive(Clone, Debug)]
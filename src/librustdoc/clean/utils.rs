@@ -1,16 +1,19 @@
 use crate::clean::auto_trait::AutoTraitFinder;
 use crate::clean::blanket_impl::BlanketImplFinder;
-use crate::clean::render_macro_matchers::render_macro_matcher;
+use crate::clean::render_macro_matchers::{
+    dumped_matchers, macro_generator, macro_signature, render_decl_macro, render_macro_rules,
+};
 use crate::clean::{
     clean_doc_module, clean_middle_const, clean_middle_region, clean_middle_ty, inline, Crate,
-    ExternalCrate, Generic, GenericArg, GenericArgs, ImportSource, Item, ItemKind, Lifetime, Path,
-    PathSegment, Primitive, PrimitiveType, Term, Type, TypeBinding, TypeBindingKind,
+    ExternalCrate, Generic, GenericArg, GenericArgs, ImportSource, Item, ItemKind, Lifetime, Macro,
+    MacroExport, Path, PathSegment, Primitive, PrimitiveType, Term, Type, TypeBinding,
+    TypeBindingKind,
 };
 use crate::core::DocContext;
-use crate::html::format::visibility_to_src_with_space;
+use crate::html::format::{join_with_double_colon, visibility_to_src_with_space};
 
 use rustc_ast as ast;
-use rustc_ast::tokenstream::TokenTree;
+use rustc_ast::attr;
 use rustc_hir as hir;
 use rustc_hir::def::{DefKind, Res};
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
@@ -18,8 +21,8 @@
 use rustc_middle::mir::interpret::ConstValue;
 use rustc_middle::ty::subst::{GenericArgKind, SubstsRef};
 use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::edition::Edition;
 use rustc_span::symbol::{kw, sym, Symbol};
-use std::fmt::Write as _;
 use std::mem;
 use thin_vec::{thin_vec, ThinVec};
 
@@ -572,48 +575,60 @@ pub(crate) fn has_doc_flag(tcx: TyCtxt<'_>, did: DefId, flag: Symbol) -> bool {
 /// Set by `bootstrap::Builder::doc_rust_lang_org_channel` in order to keep tests passing on beta/stable.
 pub(crate) const DOC_RUST_LANG_ORG_CHANNEL: &str = env!("DOC_RUST_LANG_ORG_CHANNEL");
 
-/// Render a sequence of macro arms in a format suitable for displaying to the user
-/// as part of an item declaration.
-pub(super) fn render_macro_arms<'a>(
-    tcx: TyCtxt<'_>,
-    matchers: impl Iterator<Item = &'a TokenTree>,
-    arm_delim: &str,
-) -> String {
-    let mut out = String::new();
-    for matcher in matchers {
-        writeln!(out, "    {} => {{ ... }}{}", render_macro_matcher(tcx, matcher), arm_delim)
-            .unwrap();
-    }
-    out
-}
-
 pub(super) fn display_macro_source(
     cx: &mut DocContext<'_>,
     name: Symbol,
     def: &ast::MacroDef,
     def_id: DefId,
     vis: ty::Visibility<DefId>,
-) -> String {
-    // Extract the spans of all matchers. They represent the "interface" of the macro.
-    let matchers = def.body.tokens.chunks(4).map(|arm| &arm[0]);
-
-    if def.macro_rules {
-        format!("macro_rules! {} {{\n{}}}", name, render_macro_arms(cx.tcx, matchers, ";"))
+    edition: Edition,
+) -> Macro {
+    // A macro is rendered once however many places it is documented in.
+    let key = (def_id, name, vis);
+    if let Some(rendered) = cx.rendered_macros.get(&key) {
+        return rendered.clone();
+    }
+    #[cfg(test)]
+    {
+        cx.rendered_macro_count += 1;
+    }
+    let arms: Vec<_> =
+        def.body.tokens.chunks(4).map(|arm| (arm[0].clone(), arm[2].clone())).collect();
+    let attrs = cx.tcx.get_attrs_unchecked(def_id);
+    let transparency = rustc_attr::find_transparency(attrs, def.macro_rules).0;
+    let mut rendered = if def.macro_rules {
+        let export = match attr::find_by_name(attrs, sym::macro_export) {
+            None => MacroExport::NotExported,
+            Some(attr)
+                if attr.meta_item_list().is_some_and(|l| {
+                    rustc_attr::list_contains_name(&l, sym::local_inner_macros)
+                }) =>
+            {
+                MacroExport::LocalInnerMacros
+            }
+            Some(_) => MacroExport::Exported,
+        };
+        render_macro_rules(cx, name, &arms, def_id, edition, export, transparency)
     } else {
-        if matchers.len() <= 1 {
-            format!(
-                "{}macro {}{} {{\n    ...\n}}",
-                visibility_to_src_with_space(Some(vis), cx.tcx, def_id),
-                name,
-                matchers.map(|matcher| render_macro_matcher(cx.tcx, matcher)).collect::<String>(),
-            )
+        let vis = visibility_to_src_with_space(Some(vis), cx.tcx, def_id).to_string();
+        render_decl_macro(cx, &vis, name, &arms, def_id, edition, transparency)
+    };
+    rendered.signature = macro_signature(cx, def_id, attrs);
+    rendered.generated_by = macro_generator(cx, def_id);
+    if cx.render_options.dump_macro_matchers.is_some() {
+        let krate = cx.tcx.crate_name(def_id.krate);
+        // Exported `macro_rules!` macros are at the root of their crate, wherever
+        // they are defined.
+        let mut path = vec![krate];
+        if rendered.export != MacroExport::NotExported {
+            path.push(cx.tcx.item_name(def_id));
         } else {
-            format!(
-                "{}macro {} {{\n{}}}",
-                visibility_to_src_with_space(Some(vis), cx.tcx, def_id),
-                name,
-                render_macro_arms(cx.tcx, matchers, ","),
-            )
+            let relative = cx.tcx.def_path(def_id).data.into_iter();
+            path.extend(relative.filter_map(|elem| elem.data.get_opt_name()));
         }
+        let dumped = dumped_matchers(krate, &join_with_double_colon(&path), &rendered);
+        cx.dumped_macro_matchers.entry(def_id).or_insert(dumped);
     }
+    cx.rendered_macros.insert(key, rendered.clone());
+    rendered
 }
@@ -39,3 +39,34 @@ fn int_format_octal() {
     assert_eq!(format_integer_with_underscore_sep("-0b101101011"), "-0b1_0110_1011");
     assert_eq!(format_integer_with_underscore_sep("-0b01101011"), "-0b0110_1011");
 }
+
+#[test]
+fn macros_are_rendered_once() {
+    // A macro documented in several modules, as an inlined re-export in each of
+    // them, is only rendered once.
+    let src = "#![feature(no_core)]
+        #![no_core]
+
+        #[macro_export]
+        macro_rules! reexported {
+            ($x:expr) => {};
+            ($($t:tt)*) => {};
+        }
+
+        pub mod a {
+            #[doc(inline)]
+            pub use crate::reexported;
+        }
+
+        pub mod b {
+            #[doc(inline)]
+            pub use crate::reexported;
+        }
+
+        pub mod c {
+            #[doc(inline)]
+            pub use crate::reexported;
+        }";
+    let renders = crate::core::with_cleaned_crate(src, |cx| cx.rendered_macro_count);
+    assert_eq!(renders, 1);
+}
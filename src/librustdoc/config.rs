@@ -109,7 +109,8 @@ pub(crate) struct Options {
     pub(crate) should_test: bool,
     /// List of arguments to pass to the test harness, if running tests.
     pub(crate) test_args: Vec<String>,
-    /// The working directory in which to run tests.
+    /// The working directory in which to run tests, from `--test-run-directory` or
+    /// `--doctest-run-directory`, or `CARGO_MANIFEST_DIR` when running tests.
     pub(crate) test_run_directory: Option<PathBuf>,
     /// Optional path to persist the doctest executables to, defaults to a
     /// temporary directory if not set.
@@ -124,6 +125,8 @@ pub(crate) struct Options {
     pub(crate) enable_per_target_ignores: bool,
     /// Do not run doctests, compile them if should_test is active.
     pub(crate) no_run: bool,
+    /// Which doctests get compiled together into a few shared binaries.
+    pub(crate) merge_doctests: MergeDoctests,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from `$sysroot/bin/rustc`.
@@ -197,6 +200,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
             .field("run_check", &self.run_check)
             .field("no_run", &self.no_run)
+            .field("merge_doctests", &self.merge_doctests)
             .field("nocapture", &self.nocapture)
             .field("scrape_examples_options", &self.scrape_examples_options)
             .field("unstable_features", &self.unstable_features)
@@ -272,6 +276,37 @@ pub(crate) struct RenderOptions {
     pub(crate) call_locations: AllCallLocations,
     /// If `true`, Context::init will not emit shared files.
     pub(crate) no_emit_shared: bool,
+    /// Number of columns tabs in the source of macro matchers are expanded to.
+    pub(crate) macro_matcher_tab_width: usize,
+    /// Number of columns pretty-printed macro matchers are wrapped at.
+    pub(crate) macro_matcher_width: usize,
+    /// Number of token trees after which pretty-printed macro matchers are
+    /// truncated.
+    pub(crate) macro_matcher_token_limit: usize,
+    /// Size in bytes above which the source of a macro matcher is not reparsed,
+    /// and the matcher gets pretty-printed instead.
+    pub(crate) macro_matcher_snippet_limit: usize,
+    /// If `true`, macro matchers are always pretty-printed from their tokens
+    /// instead of being copied from the source.
+    pub(crate) normalize_macro_matchers: bool,
+    /// How pretty-printed macro matchers are laid out.
+    pub(crate) macro_matcher_style: MacroMatcherStyle,
+    /// If `true`, the right-hand side of each macro rule is rendered after its
+    /// matcher instead of being elided.
+    pub(crate) document_macro_bodies: bool,
+    /// Number of arms above which a macro gets an index of them.
+    pub(crate) macro_arm_index_threshold: usize,
+    /// Number of arms of a macro shown before the rest are collapsed.
+    pub(crate) macro_visible_arms: usize,
+    /// If `true`, the arms of macros whose matcher starts with `@` and an
+    /// identifier are left out of their declarations.
+    pub(crate) hide_internal_macro_arms: bool,
+    /// If `true`, an example invocation of each arm of a macro is rendered
+    /// after its documentation.
+    pub(crate) show_macro_invocations: bool,
+    /// File the matchers of every macro are written to, one arm per line, for
+    /// tools to read them.
+    pub(crate) dump_macro_matchers: Option<PathBuf>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -280,6 +315,25 @@ pub(crate) enum ModuleSorting {
     Alphabetical,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MergeDoctests {
+    /// Doctests are always compiled on their own.
+    Never,
+    /// Only the doctests of the 2024 edition are merged.
+    Auto,
+    /// The doctests of every edition since 2018 are merged.
+    Always,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MacroMatcherStyle {
+    /// Matchers are only wrapped when they don't fit on one line.
+    Compact,
+    /// The parameters and repetitions of matchers are always put on lines of
+    /// their own.
+    Expanded,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum EmitType {
     Unversioned,
@@ -518,6 +572,18 @@ fn println_condition(condition: Condition) {
             return Err(1);
         }
 
+        let merge_doctests = match matches.opt_str("merge-doctests").as_deref() {
+            None | Some("auto") => MergeDoctests::Auto,
+            Some("yes") => MergeDoctests::Always,
+            Some("no") => MergeDoctests::Never,
+            Some(value) => {
+                diag.struct_err(format!("invalid argument: \"{value}\""))
+                    .help("arguments to --merge-doctests must be `yes`, `no` or `auto`")
+                    .emit();
+                return Err(1);
+            }
+        };
+
         let out_dir = matches.opt_str("out-dir").map(|s| PathBuf::from(&s));
         let output = matches.opt_str("output").map(|s| PathBuf::from(&s));
         let output = match (out_dir, output) {
@@ -671,7 +737,21 @@ fn println_condition(condition: Condition) {
         let crate_version = matches.opt_str("crate-version");
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
         let static_root_path = matches.opt_str("static-root-path");
-        let test_run_directory = matches.opt_str("test-run-directory").map(PathBuf::from);
+        // `--doctest-run-directory` is another name of `--test-run-directory`, and the
+        // directory of the crate is only a default for running doctests.
+        let test_run_directory = matches
+            .opt_str("doctest-run-directory")
+            .or_else(|| matches.opt_str("test-run-directory"))
+            .map(PathBuf::from)
+            .or_else(|| {
+                let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR");
+                manifest_dir.filter(|_| should_test).map(PathBuf::from)
+            });
+        // The directory is given to doctests, which run from it, so it can't be relative.
+        let test_run_directory = test_run_directory.map(|dir| match std::env::current_dir() {
+            Ok(current_dir) => current_dir.join(dir),
+            Err(_) => dir,
+        });
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
@@ -699,6 +779,66 @@ fn println_condition(condition: Condition) {
             return Err(1);
         }
 
+        let macro_matcher_tab_width = match matches.opt_get_default("macro-matcher-tab-width", 4) {
+            Ok(width) if width > 0 => width,
+            _ => {
+                diag.struct_err("`--macro-matcher-tab-width` must be a positive integer").emit();
+                return Err(1);
+            }
+        };
+
+        let macro_matcher_width = match matches.opt_get_default("macro-matcher-width", 78) {
+            Ok(width) if width > 0 => width,
+            _ => {
+                diag.struct_err("`--macro-matcher-width` must be a positive integer").emit();
+                return Err(1);
+            }
+        };
+
+        let macro_matcher_token_limit =
+            match matches.opt_get_default("macro-matcher-token-limit", 10_000) {
+                Ok(limit) => limit,
+                Err(_) => {
+                    diag.struct_err("`--macro-matcher-token-limit` must be an integer").emit();
+                    return Err(1);
+                }
+            };
+
+        let macro_matcher_snippet_limit =
+            match matches.opt_get_default("macro-matcher-snippet-limit", 256 * 1024) {
+                Ok(limit) => limit,
+                Err(_) => {
+                    diag.struct_err("`--macro-matcher-snippet-limit` must be an integer").emit();
+                    return Err(1);
+                }
+            };
+
+        let normalize_macro_matchers = matches.opt_present("normalize-macro-matchers");
+        let macro_matcher_style = if matches.opt_present("expand-macro-matchers") {
+            MacroMatcherStyle::Expanded
+        } else {
+            MacroMatcherStyle::Compact
+        };
+        let document_macro_bodies = matches.opt_present("document-macro-bodies");
+        let macro_arm_index_threshold =
+            match matches.opt_get_default("macro-arm-index-threshold", 16) {
+                Ok(threshold) => threshold,
+                Err(_) => {
+                    diag.struct_err("`--macro-arm-index-threshold` must be an integer").emit();
+                    return Err(1);
+                }
+            };
+        let hide_internal_macro_arms = matches.opt_present("hide-internal-macro-arms");
+        let show_macro_invocations = matches.opt_present("show-macro-invocations");
+        let dump_macro_matchers = matches.opt_str("dump-macro-matchers").map(PathBuf::from);
+        let macro_visible_arms = match matches.opt_get_default("macro-visible-arms", 5) {
+            Ok(arms) => arms,
+            Err(_) => {
+                diag.struct_err("`--macro-visible-arms` must be an integer").emit();
+                return Err(1);
+            }
+        };
+
         let scrape_examples_options = ScrapeExamplesOptions::new(matches, &diag)?;
         let with_examples = matches.opt_strs("with-examples");
         let call_locations = crate::scrape_examples::load_call_locations(with_examples, &diag)?;
@@ -739,6 +879,7 @@ fn println_condition(condition: Condition) {
             test_builder,
             run_check,
             no_run,
+            merge_doctests,
             nocapture,
             crate_name,
             output_format,
@@ -773,6 +914,18 @@ fn println_condition(condition: Condition) {
             generate_link_to_definition,
             call_locations,
             no_emit_shared: false,
+            macro_matcher_tab_width,
+            macro_matcher_width,
+            macro_matcher_token_limit,
+            macro_matcher_snippet_limit,
+            normalize_macro_matchers,
+            macro_matcher_style,
+            document_macro_bodies,
+            macro_arm_index_threshold,
+            macro_visible_arms,
+            hide_internal_macro_arms,
+            show_macro_invocations,
+            dump_macro_matchers,
         };
         Ok((options, render_options))
     }
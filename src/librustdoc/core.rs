@@ -1,4 +1,4 @@
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::sync::{self, Lrc};
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::emitter::{Emitter, EmitterWriter};
@@ -11,12 +11,14 @@
 use rustc_hir::{HirId, Path};
 use rustc_interface::interface;
 use rustc_middle::hir::nested_filter;
-use rustc_middle::ty::{ParamEnv, Ty, TyCtxt};
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
 use rustc_session::config::{self, CrateType, ErrorOutputType, ResolveDocLinks};
 use rustc_session::lint;
+use rustc_session::parse::ParseSess;
 use rustc_session::Session;
-use rustc_span::symbol::sym;
-use rustc_span::{source_map, Span};
+use rustc_span::edition::Edition;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::{source_map, BytePos, Span};
 
 use std::cell::RefCell;
 use std::mem;
@@ -24,6 +26,7 @@
 use std::sync::LazyLock;
 
 use crate::clean::inline::build_external_trait;
+use crate::clean::render_macro_matchers::{write_matcher_dump, DumpedMatcher, ReparsedSourceFile};
 use crate::clean::{self, ItemId};
 use crate::config::{Options as RustdocOptions, OutputFormat, RenderOptions};
 use crate::formats::cache::Cache;
@@ -63,6 +66,26 @@ pub(crate) struct DocContext<'tcx> {
     pub(crate) output_format: OutputFormat,
     /// Used by `strip_private`.
     pub(crate) show_coverage: bool,
+    /// Parse sessions used to reparse the source snippets of macro matchers, by
+    /// the edition of the crates defining the macros. Created on first use and
+    /// shared by every macro of these crates.
+    pub(crate) matcher_reparse_sess: RefCell<FxHashMap<Edition, Lrc<ParseSess>>>,
+    /// Source files containing macro matchers, lexed again in
+    /// `matcher_reparse_sess`, by the start position of the original file.
+    /// `None` for the files that could not be lexed.
+    pub(crate) matcher_reparsed_files: RefCell<FxHashMap<BytePos, Option<ReparsedSourceFile>>>,
+    /// Macros already rendered, by their definition and the name and visibility
+    /// they are rendered with, for the ones documented in several places, as
+    /// inlined re-exports are. The options that change how macros are rendered
+    /// are the same for every one of them, so they aren't part of the key.
+    pub(crate) rendered_macros: FxHashMap<(DefId, Symbol, ty::Visibility<DefId>), clean::Macro>,
+    /// How many times a macro was rendered, which tests check to tell that the
+    /// ones in `rendered_macros` are not rendered again.
+    #[cfg(test)]
+    pub(crate) rendered_macro_count: usize,
+    /// The arms of every macro rendered so far, by the macro, which
+    /// `--dump-macro-matchers` writes once the crate is cleaned.
+    pub(crate) dumped_macro_matchers: FxIndexMap<DefId, Vec<DumpedMatcher>>,
 }
 
 impl<'tcx> DocContext<'tcx> {
@@ -331,42 +354,18 @@ pub(crate) fn run_global_ctxt(
     });
     rustc_passes::stability::check_unused_or_stable_features(tcx);
 
-    let auto_traits =
-        tcx.all_traits().filter(|&trait_def_id| tcx.trait_is_auto(trait_def_id)).collect();
-
-    let mut ctxt = DocContext {
-        tcx,
-        param_env: ParamEnv::empty(),
-        external_traits: Default::default(),
-        active_extern_traits: Default::default(),
-        substs: Default::default(),
-        current_type_aliases: Default::default(),
-        impl_trait_bounds: Default::default(),
-        generated_synthetics: Default::default(),
-        auto_traits,
-        cache: Cache::new(render_options.document_private),
-        inlined: FxHashSet::default(),
-        output_format,
-        render_options,
-        show_coverage,
-    };
-
-    for cnum in tcx.crates(()) {
-        crate::visit_lib::lib_embargo_visit_item(&mut ctxt, cnum.as_def_id());
-    }
-
-    // Small hack to force the Sized trait to be present.
-    //
-    // Note that in case of `#![no_core]`, the trait is not available.
-    if let Some(sized_trait_did) = ctxt.tcx.lang_items().sized_trait() {
-        let sized_trait = build_external_trait(&mut ctxt, sized_trait_did);
-        ctxt.external_traits.borrow_mut().insert(sized_trait_did, sized_trait);
-    }
+    let mut ctxt = new_doc_context(tcx, show_coverage, render_options, output_format);
 
     debug!("crate: {:?}", tcx.hir().krate());
 
     let mut krate = tcx.sess.time("clean_crate", || clean::krate(&mut ctxt));
 
+    if let Some(path) = &ctxt.render_options.dump_macro_matchers
+        && let Err(error) = write_matcher_dump(path, ctxt.dumped_macro_matchers.values().flatten())
+    {
+        tcx.sess.err(format!("failed to write macro matchers to {}: {error}", path.display()));
+    }
+
     if krate.module.doc_value().is_empty() {
         let help = format!(
             "The following guide may be of use:\n\
@@ -452,6 +451,85 @@ fn report_deprecated_attr(name: &str, diag: &rustc_errors::Handler, sp: Span) {
     (krate, ctxt.render_options, ctxt.cache)
 }
 
+/// The context the crate is cleaned in, with the traits of other crates that
+/// are always needed already visited.
+fn new_doc_context(
+    tcx: TyCtxt<'_>,
+    show_coverage: bool,
+    render_options: RenderOptions,
+    output_format: OutputFormat,
+) -> DocContext<'_> {
+    let auto_traits =
+        tcx.all_traits().filter(|&trait_def_id| tcx.trait_is_auto(trait_def_id)).collect();
+
+    let mut ctxt = DocContext {
+        tcx,
+        param_env: ParamEnv::empty(),
+        external_traits: Default::default(),
+        active_extern_traits: Default::default(),
+        substs: Default::default(),
+        current_type_aliases: Default::default(),
+        impl_trait_bounds: Default::default(),
+        generated_synthetics: Default::default(),
+        auto_traits,
+        cache: Cache::new(render_options.document_private),
+        inlined: FxHashSet::default(),
+        output_format,
+        render_options,
+        show_coverage,
+        matcher_reparse_sess: RefCell::new(FxHashMap::default()),
+        matcher_reparsed_files: RefCell::new(FxHashMap::default()),
+        rendered_macros: FxHashMap::default(),
+        #[cfg(test)]
+        rendered_macro_count: 0,
+        dumped_macro_matchers: FxIndexMap::default(),
+    };
+
+    for cnum in tcx.crates(()) {
+        crate::visit_lib::lib_embargo_visit_item(&mut ctxt, cnum.as_def_id());
+    }
+
+    // Small hack to force the Sized trait to be present.
+    //
+    // Note that in case of `#![no_core]`, the trait is not available.
+    if let Some(sized_trait_did) = ctxt.tcx.lang_items().sized_trait() {
+        let sized_trait = build_external_trait(&mut ctxt, sized_trait_did);
+        ctxt.external_traits.borrow_mut().insert(sized_trait_did, sized_trait);
+    }
+    ctxt
+}
+
+/// Cleans the crate whose source is `src`, as rustdoc does without options, and
+/// calls `f` with the context it was cleaned in, for the tests of the clean pass.
+#[cfg(test)]
+pub(crate) fn with_cleaned_crate<R: Send>(
+    src: &str,
+    f: impl FnOnce(&mut DocContext<'_>) -> R + Send,
+) -> R {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("lib.rs");
+    std::fs::write(&input, src).unwrap();
+    let args = vec![input.to_str().unwrap().to_owned()];
+    let mut options = rustc_session::getopts::Options::new();
+    for option in crate::opts() {
+        (option.apply)(&mut options);
+    }
+    let matches = options.parse(&args).unwrap();
+    let (options, render_options) = RustdocOptions::from_matches(&matches, args).unwrap();
+    interface::set_thread_safe_mode(&options.unstable_opts);
+    let (show_coverage, output_format) = (options.show_coverage, options.output_format);
+    let config = create_config(options, &render_options);
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| {
+            queries.global_ctxt().unwrap().enter(|tcx| {
+                let mut ctxt = new_doc_context(tcx, show_coverage, render_options, output_format);
+                clean::krate(&mut ctxt);
+                f(&mut ctxt)
+            })
+        })
+    })
+}
+
 /// Due to <https://github.com/rust-lang/rust/pull/73566>,
 /// the name resolution pass may find errors that are never emitted.
 /// If typeck is called after this happens, then we'll get an ICE:
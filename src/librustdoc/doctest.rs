@@ -1,5 +1,6 @@
 use rustc_ast as ast;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::{ColorConfig, ErrorGuaranteed, FatalError, TerminalUrl};
 use rustc_hir::def_id::{LocalDefId, CRATE_DEF_ID, LOCAL_CRATE};
@@ -21,16 +22,17 @@
 use tempfile::Builder as TempFileBuilder;
 
 use std::env;
+use std::hash::Hasher;
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use crate::clean::{types::AttributesExt, Attributes};
-use crate::config::Options as RustdocOptions;
+use crate::config::{MergeDoctests, Options as RustdocOptions};
 use crate::html::markdown::{self, ErrorCodes, Ignore, LangString};
 use crate::lint::init_lints;
 use crate::passes::span_of_attrs;
@@ -42,6 +44,8 @@ pub(crate) struct GlobalTestOptions {
     pub(crate) no_crate_inject: bool,
     /// Additional crate-level attributes to add to doctests.
     pub(crate) attrs: Vec<String>,
+    /// Statements added before the code of doctests, from `#![doc(test(prelude = "..."))]`.
+    pub(crate) prelude: Vec<String>,
 }
 
 pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
@@ -110,7 +114,7 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
     let externs = options.externs.clone();
     let json_unused_externs = options.json_unused_externs;
 
-    let (tests, unused_extern_reports, compiling_test_count) =
+    let (tests, item_paths, unused_extern_reports, compiling_test_count) =
         interface::run_compiler(config, |compiler| {
             compiler.enter(|queries| {
                 let collector = queries.global_ctxt()?.enter(|tcx| {
@@ -144,6 +148,7 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
                         |this| tcx.hir().walk_toplevel_module(this),
                     );
 
+                    collector.write_persisted_manifest();
                     collector
                 });
                 if compiler.session().diagnostic().has_errors_or_lint_errors().is_some() {
@@ -152,11 +157,16 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
 
                 let unused_extern_reports = collector.unused_extern_reports.clone();
                 let compiling_test_count = collector.compiling_test_count.load(Ordering::SeqCst);
-                Ok((collector.tests, unused_extern_reports, compiling_test_count))
+                Ok((
+                    collector.tests,
+                    collector.item_paths,
+                    unused_extern_reports,
+                    compiling_test_count,
+                ))
             })
         })?;
 
-    run_tests(test_args, nocapture, tests);
+    run_tests(test_args, nocapture, tests, &item_paths);
 
     // Collect and warn about unused externs, but only if we've gotten
     // reports for each doctest
@@ -203,7 +213,9 @@ pub(crate) fn run_tests(
     mut test_args: Vec<String>,
     nocapture: bool,
     mut tests: Vec<test::TestDescAndFn>,
+    item_paths: &[(String, String)],
 ) {
+    add_item_path_filters(&mut test_args, item_paths);
     test_args.insert(0, "rustdoctest".to_string());
     if nocapture {
         test_args.push("--nocapture".to_string());
@@ -212,11 +224,49 @@ pub(crate) fn run_tests(
     test::test_main(&test_args, tests, None);
 }
 
+/// Options of the test harness followed by a value, which is never a filter.
+const TEST_OPTIONS_WITH_VALUES: &[&str] =
+    &["--logfile", "--test-threads", "--skip", "--format", "--color", "-Z", "--shuffle-seed"];
+
+/// The test harness only filters tests by name, so the names of the doctests of the items a filter
+/// is the path of, or the path of a parent of, are added to the filters.
+fn add_item_path_filters(test_args: &mut Vec<String>, item_paths: &[(String, String)]) {
+    let mut names = Vec::new();
+    let mut args = test_args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with('-') {
+            if TEST_OPTIONS_WITH_VALUES.contains(&arg.as_str()) {
+                args.next();
+            }
+            continue;
+        }
+        names.extend(
+            item_paths
+                .iter()
+                .filter(|(_, path)| matches_item_path(arg, path))
+                .map(|(name, _)| name.clone()),
+        );
+    }
+    names.sort();
+    names.dedup();
+    test_args.extend(names);
+}
+
+/// Whether `filter` is `path` or the path of one of its parents, with or without the name of the
+/// crate `path` starts with.
+fn matches_item_path(filter: &str, path: &str) -> bool {
+    let is_parent = |path: &str| {
+        path.strip_prefix(filter).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    };
+    is_parent(path) || path.split_once("::").is_some_and(|(_, path)| is_parent(path))
+}
+
 // Look for `#![doc(test(no_crate_inject))]`, used by crates in the std facade.
 fn scrape_test_config(attrs: &[ast::Attribute]) -> GlobalTestOptions {
     use rustc_ast_pretty::pprust;
 
-    let mut opts = GlobalTestOptions { no_crate_inject: false, attrs: Vec::new() };
+    let mut opts =
+        GlobalTestOptions { no_crate_inject: false, attrs: Vec::new(), prelude: Vec::new() };
 
     let test_attrs: Vec<_> = attrs
         .iter()
@@ -237,6 +287,11 @@ fn scrape_test_config(attrs: &[ast::Attribute]) -> GlobalTestOptions {
                 opts.attrs.push(pprust::meta_list_item_to_string(item));
             }
         }
+        if attr.has_name(sym::prelude)
+            && let Some(prelude) = attr.value_str()
+        {
+            opts.prelude.push(prelude.to_string());
+        }
     }
 
     opts
@@ -248,9 +303,9 @@ enum TestFailure {
     CompileError,
     /// The test is marked `compile_fail` but compiled successfully.
     UnexpectedCompilePass,
-    /// The test failed to compile (as expected) but the compiler output did not contain all
-    /// expected error codes.
-    MissingErrorCodes(Vec<String>),
+    /// The test failed to compile (as expected) but the compiler did not emit all expected error
+    /// codes.
+    MissingErrorCodes { missing: Vec<String>, emitted: Vec<String> },
     /// The test binary was unable to be executed.
     ExecutionError(io::Error),
     /// The test binary exited with a non-zero exit code.
@@ -261,6 +316,52 @@ enum TestFailure {
     UnexpectedRunPass,
 }
 
+impl TestFailure {
+    /// Describes the failure.
+    fn description(&self) -> String {
+        match self {
+            TestFailure::CompileError => "Couldn't compile the test.".to_owned(),
+            TestFailure::UnexpectedCompilePass => {
+                "Test compiled successfully, but it's marked `compile_fail`.".to_owned()
+            }
+            TestFailure::UnexpectedRunPass => {
+                "Test executable succeeded, but it's marked `should_panic`.".to_owned()
+            }
+            TestFailure::MissingErrorCodes { missing, emitted } => {
+                let mut description =
+                    format!("Some expected error codes were not found: {missing:?}");
+                if emitted.is_empty() {
+                    description.push_str("\nNo error codes were emitted.");
+                } else {
+                    description.push_str(&format!("\nError codes emitted: {emitted:?}"));
+                }
+                description
+            }
+            TestFailure::ExecutionError(err) => {
+                let mut description = format!("Couldn't run the test: {err}");
+                if err.kind() == io::ErrorKind::PermissionDenied {
+                    description.push_str(" - maybe your tempdir is mounted with noexec?");
+                }
+                description
+            }
+            TestFailure::ExecutionFailure(out) => {
+                format!("Test executable failed ({reason}).", reason = out.status)
+            }
+        }
+    }
+}
+
+/// Whether the test harness was asked for one of its machine-readable formats, in which case
+/// doctests report how they failed as the message of the failure instead of in their output.
+fn machine_readable_test_output(test_args: &[String]) -> bool {
+    let is_machine_readable = |format: &str| matches!(format, "json" | "junit");
+    test_args.iter().enumerate().any(|(i, arg)| match arg.strip_prefix("--format") {
+        Some("") => test_args.get(i + 1).is_some_and(|format| is_machine_readable(format)),
+        Some(format) => format.strip_prefix('=').is_some_and(is_machine_readable),
+        None => false,
+    })
+}
+
 enum DirState {
     Temp(tempfile::TempDir),
     Perm(PathBuf),
@@ -287,6 +388,19 @@ struct UnusedExterns {
     unused_extern_names: Vec<String>,
 }
 
+/// The parts of the JSON diagnostics of rustc that doctests look at.
+#[derive(serde::Deserialize)]
+struct JsonDiagnostic {
+    level: String,
+    code: Option<JsonDiagnosticCode>,
+    rendered: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct JsonDiagnosticCode {
+    code: String,
+}
+
 fn add_exe_suffix(input: String, target: &TargetTriple) -> String {
     let exe_suffix = match target {
         TargetTriple::TargetTriple(_) => Target::expect_builtin(target).options.exe_suffix,
@@ -297,30 +411,14 @@ fn add_exe_suffix(input: String, target: &TargetTriple) -> String {
     input + &exe_suffix
 }
 
-fn run_test(
-    test: &str,
-    crate_name: &str,
-    line: usize,
-    rustdoc_options: RustdocOptions,
-    mut lang_string: LangString,
-    no_run: bool,
-    runtool: Option<String>,
-    runtool_args: Vec<String>,
-    target: TargetTriple,
-    opts: &GlobalTestOptions,
+/// Creates the `rustc` command compiling doctests, with the options shared by all of them.
+fn doctest_compiler(
+    rustdoc_options: &RustdocOptions,
     edition: Edition,
-    outdir: DirState,
-    path: PathBuf,
-    test_id: &str,
-    report_unused_externs: impl Fn(UnusedExterns),
-) -> Result<(), TestFailure> {
-    let (test, line_offset, supports_color) =
-        make_test(test, Some(crate_name), lang_string.test_harness, opts, edition, Some(test_id));
-
-    // Make sure we emit well-formed executable names for our target.
-    let rust_out = add_exe_suffix("rust_out".to_owned(), &target);
-    let output_file = outdir.path().join(rust_out);
-
+    target: &TargetTriple,
+    supports_color: bool,
+    json_diagnostics: bool,
+) -> Command {
     let rustc_binary = rustdoc_options
         .test_builder
         .as_deref()
@@ -336,22 +434,13 @@ fn run_test(
             compiler.arg("--check-cfg").arg(&check_cfg);
         }
     }
-    if let Some(sysroot) = rustdoc_options.maybe_sysroot {
+    if let Some(sysroot) = &rustdoc_options.maybe_sysroot {
         compiler.arg("--sysroot").arg(sysroot);
     }
-    compiler.arg("--edition").arg(&edition.to_string());
-    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
-    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
-    if lang_string.test_harness {
-        compiler.arg("--test");
-    }
-    if rustdoc_options.json_unused_externs.is_enabled() && !lang_string.compile_fail {
-        compiler.arg("--error-format=json");
-        compiler.arg("--json").arg("unused-externs");
+    if !edition.is_stable() {
         compiler.arg("-Z").arg("unstable-options");
-        compiler.arg("-W").arg("unused_crate_dependencies");
     }
+    compiler.arg("--edition").arg(&edition.to_string());
     for lib_str in &rustdoc_options.lib_strs {
         compiler.arg("-L").arg(&lib_str);
     }
@@ -365,34 +454,94 @@ fn run_test(
     for unstable_option_str in &rustdoc_options.unstable_opts_strs {
         compiler.arg("-Z").arg(&unstable_option_str);
     }
-    if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
-        compiler.arg("--emit=metadata");
-    }
     compiler.arg("--target").arg(match target {
-        TargetTriple::TargetTriple(s) => s,
+        TargetTriple::TargetTriple(s) => s.clone(),
         TargetTriple::TargetJson { path_for_rustdoc, .. } => {
             path_for_rustdoc.to_str().expect("target path must be valid unicode").to_string()
         }
     });
-    if let ErrorOutputType::HumanReadable(kind) = rustdoc_options.error_format {
-        let (short, color_config) = kind.unzip();
-
+    let (short, color) = match rustdoc_options.error_format {
+        ErrorOutputType::HumanReadable(kind) => {
+            let (short, color_config) = kind.unzip();
+            let color = match color_config {
+                ColorConfig::Never => false,
+                ColorConfig::Always => true,
+                ColorConfig::Auto => supports_color,
+            };
+            (short, Some(color))
+        }
+        ErrorOutputType::Json { .. } => (false, None),
+    };
+    if json_diagnostics {
+        // The diagnostics are still rendered by rustc, to be displayed as usual.
+        compiler.arg("--error-format=json");
+        if short {
+            compiler.arg("--json=diagnostic-short");
+        }
+        if color == Some(true) {
+            compiler.arg("--json=diagnostic-rendered-ansi");
+        }
+    } else {
         if short {
             compiler.arg("--error-format").arg("short");
         }
-
-        match color_config {
-            ColorConfig::Never => {
-                compiler.arg("--color").arg("never");
-            }
-            ColorConfig::Always => {
-                compiler.arg("--color").arg("always");
-            }
-            ColorConfig::Auto => {
-                compiler.arg("--color").arg(if supports_color { "always" } else { "never" });
-            }
+        if let Some(color) = color {
+            compiler.arg("--color").arg(if color { "always" } else { "never" });
         }
     }
+    compiler
+}
+
+fn run_test(
+    test: &str,
+    crate_name: &str,
+    line: usize,
+    rustdoc_options: RustdocOptions,
+    mut lang_string: LangString,
+    no_run: bool,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    target: TargetTriple,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    outdir: DirState,
+    path: PathBuf,
+    test_id: &str,
+    report_unused_externs: impl Fn(UnusedExterns),
+) -> Result<(), TestFailure> {
+    let (test, line_offset, supports_color) = make_test(
+        test,
+        Some(crate_name),
+        lang_string.test_harness,
+        lang_string.expected_panic.as_deref(),
+        opts,
+        edition,
+        Some(test_id),
+    );
+
+    // Make sure we emit well-formed executable names for our target.
+    let rust_out = add_exe_suffix("rust_out".to_owned(), &target);
+    let output_file = outdir.path().join(rust_out);
+
+    // The error codes of `compile_fail` doctests are read from the JSON diagnostics.
+    let check_error_codes = lang_string.compile_fail && !lang_string.error_codes.is_empty();
+    let mut compiler =
+        doctest_compiler(&rustdoc_options, edition, &target, supports_color, check_error_codes);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
+    compiler.arg("-o").arg(&output_file);
+    if lang_string.test_harness {
+        compiler.arg("--test");
+    }
+    if rustdoc_options.json_unused_externs.is_enabled() && !lang_string.compile_fail {
+        compiler.arg("--error-format=json");
+        compiler.arg("--json").arg("unused-externs");
+        compiler.arg("-Z").arg("unstable-options");
+        compiler.arg("-W").arg("unused_crate_dependencies");
+    }
+    if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
+        compiler.arg("--emit=metadata");
+    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
@@ -413,24 +562,25 @@ fn drop(&mut self) {
             eprint!("{}", self.0);
         }
     }
-    let mut out = str::from_utf8(&output.stderr)
-        .unwrap()
-        .lines()
-        .filter(|l| {
-            if let Ok(uext) = serde_json::from_str::<UnusedExterns>(l) {
-                report_unused_externs(uext);
-                false
-            } else {
-                true
+    let mut out = String::new();
+    let mut emitted_codes = Vec::new();
+    for line in str::from_utf8(&output.stderr).unwrap().lines() {
+        if let Ok(uext) = serde_json::from_str::<UnusedExterns>(line) {
+            report_unused_externs(uext);
+        } else if check_error_codes &&
+            let Ok(diagnostic) = serde_json::from_str::<JsonDiagnostic>(line)
+        {
+            if diagnostic.level == "error" &&
+                let Some(code) = diagnostic.code &&
+                !emitted_codes.contains(&code.code)
+            {
+                emitted_codes.push(code.code);
             }
-        })
-        .intersperse_with(|| "\n")
-        .collect::<String>();
-
-    // Add a \n to the end to properly terminate the last line,
-    // but only if there was output to be printed
-    if !out.is_empty() {
-        out.push('\n');
+            out.push_str(&diagnostic.rendered.unwrap_or_default());
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
     }
 
     let _bomb = Bomb(&out);
@@ -440,14 +590,14 @@ fn drop(&mut self) {
         }
         (true, false) => {}
         (false, true) => {
-            if !lang_string.error_codes.is_empty() {
-                // We used to check if the output contained "error[{}]: " but since we added the
-                // colored output, we can't anymore because of the color escape characters before
-                // the ":".
-                lang_string.error_codes.retain(|err| !out.contains(&format!("error[{err}]")));
+            if check_error_codes {
+                lang_string.error_codes.retain(|code| !emitted_codes.contains(code));
 
                 if !lang_string.error_codes.is_empty() {
-                    return Err(TestFailure::MissingErrorCodes(lang_string.error_codes));
+                    return Err(TestFailure::MissingErrorCodes {
+                        missing: lang_string.error_codes,
+                        emitted: emitted_codes,
+                    });
                 }
             }
         }
@@ -460,18 +610,40 @@ fn drop(&mut self) {
         return Ok(());
     }
 
+    run_test_binary(&output_file, None, &rustdoc_options, runtool, runtool_args, &lang_string)
+}
+
+/// The environment variable telling doctests which directory they run in.
+const RUN_DIRECTORY_ENV: &str = "RUSTDOC_DOCTEST_RUN_DIRECTORY";
+
+/// Runs a compiled doctest. `merged_index` is the index of the doctest within the binary if it
+/// was compiled together with others.
+fn run_test_binary(
+    binary: &Path,
+    merged_index: Option<usize>,
+    rustdoc_options: &RustdocOptions,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    lang_string: &LangString,
+) -> Result<(), TestFailure> {
     // Run the code!
     let mut cmd;
 
     if let Some(tool) = runtool {
         cmd = Command::new(tool);
         cmd.args(runtool_args);
-        cmd.arg(output_file);
+        cmd.arg(binary);
     } else {
-        cmd = Command::new(output_file);
+        cmd = Command::new(binary);
+    }
+    if let Some(index) = merged_index {
+        cmd.env(MERGED_DOCTEST_ENV, index.to_string());
     }
-    if let Some(run_directory) = rustdoc_options.test_run_directory {
+    if let Some(run_directory) = &rustdoc_options.test_run_directory {
         cmd.current_dir(run_directory);
+        cmd.env(RUN_DIRECTORY_ENV, run_directory);
+    } else if let Ok(current_dir) = env::current_dir() {
+        cmd.env(RUN_DIRECTORY_ENV, current_dir);
     }
 
     let result = if rustdoc_options.nocapture {
@@ -486,9 +658,14 @@ fn drop(&mut self) {
     match result {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
         Ok(out) => {
-            if lang_string.should_panic && out.status.success() {
+            let should_panic = lang_string.should_panic;
+            if should_panic && out.status.success() {
                 return Err(TestFailure::UnexpectedRunPass);
-            } else if !lang_string.should_panic && !out.status.success() {
+            } else if !should_panic && !out.status.success() {
+                return Err(TestFailure::ExecutionFailure(out));
+            } else if lang_string.expected_panic.is_some()
+                && out.status.code() == Some(UNEXPECTED_PANIC_EXIT_CODE)
+            {
                 return Err(TestFailure::ExecutionFailure(out));
             }
         }
@@ -497,37 +674,77 @@ fn drop(&mut self) {
     Ok(())
 }
 
+/// The exit code of doctests that panicked without the message they expected.
+const UNEXPECTED_PANIC_EXIT_CODE: i32 = 102;
+
+/// Checks the payload of the panic of a `should_panic="..."` doctest the way libtest does for
+/// `#[should_panic(expected = "...")]`, resuming the panic if it has the expected message.
+const CHECK_PANIC_FN: &str = r#"
+fn __rustdoc_check_panic(
+    payload: ::std::boxed::Box<dyn ::std::any::Any + ::std::marker::Send>,
+    expected: &str,
+) {
+    let message = payload
+        .downcast_ref::<::std::string::String>()
+        .map(|message| message.as_str())
+        .or_else(|| payload.downcast_ref::<&'static str>().copied());
+    match message {
+        Some(message) if message.contains(expected) => ::std::panic::resume_unwind(payload),
+        Some(message) => {
+            eprintln!("panic did not contain expected string");
+            eprintln!("      panic message: `{message:?}`,");
+            eprintln!(" expected substring: `{expected:?}`");
+        }
+        None => {
+            eprintln!("expected panic with string value,");
+            eprintln!(" found non-string value: `{:?}`", ::std::any::Any::type_id(&*payload));
+            eprintln!("     expected substring: `{expected:?}`");
+        }
+    }
+}
+"#;
+
 /// Transforms a test into code that can be compiled into a Rust binary, and returns the number of
 /// lines before the test code begins as well as if the output stream supports colors or not.
+///
+/// If `expected_panic` is set, the `main` function generated for the test exits with
+/// `UNEXPECTED_PANIC_EXIT_CODE` when the test panics without this message.
 pub(crate) fn make_test(
     s: &str,
     crate_name: Option<&str>,
     dont_insert_main: bool,
+    expected_panic: Option<&str>,
     opts: &GlobalTestOptions,
     edition: Edition,
     test_id: Option<&str>,
 ) -> (String, usize, bool) {
+    let (prog, line_offset, supports_color, _) =
+        make_test_inner(s, crate_name, dont_insert_main, expected_panic, opts, edition, test_id);
+    (prog, line_offset, supports_color)
+}
+
+/// Whether the panic message expected by a `should_panic="..."` doctest is checked. It can only
+/// be when rustdoc generates the `main` function of the doctest.
+pub(crate) fn checks_expected_panic(s: &str, dont_insert_main: bool, edition: Edition) -> bool {
+    let opts = GlobalTestOptions::default();
+    make_test_inner(s, None, dont_insert_main, Some(""), &opts, edition, None).3
+}
+
+/// Like `make_test`, also returning whether a `main` function was generated for the test.
+fn make_test_inner(
+    s: &str,
+    crate_name: Option<&str>,
+    dont_insert_main: bool,
+    expected_panic: Option<&str>,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    test_id: Option<&str>,
+) -> (String, usize, bool, bool) {
     let (crate_attrs, everything_else, crates) = partition_source(s, edition);
     let everything_else = everything_else.trim();
-    let mut line_offset = 0;
-    let mut prog = String::new();
+    let (mut prog, mut line_offset) = doctest_crate_attrs(opts);
     let mut supports_color = false;
 
-    if opts.attrs.is_empty() {
-        // If there aren't any attributes supplied by #![doc(test(attr(...)))], then allow some
-        // lints that are commonly triggered in doctests. The crate-level test attributes are
-        // commonly used to make tests fail in case they trigger warnings, so having this there in
-        // that case may cause some tests to pass when they shouldn't have.
-        prog.push_str("#![allow(unused)]\n");
-        line_offset += 1;
-    }
-
-    // Next, any attributes that came from the crate root via #![doc(test(attr(...)))].
-    for attr in &opts.attrs {
-        prog.push_str(&format!("#![{attr}]\n"));
-        line_offset += 1;
-    }
-
     // Now push any outer attributes from the example, assuming they
     // are intended to be crate attributes.
     prog.push_str(&crate_attrs);
@@ -652,7 +869,7 @@ pub(crate) fn make_test(
     else {
         // If the parser panicked due to a fatal error, pass the test code through unchanged.
         // The error will be reported during compilation.
-        return (s.to_owned(), 0, false);
+        return (s.to_owned(), 0, false, false);
     };
 
     // If a doctest's `fn main` is being masked by a wrapper macro, the parsing loop above won't
@@ -678,7 +895,7 @@ pub(crate) fn make_test(
             // NOTE: this is terribly inaccurate because it doesn't actually
             // parse the source, but only has false positives, not false
             // negatives.
-            if s.contains(crate_name) {
+            if s.contains(crate_name) || opts.prelude.iter().any(|s| s.contains(crate_name)) {
                 // rustdoc implicitly inserts an `extern crate` item for the own crate
                 // which may be unused, so we need to allow the lint.
                 prog.push_str(&format!("#[allow(unused_extern_crates)]\n"));
@@ -690,9 +907,20 @@ pub(crate) fn make_test(
     }
 
     // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+    let insert_main = !dont_insert_main && !already_has_main && !prog.contains("![no_std]");
+    if !insert_main {
+        // The prelude is made of statements, which have nowhere to go without the `fn main` we
+        // generate.
         prog.push_str(everything_else);
     } else {
+        // The prelude goes right before the code of the test, on lines of its own.
+        let mut prelude = String::new();
+        for statement in &opts.prelude {
+            prelude.push_str(statement);
+            prelude.push('\n');
+            line_offset += statement.matches('\n').count() + 1;
+        }
+
         let returns_result = everything_else.trim_end().ends_with("(())");
         // Give each doctest main function a unique name.
         // This is for example needed for the tooling around `-C instrument-coverage`.
@@ -702,7 +930,21 @@ pub(crate) fn make_test(
             "_inner".into()
         };
         let inner_attr = if test_id.is_some() { "#[allow(non_snake_case)] " } else { "" };
-        let (main_pre, main_post) = if returns_result {
+        let (main_pre, main_post) = if let Some(expected) = expected_panic {
+            let (return_type, call) = if returns_result {
+                (" -> Result<(), impl core::fmt::Debug>", format!("{inner_fn_name}().unwrap()"))
+            } else {
+                ("", format!("{inner_fn_name}()"))
+            };
+            (
+                format!("fn main() {{ {inner_attr}fn {inner_fn_name}(){return_type} {{\n"),
+                format!(
+                    "\n}} if let Err(payload) = ::std::panic::catch_unwind(|| {call}) {{ \
+                     __rustdoc_check_panic(payload, {expected:?}); \
+                     ::std::process::exit({UNEXPECTED_PANIC_EXIT_CODE}) }} }}\n{CHECK_PANIC_FN}"
+                ),
+            )
+        } else if returns_result {
             (
                 format!(
                     "fn main() {{ {inner_attr}fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
@@ -727,12 +969,176 @@ pub(crate) fn make_test(
         // /// ``` <- end of the inner main
         line_offset += 1;
 
-        prog.extend([&main_pre, everything_else, &main_post].iter().cloned());
+        prog.extend([&main_pre, &prelude, everything_else, &main_post].iter().cloned());
     }
 
     debug!("final doctest:\n{prog}");
 
-    (prog, line_offset, supports_color)
+    (prog, line_offset, supports_color, insert_main)
+}
+
+/// Returns the crate-level attributes all doctests start with, and the number of lines they take.
+fn doctest_crate_attrs(opts: &GlobalTestOptions) -> (String, usize) {
+    let mut line_offset = 0;
+    let mut attrs = String::new();
+
+    if opts.attrs.is_empty() {
+        // If there aren't any attributes supplied by #![doc(test(attr(...)))], then allow some
+        // lints that are commonly triggered in doctests. The crate-level test attributes are
+        // commonly used to make tests fail in case they trigger warnings, so having this there in
+        // that case may cause some tests to pass when they shouldn't have.
+        attrs.push_str("#![allow(unused)]\n");
+        line_offset += 1;
+    }
+
+    // Next, any attributes that came from the crate root via #![doc(test(attr(...)))].
+    for attr in &opts.attrs {
+        attrs.push_str(&format!("#![{attr}]\n"));
+        line_offset += 1;
+    }
+
+    (attrs, line_offset)
+}
+
+/// Transforms a test into a module of a crate merging several doctests, whose
+/// `__rustdoc_main` function runs the test. The code of the test is moved down to the line
+/// following `line`, so that the module can be mapped onto the file the test comes from.
+///
+/// Returns `None` if the test has attributes of its own for the whole crate, which stop it
+/// from sharing a crate with other tests.
+pub(crate) fn make_merged_test(
+    s: &str,
+    crate_name: &str,
+    line: usize,
+    expected_panic: Option<&str>,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    test_id: &str,
+) -> Option<String> {
+    let (crate_attrs, _, crates) = partition_source(s, edition);
+    if crate_attrs.lines().any(|line| line.trim_start().starts_with("#![")) {
+        return None;
+    }
+    // `#[macro_use]` is only allowed on crates imported at the root.
+    if crates.contains("#[macro_use]") {
+        return None;
+    }
+
+    let (prog, line_offset, _) =
+        make_test(s, Some(crate_name), false, expected_panic, opts, edition, Some(test_id));
+    let (attrs, attrs_line_offset) = doctest_crate_attrs(opts);
+    // If the test failed to parse, it has been left as is and is best compiled on its own.
+    let module = prog.strip_prefix(&attrs)?;
+    let padding = "\n".repeat(line.saturating_sub(line_offset - attrs_line_offset));
+
+    Some(format!(
+        "{padding}{module}\n\
+         pub(crate) fn __rustdoc_main() -> impl ::std::process::Termination {{ main() }}\n"
+    ))
+}
+
+/// Doctests compiled together into a single binary, see `--merge-doctests`. The binary is built
+/// by the first of them to run.
+struct MergedDoctests {
+    edition: Edition,
+    crate_attrs: String,
+    outdir: DirState,
+    /// The module of each doctest, and the file it comes from.
+    tests: Mutex<Vec<(String, PathBuf)>>,
+    /// The compiled binary, or `None` if the doctests failed to compile together, in which case
+    /// each of them gets compiled on its own.
+    binary: OnceLock<Option<PathBuf>>,
+}
+
+/// Number of doctests compiled into the same binary.
+const MERGED_DOCTESTS_PER_BINARY: usize = 64;
+
+/// Environment variable telling a binary of merged doctests which one to run.
+const MERGED_DOCTEST_ENV: &str = "RUSTDOC_MERGED_DOCTEST";
+
+impl MergedDoctests {
+    fn new(edition: Edition, opts: &GlobalTestOptions, outdir: DirState) -> MergedDoctests {
+        MergedDoctests {
+            edition,
+            crate_attrs: doctest_crate_attrs(opts).0,
+            outdir,
+            tests: Mutex::new(Vec::new()),
+            binary: OnceLock::new(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.tests.lock().unwrap().len() >= MERGED_DOCTESTS_PER_BINARY
+    }
+
+    /// Adds a doctest created by `make_merged_test`, returning its index in the binary.
+    fn push(&self, module: String, path: PathBuf) -> usize {
+        let mut tests = self.tests.lock().unwrap();
+        tests.push((module, path));
+        tests.len() - 1
+    }
+
+    fn binary(&self, rustdoc_options: &RustdocOptions, target: &TargetTriple) -> Option<&Path> {
+        self.binary.get_or_init(|| self.compile(rustdoc_options, target)).as_deref()
+    }
+
+    fn compile(&self, rustdoc_options: &RustdocOptions, target: &TargetTriple) -> Option<PathBuf> {
+        let tests = self.tests.lock().unwrap();
+        let dir = self.outdir.path();
+        let root = dir.join("doctests.rs");
+        let output_file = dir.join(add_exe_suffix("rust_out".to_owned(), target));
+        let mut compiler = doctest_compiler(rustdoc_options, self.edition, target, false, false);
+
+        let mut source = self.crate_attrs.clone();
+        let mut dispatch = String::new();
+        for (i, (module, path)) in tests.iter().enumerate() {
+            let file = format!("doctest_{i}.rs");
+            std::fs::write(dir.join(&file), module).ok()?;
+            // Point diagnostics and panics back to the file the doctest comes from.
+            let mut remap = dir.join(&file).into_os_string();
+            remap.push("=");
+            remap.push(path);
+            compiler.arg("--remap-path-prefix").arg(remap);
+
+            source.push_str(&format!("#[path = \"{file}\"]\nmod doctest_{i};\n"));
+            dispatch.push_str(&format!(
+                "        \"{i}\" => Termination::report(doctest_{i}::__rustdoc_main()),\n"
+            ));
+        }
+        // The doctest to run is picked by index, so that the tests see the same arguments as
+        // when they are compiled on their own.
+        source.push_str("fn main() -> ::std::process::ExitCode {\n");
+        source.push_str("    use ::std::process::Termination;\n");
+        source.push_str(&format!(
+            "    match &*::std::env::var(\"{MERGED_DOCTEST_ENV}\").unwrap_or_default() {{\n"
+        ));
+        source.push_str(&dispatch);
+        source.push_str("        _ => ::std::process::ExitCode::FAILURE,\n    }\n}\n");
+        std::fs::write(&root, source).ok()?;
+
+        compiler.arg("-o").arg(&output_file);
+        compiler.arg(&root);
+        debug!("compiler invocation for merged doctests: {:?}", compiler);
+
+        let output = compiler.output().expect("Failed to spawn rustc process");
+        if output.status.success() {
+            Some(output_file)
+        } else {
+            debug!(
+                "merged doctests failed to compile, compiling them one by one:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            // Written past the output capture of the doctest compiling the binary, as this is
+            // about all of the doctests in it.
+            let _ = writeln!(
+                io::stderr(),
+                "note: {} doctests failed to compile together, so each of them is compiled on its \
+                own; mark the ones that need a crate of their own with `standalone_crate`",
+                tests.len(),
+            );
+            None
+        }
+    }
 }
 
 fn check_if_attr_is_complete(source: &str, edition: Edition) -> bool {
@@ -883,6 +1289,43 @@ enum PartitionState {
     (before, after, crates)
 }
 
+/// Creates the directory doctest executables are written to, within `--persist-doctests` if it
+/// was passed.
+fn make_outdir(rustdoc_options: &RustdocOptions, name: &str) -> DirState {
+    if let Some(mut path) = rustdoc_options.persist_doctests.clone() {
+        path.push(name);
+
+        if let Err(err) = std::fs::create_dir_all(&path) {
+            eprintln!("Couldn't create directory for doctest executables: {}", err);
+            panic::resume_unwind(Box::new(()));
+        }
+
+        DirState::Perm(path)
+    } else {
+        DirState::Temp(
+            TempFileBuilder::new()
+                .prefix("rustdoctest")
+                .tempdir()
+                .expect("rustdoc needs a tempdir"),
+        )
+    }
+}
+
+/// The name of the file, within `--persist-doctests`, listing where each persisted doctest comes
+/// from.
+const PERSISTED_MANIFEST: &str = "doctests.json";
+
+/// An entry of the manifest of persisted doctests.
+#[derive(serde::Serialize)]
+struct PersistedDoctest {
+    /// The directory the doctest was persisted in.
+    name: String,
+    /// The name of the test, as displayed by libtest.
+    test: String,
+    file: String,
+    line: usize,
+}
+
 pub(crate) trait Tester {
     fn add_test(&mut self, test: String, config: LangString, line: usize);
     fn get_line(&self) -> usize {
@@ -893,6 +1336,8 @@ fn register_header(&mut self, _name: &str, _level: u32) {}
 
 pub(crate) struct Collector {
     pub(crate) tests: Vec<test::TestDescAndFn>,
+    /// The name of each test, and the path of the item it documents. See `item_path`.
+    pub(crate) item_paths: Vec<(String, String)>,
 
     // The name of the test displayed to the user, separated by `::`.
     //
@@ -927,6 +1372,12 @@ pub(crate) struct Collector {
     visited_tests: FxHashMap<(String, usize), usize>,
     unused_extern_reports: Arc<Mutex<Vec<UnusedExterns>>>,
     compiling_test_count: AtomicUsize,
+    /// The binary new doctests of each edition get merged into.
+    merged_doctests: FxHashMap<Edition, Arc<MergedDoctests>>,
+    merged_binary_count: usize,
+    /// The number of doctests persisted so far for each item path.
+    persisted_counts: FxHashMap<String, usize>,
+    persisted_doctests: Vec<PersistedDoctest>,
 }
 
 impl Collector {
@@ -941,6 +1392,7 @@ pub(crate) fn new(
     ) -> Collector {
         Collector {
             tests: Vec::new(),
+            item_paths: Vec::new(),
             names: Vec::new(),
             rustdoc_options,
             use_headers,
@@ -953,9 +1405,58 @@ pub(crate) fn new(
             visited_tests: FxHashMap::default(),
             unused_extern_reports: Default::default(),
             compiling_test_count: AtomicUsize::new(0),
+            merged_doctests: FxHashMap::default(),
+            merged_binary_count: 0,
+            persisted_counts: FxHashMap::default(),
+            persisted_doctests: Vec::new(),
         }
     }
 
+    /// Whether a doctest can be compiled together with others, as long as it doesn't have
+    /// attributes for the whole crate.
+    fn can_merge(&self, config: &LangString, edition: Edition, no_run: bool, ignore: bool) -> bool {
+        let enabled = match self.rustdoc_options.merge_doctests {
+            MergeDoctests::Never => false,
+            MergeDoctests::Auto => edition.at_least_rust_2024(),
+            // Extern crates can't be imported in modules in the 2015 edition.
+            MergeDoctests::Always => edition.at_least_rust_2018(),
+        };
+        enabled
+            && !ignore
+            && !no_run
+            && !config.compile_fail
+            && !config.test_harness
+            && !config.standalone_crate
+            && !self.rustdoc_options.json_unused_externs.is_enabled()
+            // Each persisted doctest gets a binary of its own, listed in the manifest.
+            && self.rustdoc_options.persist_doctests.is_none()
+    }
+
+    /// Adds a doctest to the binary of its edition, returning the binary and the index of the
+    /// doctest in it.
+    fn add_merged_test(
+        &mut self,
+        edition: Edition,
+        module: String,
+        path: PathBuf,
+    ) -> (Arc<MergedDoctests>, usize) {
+        let merged = match self.merged_doctests.get(&edition) {
+            Some(merged) if !merged.is_full() => merged.clone(),
+            _ => {
+                let outdir = make_outdir(
+                    &self.rustdoc_options,
+                    &format!("merged_{edition}_{}", self.merged_binary_count),
+                );
+                self.merged_binary_count += 1;
+                let merged = Arc::new(MergedDoctests::new(edition, &self.opts, outdir));
+                self.merged_doctests.insert(edition, merged.clone());
+                merged
+            }
+        };
+        let index = merged.push(module, path);
+        (merged, index)
+    }
+
     fn generate_name(&self, line: usize, filename: &FileName) -> String {
         let mut item_path = self.names.join("::");
         item_path.retain(|c| c != ' ');
@@ -965,6 +1466,58 @@ fn generate_name(&self, line: usize, filename: &FileName) -> String {
         format!("{} - {}(line {})", filename.prefer_local(), item_path, line)
     }
 
+    /// The path of the item whose docs are being collected, starting with the name of the crate.
+    /// In Markdown files, it is made of the name of the file and of the titles of the sections
+    /// instead.
+    fn item_path(&self) -> String {
+        let mut path = match &self.filename {
+            Some(filename) => {
+                filename.file_stem().unwrap_or_default().to_string_lossy().into_owned()
+            }
+            None => self.crate_name.clone(),
+        };
+        for name in &self.names {
+            path.push_str("::");
+            path.push_str(name);
+        }
+        path.retain(|c| c != ' ');
+        path
+    }
+
+    /// The name of the directory the next doctest of the current item is persisted in, made of the
+    /// path of the item, of the index of the doctest among the ones of the item and of a hash of
+    /// its code. Unlike the line of the doctest, none of them changes when unrelated docs do.
+    fn persisted_name(&mut self, test: &str) -> String {
+        let item_path = self.item_path();
+        let count = self.persisted_counts.entry(item_path.clone()).or_insert(0);
+        let index = *count;
+        *count += 1;
+
+        // The hash must be the same whatever the version of rustdoc and the host running it, so
+        // it hashes the bytes of the code with fixed keys.
+        let mut hasher = StableHasher::new();
+        hasher.write(test.as_bytes());
+        let (hash, _) = hasher.finalize();
+        // For example `krate::Type::method` would become `krate__Type__method`
+        let item_path = item_path
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("{item_path}_{index}_{hash:016x}")
+    }
+
+    /// Writes the manifest mapping the directories of persisted doctests to their sources, if
+    /// `--persist-doctests` was passed.
+    pub(crate) fn write_persisted_manifest(&self) {
+        let Some(persist_doctests) = &self.rustdoc_options.persist_doctests else { return };
+        let manifest = serde_json::to_string(&self.persisted_doctests).unwrap();
+        if let Err(err) = std::fs::create_dir_all(persist_doctests)
+            .and_then(|()| std::fs::write(persist_doctests.join(PERSISTED_MANIFEST), manifest))
+        {
+            eprintln!("Couldn't write the manifest of persisted doctests: {}", err);
+        }
+    }
+
     pub(crate) fn set_position(&mut self, position: Span) {
         self.position = position;
     }
@@ -993,7 +1546,10 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
         let filename = self.get_filename();
         let name = self.generate_name(line, &filename);
         let crate_name = self.crate_name.clone();
-        let opts = self.opts.clone();
+        let mut opts = self.opts.clone();
+        if config.no_prelude {
+            opts.prelude.clear();
+        }
         let edition = config.edition.unwrap_or(self.rustdoc_options.edition);
         let rustdoc_options = self.rustdoc_options.clone();
         let runtool = self.rustdoc_options.runtool.clone();
@@ -1002,6 +1558,7 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
         let target_str = target.to_string();
         let unused_externs = self.unused_extern_reports.clone();
         let no_run = config.no_run || rustdoc_options.no_run;
+        let machine_readable = machine_readable_test_output(&rustdoc_options.test_args);
         if !config.compile_fail {
             self.compiling_test_count.fetch_add(1, Ordering::SeqCst);
         }
@@ -1035,33 +1592,49 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 self.visited_tests.entry((file.clone(), line)).and_modify(|v| *v += 1).or_insert(0)
             },
         );
-        let outdir = if let Some(mut path) = rustdoc_options.persist_doctests.clone() {
-            path.push(&test_id);
-
-            if let Err(err) = std::fs::create_dir_all(&path) {
-                eprintln!("Couldn't create directory for doctest executables: {}", err);
-                panic::resume_unwind(Box::new(()));
-            }
-
-            DirState::Perm(path)
+        let outdir = if rustdoc_options.persist_doctests.is_some() {
+            let persisted_name = self.persisted_name(&test);
+            let outdir = make_outdir(&rustdoc_options, &persisted_name);
+            self.persisted_doctests.push(PersistedDoctest {
+                name: persisted_name,
+                test: name.clone(),
+                file: filename.prefer_local().to_string(),
+                line,
+            });
+            Some(outdir)
         } else {
-            DirState::Temp(
-                TempFileBuilder::new()
-                    .prefix("rustdoctest")
-                    .tempdir()
-                    .expect("rustdoc needs a tempdir"),
-            )
+            // Made when the doctest is compiled on its own, which merged doctests are not unless
+            // their binary fails to compile.
+            None
+        };
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+        let merged = if self.can_merge(&config, edition, no_run, ignore)
+            && let Some(module) =
+                make_merged_test(
+                    &test,
+                    &crate_name,
+                    line,
+                    config.expected_panic.as_deref(),
+                    &opts,
+                    edition,
+                    &test_id,
+                )
+        {
+            Some(self.add_merged_test(edition, module, path.clone()))
+        } else {
+            None
         };
 
         debug!("creating test {name}: {test}");
+        self.item_paths.push((name.clone(), self.item_path()));
         self.tests.push(test::TestDescAndFn {
             desc: test::TestDesc {
                 name: test::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
+                ignore,
                 ignore_message: None,
                 source_file: "",
                 start_line: 0,
@@ -1078,46 +1651,45 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 let report_unused_externs = |uext| {
                     unused_externs.lock().unwrap().push(uext);
                 };
-                let res = run_test(
-                    &test,
-                    &crate_name,
-                    line,
-                    rustdoc_options,
-                    config,
-                    no_run,
-                    runtool,
-                    runtool_args,
-                    target,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                    &test_id,
-                    report_unused_externs,
-                );
+                let res = if let Some((merged, index)) = &merged
+                    && let Some(binary) = merged.binary(&rustdoc_options, &target)
+                {
+                    run_test_binary(
+                        binary,
+                        Some(*index),
+                        &rustdoc_options,
+                        runtool,
+                        runtool_args,
+                        &config,
+                    )
+                } else {
+                    let outdir = outdir.unwrap_or_else(|| make_outdir(&rustdoc_options, &test_id));
+                    run_test(
+                        &test,
+                        &crate_name,
+                        line,
+                        rustdoc_options,
+                        config,
+                        no_run,
+                        runtool,
+                        runtool_args,
+                        target,
+                        &opts,
+                        edition,
+                        outdir,
+                        path,
+                        &test_id,
+                        report_unused_externs,
+                    )
+                };
 
                 if let Err(err) = res {
+                    let description = err.description();
                     match err {
-                        TestFailure::CompileError => {
-                            eprint!("Couldn't compile the test.");
-                        }
-                        TestFailure::UnexpectedCompilePass => {
-                            eprint!("Test compiled successfully, but it's marked `compile_fail`.");
-                        }
-                        TestFailure::UnexpectedRunPass => {
-                            eprint!("Test executable succeeded, but it's marked `should_panic`.");
-                        }
-                        TestFailure::MissingErrorCodes(codes) => {
-                            eprint!("Some expected error codes were not found: {:?}", codes);
-                        }
-                        TestFailure::ExecutionError(err) => {
-                            eprint!("Couldn't run the test: {err}");
-                            if err.kind() == io::ErrorKind::PermissionDenied {
-                                eprint!(" - maybe your tempdir is mounted with noexec?");
-                            }
-                        }
                         TestFailure::ExecutionFailure(out) => {
-                            eprintln!("Test executable failed ({reason}).", reason = out.status);
+                            if !machine_readable {
+                                eprintln!("{description}");
+                            }
 
                             // FIXME(#12309): An unfortunate side-effect of capturing the test
                             // executable's output is that the relative ordering between the test's
@@ -1132,7 +1704,9 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                             let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
 
                             if !stdout.is_empty() || !stderr.is_empty() {
-                                eprintln!();
+                                if !machine_readable {
+                                    eprintln!();
+                                }
 
                                 if !stdout.is_empty() {
                                     eprintln!("stdout:\n{stdout}");
@@ -1143,8 +1717,13 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                                 }
                             }
                         }
+                        _ if !machine_readable => eprint!("{description}"),
+                        _ => {}
                     }
 
+                    if machine_readable {
+                        return Err(description);
+                    }
                     panic::resume_unwind(Box::new(()));
                 }
                 Ok(())
@@ -1,4 +1,7 @@
-use super::{make_test, GlobalTestOptions};
+use super::{
+    add_item_path_filters, checks_expected_panic, make_merged_test, make_test, GlobalTestOptions,
+    CHECK_PANIC_FN,
+};
 use rustc_span::edition::DEFAULT_EDITION;
 
 #[test]
@@ -11,7 +14,7 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -26,7 +29,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -45,7 +49,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 3));
 }
 
@@ -53,7 +58,7 @@ fn main() {
 fn make_test_no_crate_inject() {
     // Even if you do use the crate within the test, setting `opts.no_crate_inject` will skip
     // adding it anyway.
-    let opts = GlobalTestOptions { no_crate_inject: true, attrs: vec![] };
+    let opts = GlobalTestOptions { no_crate_inject: true, attrs: vec![], prelude: vec![] };
     let input = "use asdf::qwop;
 assert_eq!(2+2, 4);";
     let expected = "#![allow(unused)]
@@ -62,7 +67,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -80,7 +86,7 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("std"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, Some("std"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -99,7 +105,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -116,7 +123,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -136,7 +144,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 3));
 
     // Adding more will also bump the returned line offset.
@@ -150,7 +159,8 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 4));
 }
 
@@ -167,7 +177,7 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -183,7 +193,7 @@ fn main() {
     assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 1));
 }
 
@@ -199,7 +209,7 @@ fn main() {
 assert_eq!(2+2, 4);
 }"
     .to_string();
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -213,7 +223,7 @@ fn make_test_dont_insert_main() {
 //Ceci n'est pas une `fn main`
 assert_eq!(2+2, 4);"
         .to_string();
-    let (output, len, _) = make_test(input, None, true, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, true, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 1));
 }
 
@@ -231,7 +241,7 @@ fn main() {
 }"
     .to_string();
 
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 
     let input = "extern crate hella_qwop;
@@ -246,7 +256,8 @@ fn main() {
 }"
     .to_string();
 
-    let (output, len, _) = make_test(input, Some("asdf"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 3));
 }
 
@@ -264,7 +275,8 @@ fn main() {}
 }"
     .to_string();
 
-    let (output, len, _) = make_test(input, Some("my_crate"), false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) =
+        make_test(input, Some("my_crate"), false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 1));
 }
 
@@ -284,7 +296,7 @@ fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {
 Ok::<(), io:Error>(())
 } _inner().unwrap() }"
         .to_string();
-    let (output, len, _) = make_test(input, None, false, &opts, DEFAULT_EDITION, None);
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
     assert_eq!((output, len), (expected, 2));
 }
 
@@ -299,6 +311,152 @@ fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
 } _doctest_main__some_unique_name() }"
         .to_string();
     let (output, len, _) =
-        make_test(input, None, false, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
+        make_test(input, None, false, None, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
     assert_eq!((output, len), (expected, 2));
 }
+
+#[test]
+fn make_test_expected_panic() {
+    // the panic of the test is caught, so that its message can be checked
+    let opts = GlobalTestOptions::default();
+    let input = "panic!(\"oh no\");";
+    let expected = format!(
+        "#![allow(unused)]
+fn main() {{ fn _inner() {{
+panic!(\"oh no\");
+}} if let Err(payload) = ::std::panic::catch_unwind(|| _inner()) {{ \
+__rustdoc_check_panic(payload, \"no \\\"way\\\"\"); ::std::process::exit(102) }} }}
+{CHECK_PANIC_FN}"
+    );
+    let (output, len, _) =
+        make_test(input, None, false, Some("no \"way\""), &opts, DEFAULT_EDITION, None);
+    assert_eq!((output, len), (expected, 2));
+}
+
+#[test]
+fn make_test_expected_panic_own_main() {
+    // the message can only be checked when the test gets a generated `main`
+    assert!(checks_expected_panic("panic!(\"oh no\");", false, DEFAULT_EDITION));
+    assert!(!checks_expected_panic("fn main() { panic!(\"oh no\"); }", false, DEFAULT_EDITION));
+    assert!(!checks_expected_panic("panic!(\"oh no\");", true, DEFAULT_EDITION));
+    assert!(!checks_expected_panic("#![no_std]\npanic!(\"oh no\");", false, DEFAULT_EDITION));
+
+    // the test is left as is
+    let opts = GlobalTestOptions::default();
+    let input = "fn main() { panic!(\"oh no\"); }";
+    let expected = "#![allow(unused)]\nfn main() { panic!(\"oh no\"); }".to_string();
+    let (output, len, _) =
+        make_test(input, None, false, Some("oh no"), &opts, DEFAULT_EDITION, None);
+    assert_eq!((output, len), (expected, 1));
+}
+
+#[test]
+fn make_test_prelude() {
+    // the prelude is added at the start of `fn main`, and counted in the lines before the test
+    let opts = GlobalTestOptions {
+        no_crate_inject: false,
+        attrs: vec![],
+        prelude: vec!["use asdf::qwop;".to_string(), "let x = 1;\nlet y = 2;".to_string()],
+    };
+    let input = "assert_eq!(x + y, 3);";
+    let expected = "#![allow(unused)]
+#[allow(unused_extern_crates)]
+extern crate r#asdf;
+fn main() {
+use asdf::qwop;
+let x = 1;
+let y = 2;
+assert_eq!(x + y, 3);
+}"
+    .to_string();
+    let (output, len, _) =
+        make_test(input, Some("asdf"), false, None, &opts, DEFAULT_EDITION, None);
+    assert_eq!((output, len), (expected, 6));
+}
+
+#[test]
+fn make_test_prelude_main() {
+    // tests with their own `fn main` don't get the prelude, which has nowhere to go
+    let opts = GlobalTestOptions {
+        no_crate_inject: false,
+        attrs: vec![],
+        prelude: vec!["let x = 1;".to_string()],
+    };
+    let input = "fn main() {
+    assert_eq!(2+2, 4);
+}";
+    let expected = "#![allow(unused)]
+fn main() {
+    assert_eq!(2+2, 4);
+}"
+    .to_string();
+    let (output, len, _) = make_test(input, None, false, None, &opts, DEFAULT_EDITION, None);
+    assert_eq!((output, len), (expected, 1));
+}
+
+#[test]
+fn make_merged_test_padded() {
+    // the code of the test is moved down to the line after `line`, and kept out of the crate root
+    let opts = GlobalTestOptions::default();
+    let input = "use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let expected = "\n\n#[allow(unused_extern_crates)]
+extern crate r#asdf;
+fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+} _doctest_main__some_unique_name() }
+pub(crate) fn __rustdoc_main() -> impl ::std::process::Termination { main() }
+"
+    .to_string();
+    let output =
+        make_merged_test(input, "asdf", 4, None, &opts, DEFAULT_EDITION, "_some_unique_name");
+    assert_eq!(output, Some(expected));
+}
+
+#[test]
+fn make_merged_test_crate_attrs() {
+    // tests with attributes for the whole crate can't be merged
+    let opts = GlobalTestOptions::default();
+    let input = "#![feature(asdf)]
+assert_eq!(2+2, 4);";
+    let output =
+        make_merged_test(input, "asdf", 4, None, &opts, DEFAULT_EDITION, "_some_unique_name");
+    assert_eq!(output, None);
+
+    let input = "#[macro_use] extern crate asdf;
+assert_eq!(2+2, 4);";
+    let output =
+        make_merged_test(input, "asdf", 4, None, &opts, DEFAULT_EDITION, "_some_unique_name");
+    assert_eq!(output, None);
+}
+
+#[test]
+fn item_path_filters() {
+    // filters matching the path of an item, or of one of its parents, select its doctests
+    let item_paths = [
+        ("src/lib.rs - (line 1)".to_string(), "asdf".to_string()),
+        ("src/lib.rs - Qwop (line 5)".to_string(), "asdf::Qwop".to_string()),
+        ("src/lib.rs - Qwop::parse (line 9)".to_string(), "asdf::Qwop::parse".to_string()),
+        ("src/lib.rs - Qwop::parse_all (line 13)".to_string(), "asdf::Qwop::parse_all".to_string()),
+    ];
+    let filtered = |args: &[&str]| {
+        let mut test_args = args.iter().map(|arg| arg.to_string()).collect();
+        add_item_path_filters(&mut test_args, &item_paths);
+        test_args
+    };
+
+    assert_eq!(filtered(&["Qwop::parse"]), ["Qwop::parse", "src/lib.rs - Qwop::parse (line 9)"]);
+    assert_eq!(
+        filtered(&["asdf::Qwop"]),
+        [
+            "asdf::Qwop",
+            "src/lib.rs - Qwop (line 5)",
+            "src/lib.rs - Qwop::parse (line 9)",
+            "src/lib.rs - Qwop::parse_all (line 13)",
+        ],
+    );
+    assert_eq!(filtered(&["--exact", "asdf"]).len(), 6);
+    // option values and partial names are not paths
+    assert_eq!(filtered(&["--skip", "Qwop", "Qwo"]), ["--skip", "Qwop", "Qwo"]);
+}
@@ -10,6 +10,7 @@
 use crate::fold::DocFolder;
 use crate::formats::item_type::ItemType;
 use crate::formats::Impl;
+use crate::html::escape::Escape;
 use crate::html::format::join_with_double_colon;
 use crate::html::markdown::short_markdown_summary;
 use crate::html::render::search_index::get_function_type_for_search;
@@ -327,8 +328,16 @@ fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
                     // which should not be indexed. The crate-item itself is
                     // inserted later on when serializing the search-index.
                     if item.item_id.as_def_id().map_or(false, |idx| !idx.is_crate_root()) {
-                        let desc =
+                        let mut desc =
                             short_markdown_summary(&item.doc_value(), &item.link_names(self.cache));
+                        // Macros are shown with their short form, as functions
+                        // are with their signature.
+                        if let clean::MacroItem(ref m) = *item.kind
+                            && !m.short.is_empty()
+                        {
+                            let short = format!("<code>{}</code>", Escape(&m.short));
+                            desc = if desc.is_empty() { short } else { format!("{short} {desc}") };
+                        }
                         let ty = item.type_();
                         if ty != ItemType::StructField
                             || u16::from_str_radix(s.as_str(), 10).is_err()
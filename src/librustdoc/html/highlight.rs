@@ -5,12 +5,13 @@
 //!
 //! Use the `render_with_highlighting` to highlight some rust code.
 
-use crate::clean::PrimitiveType;
+use crate::clean::{MacroArm, PrimitiveType};
 use crate::html::escape::Escape;
 use crate::html::render::{Context, LinkFromSrc};
 
 use std::collections::VecDeque;
 use std::fmt::{Display, Write};
+use std::mem;
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_lexer::{Cursor, LiteralKind, TokenKind};
@@ -65,6 +66,75 @@ pub(crate) fn render_item_decl_with_highlighting(src: &str, out: &mut Buffer) {
     write!(out, "</pre>");
 }
 
+/// An arm of a macro declaration, as `render_macro_decl_with_highlighting` renders it.
+pub(crate) struct DeclArm<'a> {
+    /// The id of the arm, which it can be linked to with.
+    pub(crate) id: String,
+    pub(crate) arm: &'a MacroArm,
+    /// Link to the source of the arm, if it is shown.
+    pub(crate) src_href: Option<String>,
+}
+
+/// Highlights the declaration of a macro like `render_item_decl_with_highlighting`, wrapping each
+/// of its arms in a `<span>` with its id, so that it can be linked to, followed by a link to its
+/// source if any.
+///
+/// Arms sharing their range with the previous one, as identical arms shown once do, get an empty
+/// `<span>`. The lines of the hidden arms, which are the last ones, are put after `hide`, which is
+/// given how many of them there are, and before `</details>`. `attrs` are highlighted before the
+/// declaration, and the ranges of the arms don't count them.
+pub(crate) fn render_macro_decl_with_highlighting(
+    attrs: &str,
+    src: &str,
+    arms: &[DeclArm<'_>],
+    out: &mut Buffer,
+    hide: impl FnOnce(&mut Buffer, usize),
+) {
+    // Identical arms are shown once, and counted as one.
+    let mut hidden: Vec<_> =
+        arms.iter().filter(|decl| decl.arm.hidden).map(|decl| &decl.arm.range).collect();
+    hidden.dedup();
+    let hidden = hidden.len();
+    write!(out, "<pre class=\"rust item-decl\">");
+    write_code(out, attrs, None, None);
+    let mut pos = 0;
+    let mut hide = Some(hide);
+    let mut arms = arms.iter().peekable();
+    while let Some(DeclArm { id, arm, src_href }) = arms.next() {
+        let before = &src[pos..arm.range.start];
+        if arm.hidden && let Some(hide) = hide.take() {
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            write_code(out, &before[..line_start], None, None);
+            hide(out, hidden);
+            write_code(out, &before[line_start..], None, None);
+        } else {
+            write_code(out, before, None, None);
+        }
+        write!(out, "<span id=\"{id}\" class=\"macro-arm\">");
+        while let Some(next) = arms.next_if(|next| next.arm.range == arm.range) {
+            write!(out, "<span id=\"{}\"></span>", next.id);
+        }
+        write_code(out, &src[arm.range.clone()], None, None);
+        write!(out, "</span>");
+        // The link has no text of its own, so that copying the declaration
+        // does not copy it.
+        if let Some(href) = src_href {
+            write!(out, "<a class=\"macro-arm-src\" href=\"{href}\" title=\"source\"></a>");
+        }
+        pos = arm.range.end;
+    }
+    let after = &src[pos..];
+    if hide.is_none() {
+        let line_end = after.find('\n').map_or(after.len(), |i| i + 1);
+        write_code(out, &after[..line_end], None, None);
+        write!(out, "</details>");
+        write_code(out, &after[line_end..], None, None);
+    } else {
+        write_code(out, after, None, None);
+    }
+    write!(out, "</pre>");
+}
+
 fn write_header(out: &mut Buffer, class: &str, extra_content: Option<Buffer>, tooltip: Tooltip) {
     write!(
         out,
@@ -308,6 +378,11 @@ enum Class {
     Self_(Span),
     Macro(Span),
     MacroNonTerminal,
+    /// The fragment specifier of a macro metavariable, as `expr` in `$x:expr`, with the page of
+    /// the Rust Reference documenting it.
+    MacroFragment(&'static str),
+    /// The `*`, `+` or `?` ending a macro repetition, as in `$($x:expr),*`.
+    MacroRepetition,
     String,
     Number,
     Bool,
@@ -357,6 +432,8 @@ fn as_html(self) -> &'static str {
             Class::Self_(_) => "self",
             Class::Macro(_) => "macro",
             Class::MacroNonTerminal => "macro-nonterminal",
+            Class::MacroFragment(_) => "macro-fragment",
+            Class::MacroRepetition => "macro-repetition",
             Class::String => "string",
             Class::Number => "number",
             Class::Bool => "bool-val",
@@ -380,6 +457,8 @@ fn get_span(self) -> Option<Span> {
             | Self::KeyWord
             | Self::RefKeyWord
             | Self::MacroNonTerminal
+            | Self::MacroFragment(_)
+            | Self::MacroRepetition
             | Self::String
             | Self::Number
             | Self::Bool
@@ -431,6 +510,27 @@ fn get_real_ident_class(text: &str, allow_path_keywords: bool) -> Option<Class>
     })
 }
 
+/// The page of the Rust Reference documenting what a fragment specifier of a macro metavariable
+/// matches, or `None` if `text` is not a fragment specifier.
+fn fragment_specifier_reference(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "block" => "expressions/block-expr.html",
+        "expr" => "expressions.html",
+        "ident" => "identifiers.html",
+        "item" => "items.html",
+        "lifetime" => "tokens.html#lifetimes-and-loop-labels",
+        "literal" => "expressions/literal-expr.html",
+        "meta" => "attributes.html#meta-item-attribute-syntax",
+        "pat" | "pat_param" => "patterns.html",
+        "path" => "paths.html#paths-in-types",
+        "stmt" => "statements.html",
+        "tt" => "macros.html#macro-invocation",
+        "ty" => "types.html#type-expressions",
+        "vis" => "visibility-and-privacy.html",
+        _ => return None,
+    })
+}
+
 /// This iterator comes from the same idea than "Peekable" except that it allows to "peek" more than
 /// just the next item by using `peek_next`. The `peek` method always returns the next item after
 /// the current one whereas `peek_next` will return the next item after the last one peeked.
@@ -506,6 +606,19 @@ struct Classifier<'src> {
     in_attribute: bool,
     in_macro: bool,
     in_macro_nonterminal: bool,
+    /// The last token was the name of a macro metavariable, as `x` in `$x:expr`.
+    after_macro_nonterminal: bool,
+    /// The last token was the `:` before a fragment specifier.
+    in_macro_fragment: bool,
+    /// How many parentheses are open.
+    paren_depth: usize,
+    /// The `paren_depth` in each of the macro repetitions that are open.
+    macro_repetitions: Vec<usize>,
+    /// The last token closed a macro repetition, so this one is its operator
+    /// or separator.
+    after_macro_repetition: bool,
+    /// The last token was the separator of a macro repetition.
+    after_macro_separator: bool,
     byte_pos: u32,
     file_span: Span,
     src: &'src str,
@@ -523,6 +636,12 @@ fn new(src: &str, file_span: Span, decoration_info: Option<DecorationInfo>) -> C
             in_attribute: false,
             in_macro: false,
             in_macro_nonterminal: false,
+            after_macro_nonterminal: false,
+            in_macro_fragment: false,
+            paren_depth: 0,
+            macro_repetitions: Vec::new(),
+            after_macro_repetition: false,
+            after_macro_separator: false,
             byte_pos: 0,
             file_span,
             src,
@@ -659,8 +778,34 @@ fn advance(
     ) {
         let lookahead = self.peek();
         let no_highlight = |sink: &mut dyn FnMut(_)| sink(Highlight::Token { text, class: None });
+        if token == TokenKind::Whitespace {
+            return no_highlight(sink);
+        }
+        // In macro matchers, as in `$($x:expr),*`, fragment specifiers and
+        // repetition operators are told apart by the tokens right before them.
+        let after_macro_nonterminal = mem::take(&mut self.after_macro_nonterminal);
+        let in_macro_fragment = mem::take(&mut self.in_macro_fragment);
+        let after_macro_repetition = mem::take(&mut self.after_macro_repetition);
+        let after_macro_separator = mem::take(&mut self.after_macro_separator);
+        if (after_macro_repetition || after_macro_separator)
+            && matches!(token, TokenKind::Star | TokenKind::Plus | TokenKind::Question)
+        {
+            sink(Highlight::Token { text, class: Some(Class::MacroRepetition) });
+            return;
+        }
+        self.after_macro_separator = after_macro_repetition;
+        match token {
+            TokenKind::OpenParen => self.paren_depth += 1,
+            TokenKind::CloseParen => {
+                if self.macro_repetitions.last() == Some(&self.paren_depth) {
+                    self.macro_repetitions.pop();
+                    self.after_macro_repetition = true;
+                }
+                self.paren_depth = self.paren_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
         let class = match token {
-            TokenKind::Whitespace => return no_highlight(sink),
             TokenKind::LineComment { doc_style } | TokenKind::BlockComment { doc_style, .. } => {
                 if doc_style.is_some() {
                     Class::DocComment
@@ -746,7 +891,8 @@ fn advance(
             | TokenKind::Gt => return no_highlight(sink),
 
             // Miscellaneous, no highlighting.
-            TokenKind::Dot
+            TokenKind::Whitespace
+            | TokenKind::Dot
             | TokenKind::Semi
             | TokenKind::Comma
             | TokenKind::OpenParen
@@ -757,7 +903,10 @@ fn advance(
             | TokenKind::At
             | TokenKind::Tilde
             | TokenKind::Colon
-            | TokenKind::Unknown => return no_highlight(sink),
+            | TokenKind::Unknown => {
+                self.in_macro_fragment = token == TokenKind::Colon && after_macro_nonterminal;
+                return no_highlight(sink);
+            }
 
             TokenKind::Question => Class::QuestionMark,
 
@@ -766,6 +915,10 @@ fn advance(
                     self.in_macro_nonterminal = true;
                     Class::MacroNonTerminal
                 }
+                Some(TokenKind::OpenParen) => {
+                    self.macro_repetitions.push(self.paren_depth + 1);
+                    return no_highlight(sink);
+                }
                 _ => return no_highlight(sink),
             },
 
@@ -832,8 +985,13 @@ fn advance(
                     "union" if self.check_if_is_union_keyword() => Class::KeyWord,
                     _ if self.in_macro_nonterminal => {
                         self.in_macro_nonterminal = false;
+                        self.after_macro_nonterminal = true;
                         Class::MacroNonTerminal
                     }
+                    _ if in_macro_fragment => match fragment_specifier_reference(text) {
+                        Some(page) => Class::MacroFragment(page),
+                        None => Class::Ident(self.new_span(before, text)),
+                    },
                     "self" | "Self" => Class::Self_(self.new_span(before, text)),
                     _ => Class::Ident(self.new_span(before, text)),
                 },
@@ -933,6 +1091,22 @@ fn string_without_closing_tag<T: Display>(
         write!(out, "{}", text).unwrap();
         return None;
     };
+    // Fragment specifiers link to what they match, like primitive types link to their docs.
+    if let Class::MacroFragment(page) = klass {
+        if !open_tag {
+            write!(out, "{}", text).unwrap();
+            return None;
+        }
+        write!(
+            out,
+            "<a class=\"{}\" href=\"https://doc.rust-lang.org/reference/{}\">{}",
+            klass.as_html(),
+            page,
+            text
+        )
+        .unwrap();
+        return Some("</a>");
+    }
     let Some(def_span) = klass.get_span()
     else {
         if !open_tag {
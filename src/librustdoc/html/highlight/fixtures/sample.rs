@@ -24,4 +24,5 @@ fn main() -> () {
 
 macro_rules! bar {
     ($foo:tt) => {};
+    ($($x:expr),*; $($y:ident)+) => {};
 }
@@ -65,6 +65,17 @@ fn test_union_highlighting() {
     });
 }
 
+#[test]
+fn test_macro_fragment_links() {
+    create_default_session_globals_then(|| {
+        // Specifiers rustdoc doesn't know of aren't linked.
+        let src = "($a:expr, $b:future_fragment)";
+        let mut html = Buffer::new();
+        write_code(&mut html, src, None, None);
+        expect_file!["fixtures/macro_fragments.html"].assert_eq(&html.into_inner());
+    });
+}
+
 #[test]
 fn test_decorations() {
     create_default_session_globals_then(|| {
@@ -30,7 +30,7 @@
 use rustc_middle::ty::TyCtxt;
 pub(crate) use rustc_resolve::rustdoc::main_body_opts;
 use rustc_resolve::rustdoc::may_be_doc_link;
-use rustc_span::edition::Edition;
+use rustc_span::edition::{Edition, ALL_EDITIONS};
 use rustc_span::{Span, Symbol};
 
 use once_cell::sync::Lazy;
@@ -283,7 +283,7 @@ fn next(&mut self) -> Option<Self::Item> {
                 .collect::<String>();
             let krate = krate.as_ref().map(|s| s.as_str());
             let (test, _, _) =
-                doctest::make_test(&test, krate, false, &Default::default(), edition, None);
+                doctest::make_test(&test, krate, false, None, &Default::default(), edition, None);
             let channel = if test.contains("#![feature(") { "&amp;version=nightly" } else { "" };
 
             let test_escaped = small_url_encode(test);
@@ -753,6 +753,9 @@ pub(crate) fn find_testable_code<T: doctest::Tester>(
                 if nb_lines != 0 && !&doc[prev_offset..offset.start].ends_with('\n') {
                     nb_lines -= 1;
                 }
+                if let Some(extra) = extra_info && block_info.expected_panic.is_some() {
+                    extra.check_expected_panic(&text, &block_info);
+                }
                 let line = tests.get_line() + nb_lines + 1;
                 tests.add_test(text, block_info, line);
                 prev_offset = offset.start;
@@ -792,17 +795,36 @@ fn error_invalid_codeblock_attr(&self, msg: String, help: &str) {
             );
         }
     }
+
+    /// Warns about `should_panic="..."` doctests whose panic message can't be checked.
+    fn check_expected_panic(&self, text: &str, block_info: &LangString) {
+        let edition = block_info.edition.unwrap_or_else(|| self.tcx.sess.edition());
+        if !doctest::checks_expected_panic(text, block_info.test_harness, edition) {
+            self.error_invalid_codeblock_attr(
+                "the panic message expected by this doctest is not checked".to_owned(),
+                "rustdoc can only check the message when it generates the `main` function of the \
+                 doctest, which it doesn't for doctests with a `fn main`, `test_harness` doctests \
+                 and `#![no_std]` doctests; such a doctest only checks that it panics",
+            );
+        }
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub(crate) struct LangString {
     original: String,
     pub(crate) should_panic: bool,
+    /// The message the panic of the test has to contain, from `should_panic="..."`.
+    pub(crate) expected_panic: Option<String>,
     pub(crate) no_run: bool,
     pub(crate) ignore: Ignore,
     pub(crate) rust: bool,
     pub(crate) test_harness: bool,
     pub(crate) compile_fail: bool,
+    pub(crate) no_prelude: bool,
+    /// Whether the test has to be compiled and run as a crate of its own, instead of being merged
+    /// with others.
+    pub(crate) standalone_crate: bool,
     pub(crate) error_codes: Vec<String>,
     pub(crate) edition: Option<Edition>,
 }
@@ -819,11 +841,14 @@ fn default() -> Self {
         Self {
             original: String::new(),
             should_panic: false,
+            expected_panic: None,
             no_run: false,
             ignore: Ignore::None,
             rust: true,
             test_harness: false,
             compile_fail: false,
+            no_prelude: false,
+            standalone_crate: false,
             error_codes: Vec::new(),
             edition: None,
         }
@@ -857,8 +882,16 @@ fn tokens(string: &str) -> impl Iterator<Item = &str> {
             string
         };
 
+        // Separators within double quotes, like in `should_panic="some message"`, are part of the
+        // token.
+        let mut in_quotes = false;
         string
-            .split(|c| c == ',' || c == ' ' || c == '\t')
+            .split(move |c| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                !in_quotes && (c == ',' || c == ' ' || c == '\t')
+            })
             .map(str::trim)
             .map(|token| token.strip_prefix('.').unwrap_or(token))
             .filter(|token| !token.is_empty())
@@ -884,6 +917,16 @@ fn parse(
                     data.should_panic = true;
                     seen_rust_tags = !seen_other_tags;
                 }
+                x if x.starts_with("should_panic=") => {
+                    let expected = &x["should_panic=".len()..];
+                    let expected = expected
+                        .strip_prefix('"')
+                        .and_then(|e| e.strip_suffix('"'))
+                        .unwrap_or(expected);
+                    data.should_panic = true;
+                    data.expected_panic = Some(expected.to_owned());
+                    seen_rust_tags = !seen_other_tags;
+                }
                 "no_run" => {
                     data.no_run = true;
                     seen_rust_tags = !seen_other_tags;
@@ -911,9 +954,34 @@ fn parse(
                     seen_rust_tags = !seen_other_tags || seen_rust_tags;
                     data.no_run = true;
                 }
-                x if x.starts_with("edition") => {
-                    data.edition = x[7..].parse::<Edition>().ok();
+                "no_prelude" => {
+                    data.no_prelude = true;
+                    seen_rust_tags = !seen_other_tags || seen_rust_tags;
+                }
+                "standalone_crate" => {
+                    data.standalone_crate = true;
+                    seen_rust_tags = !seen_other_tags || seen_rust_tags;
                 }
+                x if x.starts_with("edition") => match x[7..].parse::<Edition>() {
+                    Ok(edition) => data.edition = Some(edition),
+                    Err(_) => {
+                        if let Some(extra) = extra {
+                            let editions = ALL_EDITIONS
+                                .iter()
+                                .map(|edition| format!("`edition{edition}`"))
+                                .collect::<Vec<_>>();
+                            let (last, editions) = editions.split_last().unwrap();
+                            extra.error_invalid_codeblock_attr(
+                                format!("unknown edition `{x}`"),
+                                &format!(
+                                    "the code block will be compiled with the edition of the \
+                                     crate; the known editions are {} and {last}",
+                                    editions.join(", "),
+                                ),
+                            );
+                        }
+                    }
+                },
                 x if allow_error_code_check && x.starts_with('E') && x.len() == 5 => {
                     if x[1..].parse::<u32>().is_ok() {
                         data.error_codes.push(x.to_owned());
@@ -951,6 +1019,22 @@ fn parse(
                             "the code block will either not be tested if not marked as a rust one \
                              or the code will be wrapped inside a main function",
                         ))
+                    } else if s == "no-prelude" || s == "no_prelude" || s == "noprelude" {
+                        Some((
+                            "no_prelude",
+                            "the code block will either not be tested if not marked as a rust one \
+                             or the statements of `#![doc(test(prelude = \"...\"))]` will be \
+                             added to it",
+                        ))
+                    } else if s == "standalone-crate"
+                        || s == "standalone_crate"
+                        || s == "standalonecrate"
+                    {
+                        Some((
+                            "standalone_crate",
+                            "the code block will either not be tested if not marked as a rust one \
+                             or may be compiled together with other doctests",
+                        ))
                     } else {
                         None
                     } {
@@ -64,6 +64,11 @@ fn t(lg: LangString) {
     t(LangString { original: "should_panic".into(), should_panic: true, ..Default::default() });
     t(LangString { original: "no_run".into(), no_run: true, ..Default::default() });
     t(LangString { original: "test_harness".into(), test_harness: true, ..Default::default() });
+    t(LangString {
+        original: "standalone_crate".into(),
+        standalone_crate: true,
+        ..Default::default()
+    });
     t(LangString {
         original: "compile_fail".into(),
         no_run: true,
@@ -117,6 +122,35 @@ fn t(lg: LangString) {
         edition: Some(Edition::Edition2018),
         ..Default::default()
     });
+    t(LangString {
+        original: "edition2021".into(),
+        edition: Some(Edition::Edition2021),
+        ..Default::default()
+    });
+    t(LangString {
+        original: "should_panic,edition2024".into(),
+        should_panic: true,
+        edition: Some(Edition::Edition2024),
+        ..Default::default()
+    });
+    t(LangString {
+        original: "should_panic=\"out of bounds, sadly\",no_run".into(),
+        should_panic: true,
+        expected_panic: Some("out of bounds, sadly".into()),
+        no_run: true,
+        ..Default::default()
+    });
+    t(LangString {
+        original: "should_panic=oops".into(),
+        should_panic: true,
+        expected_panic: Some("oops".into()),
+        ..Default::default()
+    });
+    t(LangString {
+        original: "edition2015,edition2020".into(),
+        edition: Some(Edition::Edition2015),
+        ..Default::default()
+    });
 }
 
 #[test]
@@ -138,6 +172,7 @@ fn case(lang_string: &str, want: &[&str]) {
     case(" foo , bar ", &["foo", "bar"]);
     case(",,foo,,bar,,", &["foo", "bar"]);
     case("foo=bar", &["foo=bar"]);
+    case("foo=\"bar, baz\" qux", &["foo=\"bar, baz\"", "qux"]);
     case("a-b-c", &["a-b-c"]);
     case("a_b_c", &["a_b_c"]);
 }
@@ -592,10 +592,14 @@ enum ShortItemInfo {
     Portability {
         message: String,
     },
+    /// Which macro a macro was generated by, linking to it if it is documented.
+    MacroGenerated {
+        message: String,
+    },
 }
 
-/// Render the stability, deprecation and portability information that is displayed at the top of
-/// the item's documentation.
+/// Render the stability, deprecation and portability information, and where a generated macro
+/// comes from, that is displayed at the top of the item's documentation.
 fn short_item_info(
     item: &clean::Item,
     cx: &mut Context<'_>,
@@ -653,6 +657,20 @@ fn short_item_info(
         extra_info.push(ShortItemInfo::Portability { message });
     }
 
+    // The declaration of a generated macro is nowhere to be found in the source
+    // of the crate, so it is told where it comes from.
+    if let clean::MacroItem(ref mac) = *item.kind
+        && let Some(generator) = mac.generated_by
+    {
+        let name = format!("<code>{}!</code>", cx.tcx().item_name(generator));
+        let generator = match href(generator, cx) {
+            Ok((url, ..)) => format!("<a href=\"{url}\">{name}</a>"),
+            Err(_) => name,
+        };
+        let message = format!("Generated by the {generator} macro.");
+        extra_info.push(ShortItemInfo::MacroGenerated { message });
+    }
+
     extra_info
 }
 
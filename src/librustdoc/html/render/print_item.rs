@@ -7,8 +7,9 @@
 use rustc_hir::def_id::DefId;
 use rustc_middle::middle::stability;
 use rustc_middle::ty::{self, TyCtxt};
-use rustc_span::hygiene::MacroKind;
+use rustc_span::hygiene::{MacroKind, Transparency};
 use rustc_span::symbol::{kw, sym, Symbol};
+use rustc_span::Span;
 use std::borrow::Borrow;
 use std::cell::{RefCell, RefMut};
 use std::cmp::Ordering;
@@ -469,8 +470,14 @@ fn cmp(
                 };
 
                 w.write_str(ITEM_TABLE_ROW_OPEN);
-                let docs =
+                let mut docs =
                     MarkdownSummaryLine(&myitem.doc_value(), &myitem.links(cx)).into_string();
+                if let clean::MacroItem(ref m) = *myitem.kind
+                    && !m.short.is_empty()
+                {
+                    let short = format!("<code class=\"macro-short\">{}</code>", Escape(&m.short));
+                    docs = if docs.is_empty() { short } else { format!("{short} {docs}") };
+                }
                 let (docs_before, docs_after) = if docs.is_empty() {
                     ("", "")
                 } else {
@@ -1416,8 +1423,116 @@ fn item_enum(w: &mut Buffer, cx: &mut Context<'_>, it: &clean::Item, e: &clean::
 }
 
 fn item_macro(w: &mut Buffer, cx: &mut Context<'_>, it: &clean::Item, t: &clean::Macro) {
-    highlight::render_item_decl_with_highlighting(&t.source, w);
-    write!(w, "{}", document(cx, it, None, HeadingOffset::H2))
+    // The declaration stays one click away from a signature the author gave.
+    if let Some(signature) = t.signature {
+        highlight::render_item_decl_with_highlighting(signature.as_str(), w);
+        toggle_open(&mut *w, "declaration");
+    }
+    let attrs = macro_attributes(t);
+    if t.arms.is_empty() {
+        highlight::render_item_decl_with_highlighting(&format!("{attrs}{}", t.source), w);
+    } else {
+        // Arms are numbered from the first one, so that adding one at the end
+        // leaves links to the others as they were.
+        let arms: Vec<_> = t
+            .arms
+            .iter()
+            .zip(&t.matchers)
+            .enumerate()
+            .map(|(i, (arm, matcher))| highlight::DeclArm {
+                id: cx.derive_id(format!("arm-{i}")),
+                arm,
+                src_href: macro_arm_src_href(cx, it, matcher.span),
+            })
+            .collect();
+        item_macro_arm_index(w, t, &arms);
+        highlight::render_macro_decl_with_highlighting(&attrs, &t.source, &arms, w, |w, hidden| {
+            toggle_open(w, format_args!("{hidden} more arm{}", pluralize(hidden)))
+        });
+    }
+    if t.signature.is_some() {
+        toggle_close(&mut *w);
+    }
+    write!(w, "{}", document(cx, it, None, HeadingOffset::H2));
+    item_macro_invocations(w, cx, t);
+}
+
+/// The attributes shown above the declaration of a macro, one per line: how it
+/// is exported, and its transparency unless it is the default one for its kind.
+fn macro_attributes(t: &clean::Macro) -> String {
+    let mut attrs = String::new();
+    match t.export {
+        clean::MacroExport::NotExported => {}
+        clean::MacroExport::Exported => attrs.push_str("#[macro_export]\n"),
+        clean::MacroExport::LocalInnerMacros => {
+            attrs.push_str("#[macro_export(local_inner_macros)]\n")
+        }
+    }
+    let default = if t.macro_rules { Transparency::SemiTransparent } else { Transparency::Opaque };
+    if t.transparency != default {
+        let transparency = match t.transparency {
+            Transparency::Transparent => "transparent",
+            Transparency::SemiTransparent => "semitransparent",
+            Transparency::Opaque => "opaque",
+        };
+        attrs.push_str(&format!("#[rustc_macro_transparency = \"{transparency}\"]\n"));
+    }
+    attrs
+}
+
+/// Shows an example invocation of each arm of a macro, if these were rendered
+/// with `--show-macro-invocations`. Consecutive identical ones are shown once.
+fn item_macro_invocations(w: &mut Buffer, cx: &mut Context<'_>, t: &clean::Macro) {
+    let mut invocations: Vec<_> =
+        t.matchers.iter().filter_map(|matcher| matcher.invocation.as_deref()).collect();
+    if invocations.is_empty() {
+        return;
+    }
+    invocations.dedup();
+    let id = cx.derive_id("example-invocations".to_owned());
+    write!(
+        w,
+        "<h2 id=\"{id}\" class=\"small-section-header\">\
+            Example invocations<a href=\"#{id}\" class=\"anchor\">§</a>\
+         </h2>"
+    );
+    highlight::render_example_with_highlighting(
+        &invocations.join("\n"),
+        w,
+        highlight::Tooltip::None,
+        None,
+    );
+}
+
+/// Links to the line of the source view the arm whose matcher has `span` starts
+/// at, or to the whole macro if the matcher comes from an expansion.
+fn macro_arm_src_href(cx: &Context<'_>, it: &clean::Item, span: Span) -> Option<String> {
+    if !cx.include_sources {
+        return None;
+    }
+    if span.is_dummy() || span.from_expansion() {
+        return cx.src_href(it);
+    }
+    let span = clean::Span::new(span);
+    let href = cx.href_from_span(span, false)?;
+    Some(format!("{href}#{}", span.lo(cx.sess()).line))
+}
+
+/// Lists the arms of a macro with enough of them to have summaries, linking to
+/// each of them in the declaration. Identical arms, which are shown once, are
+/// listed once.
+fn item_macro_arm_index(w: &mut Buffer, t: &clean::Macro, arms: &[highlight::DeclArm<'_>]) {
+    if t.matchers.iter().all(|matcher| matcher.summary.is_none()) {
+        return;
+    }
+    w.write_str("<ul class=\"macro-arm-index\">");
+    let mut arms = arms.iter().zip(&t.matchers).peekable();
+    while let Some((arm, matcher)) = arms.next() {
+        while arms.next_if(|(next, _)| next.arm.range == arm.arm.range).is_some() {}
+        let summary = matcher.summary.as_deref().unwrap_or(&matcher.text);
+        write!(w, "<li><a href=\"#{}\"><code>{}</code></a></li>", arm.id, Escape(summary));
+    }
+    w.write_str("</ul>");
 }
 
 fn item_proc_macro(w: &mut Buffer, cx: &mut Context<'_>, it: &clean::Item, m: &clean::ProcMacro) {
@@ -9,12 +9,15 @@
 use rustc_ast::ast;
 use rustc_hir::{def::CtorKind, def::DefKind, def_id::DefId};
 use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::Session;
+use rustc_span::hygiene::Transparency;
 use rustc_span::symbol::sym;
 use rustc_span::{Pos, Symbol};
 use rustc_target::spec::abi::Abi as RustcAbi;
 
 use rustdoc_json_types::*;
 
+use crate::clean::render_macro_matchers::MatcherTokenKind;
 use crate::clean::utils::print_const_expr;
 use crate::clean::{self, ItemId};
 use crate::formats::item_type::ItemType;
@@ -78,22 +81,7 @@ pub(super) fn convert_item(&self, item: clean::Item) -> Option<Item> {
     }
 
     fn convert_span(&self, span: clean::Span) -> Option<Span> {
-        match span.filename(self.sess()) {
-            rustc_span::FileName::Real(name) => {
-                if let Some(local_path) = name.into_local_path() {
-                    let hi = span.hi(self.sess());
-                    let lo = span.lo(self.sess());
-                    Some(Span {
-                        filename: local_path,
-                        begin: (lo.line, lo.col.to_usize()),
-                        end: (hi.line, hi.col.to_usize()),
-                    })
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        }
+        from_span(span, self.sess())
     }
 
     fn convert_visibility(&self, v: Option<ty::Visibility<DefId>>) -> Visibility {
@@ -313,7 +301,7 @@ fn from_clean_item(item: clean::Item, tcx: TyCtxt<'_>) -> ItemEnum {
         TypedefItem(t) => ItemEnum::Typedef(t.into_tcx(tcx)),
         OpaqueTyItem(t) => ItemEnum::OpaqueTy(t.into_tcx(tcx)),
         ConstantItem(c) => ItemEnum::Constant(c.into_tcx(tcx)),
-        MacroItem(m) => ItemEnum::Macro(m.source),
+        MacroItem(m) => ItemEnum::Macro(m.into_tcx(tcx)),
         ProcMacroItem(m) => ItemEnum::ProcMacro(m.into_tcx(tcx)),
         PrimitiveItem(p) => {
             ItemEnum::Primitive(Primitive {
@@ -767,6 +755,97 @@ fn from_tcx(import: clean::Import, tcx: TyCtxt<'_>) -> Self {
     }
 }
 
+fn from_span(span: clean::Span, sess: &Session) -> Option<Span> {
+    match span.filename(sess) {
+        rustc_span::FileName::Real(name) => {
+            if let Some(local_path) = name.into_local_path() {
+                let hi = span.hi(sess);
+                let lo = span.lo(sess);
+                Some(Span {
+                    filename: local_path,
+                    begin: (lo.line, lo.col.to_usize()),
+                    end: (hi.line, hi.col.to_usize()),
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+impl FromWithTcx<clean::Macro> for Macro {
+    fn from_tcx(mac: clean::Macro, tcx: TyCtxt<'_>) -> Self {
+        Macro {
+            source: mac.source.into(),
+            signature: mac.signature.map(|signature| signature.to_string()),
+            macro_export: mac.export != clean::MacroExport::NotExported,
+            local_inner_macros: mac.export == clean::MacroExport::LocalInnerMacros,
+            transparency: match mac.transparency {
+                Transparency::Transparent => MacroTransparency::Transparent,
+                Transparency::SemiTransparent => MacroTransparency::SemiTransparent,
+                Transparency::Opaque => MacroTransparency::Opaque,
+            },
+            generated_by: mac.generated_by.map(|def_id| MacroGenerator {
+                name: tcx.item_name(def_id).to_string(),
+                // Only kept by `JsonRenderer::item` if the generator is documented.
+                id: Some(id_from_item_default(def_id.into(), tcx)),
+            }),
+            matchers: mac
+                .matchers
+                .into_iter()
+                .map(|matcher| MacroMatcher {
+                    text: matcher.text,
+                    from_source: matcher.from_source,
+                    // The span of a macro-generated matcher points into the macro
+                    // that generated it, which is not where the matcher is.
+                    span: if matcher.span.from_expansion() {
+                        None
+                    } else {
+                        from_span(clean::Span::new(matcher.span), tcx.sess)
+                    },
+                    synthetic: matcher.span.from_expansion(),
+                    tokens: matcher
+                        .tokens
+                        .into_iter()
+                        .map(|token| MacroMatcherToken {
+                            kind: from_matcher_token_kind(token.kind),
+                            text: token.text,
+                        })
+                        .collect(),
+                    bindings: matcher
+                        .bindings
+                        .into_iter()
+                        .map(|binding| MacroBinding {
+                            name: binding.name,
+                            fragment: binding.fragment,
+                            repetitions: binding
+                                .repetitions
+                                .into_iter()
+                                .map(|rep| MacroRepetition {
+                                    separator: rep.separator,
+                                    operator: rep.operator,
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    normalized: matcher.normalized,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn from_matcher_token_kind(kind: MatcherTokenKind) -> MacroMatcherTokenKind {
+    use MatcherTokenKind::*;
+    match kind {
+        Metavariable => MacroMatcherTokenKind::Metavariable,
+        FragmentSpecifier => MacroMatcherTokenKind::FragmentSpecifier,
+        RepetitionOperator => MacroMatcherTokenKind::RepetitionOperator,
+        Token => MacroMatcherTokenKind::Token,
+    }
+}
+
 impl FromWithTcx<clean::ProcMacro> for ProcMacro {
     fn from_tcx(mac: clean::ProcMacro, _tcx: TyCtxt<'_>) -> Self {
         ProcMacro {
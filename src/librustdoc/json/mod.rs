@@ -151,6 +151,10 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
         item.kind.inner_items().for_each(|i| self.item(i.clone()).unwrap());
 
         let item_id = item.item_id;
+        let generator = match *item.kind {
+            clean::MacroItem(ref m) => m.generated_by,
+            _ => None,
+        };
         if let Some(mut new_item) = self.convert_item(item) {
             let can_be_ignored = match new_item.inner {
                 types::ItemEnum::Trait(ref mut t) => {
@@ -173,6 +177,17 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
                     p.impls = self.get_impls(item_id.expect_def_id());
                     false
                 }
+                types::ItemEnum::Macro(ref mut m) => {
+                    // The ID of an undocumented generator would be found nowhere.
+                    if let Some(generated_by) = &mut m.generated_by
+                        && let Some(generator) = generator
+                        && !self.cache.paths.contains_key(&generator)
+                        && !self.cache.external_paths.contains_key(&generator)
+                    {
+                        generated_by.id = None;
+                    }
+                    false
+                }
 
                 types::ItemEnum::Function(_)
                 | types::ItemEnum::Module(_)
@@ -189,7 +204,6 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
                 | types::ItemEnum::Constant(_)
                 | types::ItemEnum::Static(_)
                 | types::ItemEnum::ForeignType
-                | types::ItemEnum::Macro(_)
                 | types::ItemEnum::ProcMacro(_) => false,
             };
             let removed = self.index.borrow_mut().insert(new_item.id.clone(), new_item.clone());
@@ -301,6 +301,14 @@ fn opts() -> Vec<RustcOptGroup> {
                 "PATH",
             )
         }),
+        unstable("doctest-run-directory", |o| {
+            o.optopt(
+                "",
+                "doctest-run-directory",
+                "Same as --test-run-directory",
+                "PATH",
+            )
+        }),
         stable("target", |o| o.optopt("", "target", "target triple to document", "TRIPLE")),
         stable("markdown-css", |o| {
             o.optmulti(
@@ -576,6 +584,14 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("no-run", |o| {
             o.optflagmulti("", "no-run", "Compile doctests without running them")
         }),
+        unstable("merge-doctests", |o| {
+            o.optopt(
+                "",
+                "merge-doctests",
+                "Compile compatible doctests together into a few binaries",
+                "yes|no|auto",
+            )
+        }),
         unstable("show-type-layout", |o| {
             o.optflagmulti("", "show-type-layout", "Include the memory layout of types in the docs")
         }),
@@ -616,6 +632,98 @@ fn opts() -> Vec<RustcOptGroup> {
                 "path to function call information (for displaying examples in the documentation)",
             )
         }),
+        unstable("macro-matcher-tab-width", |o| {
+            o.optopt(
+                "",
+                "macro-matcher-tab-width",
+                "Number of columns tabs in macro matchers are expanded to",
+                "WIDTH",
+            )
+        }),
+        unstable("macro-matcher-width", |o| {
+            o.optopt(
+                "",
+                "macro-matcher-width",
+                "Number of columns pretty-printed macro matchers are wrapped at",
+                "WIDTH",
+            )
+        }),
+        unstable("macro-matcher-token-limit", |o| {
+            o.optopt(
+                "",
+                "macro-matcher-token-limit",
+                "Number of tokens after which pretty-printed macro matchers are truncated",
+                "TOKENS",
+            )
+        }),
+        unstable("macro-matcher-snippet-limit", |o| {
+            o.optopt(
+                "",
+                "macro-matcher-snippet-limit",
+                "Size in bytes above which macro matchers are not copied from the source",
+                "BYTES",
+            )
+        }),
+        unstable("normalize-macro-matchers", |o| {
+            o.optflag(
+                "",
+                "normalize-macro-matchers",
+                "Always pretty-print macro matchers instead of copying them from the source",
+            )
+        }),
+        unstable("expand-macro-matchers", |o| {
+            o.optflag(
+                "",
+                "expand-macro-matchers",
+                "Put the parameters of macro matchers on lines of their own",
+            )
+        }),
+        unstable("document-macro-bodies", |o| {
+            o.optflag(
+                "",
+                "document-macro-bodies",
+                "Show what each macro rule expands to instead of eliding it",
+            )
+        }),
+        unstable("macro-arm-index-threshold", |o| {
+            o.optopt(
+                "",
+                "macro-arm-index-threshold",
+                "Number of arms above which macros get an index of them",
+                "ARMS",
+            )
+        }),
+        unstable("macro-visible-arms", |o| {
+            o.optopt(
+                "",
+                "macro-visible-arms",
+                "Number of arms of a macro shown before the rest are collapsed",
+                "ARMS",
+            )
+        }),
+        unstable("hide-internal-macro-arms", |o| {
+            o.optflag(
+                "",
+                "hide-internal-macro-arms",
+                "Leave the arms of macros whose matcher starts with `@name` out of their \
+                declarations",
+            )
+        }),
+        unstable("show-macro-invocations", |o| {
+            o.optflag(
+                "",
+                "show-macro-invocations",
+                "Show an example invocation of each arm of a macro",
+            )
+        }),
+        unstable("dump-macro-matchers", |o| {
+            o.optopt(
+                "",
+                "dump-macro-matchers",
+                "Write the matchers of every macro to PATH, as one line of JSON per arm",
+                "PATH",
+            )
+        }),
         // deprecated / removed options
         unstable("disable-minification", |o| o.optflagmulti("", "disable-minification", "removed")),
         stable("plugin-path", |o| {
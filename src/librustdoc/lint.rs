@@ -185,6 +185,18 @@ macro_rules! declare_rustdoc_lint {
    "detects unescaped backticks in doc comments"
 }
 
+declare_rustdoc_lint! {
+   /// The `reformatted_macro_matchers` lint detects macro matchers that are
+   /// documented pretty-printed from their tokens rather than as they are
+   /// written in the source. This is a `rustdoc` only lint, see the
+   /// documentation in the [rustdoc book].
+   ///
+   /// [rustdoc book]: ../../../rustdoc/lints.html#reformatted_macro_matchers
+   REFORMATTED_MACRO_MATCHERS,
+   Allow,
+   "detects macro matchers that are not documented as written in the source"
+}
+
 pub(crate) static RUSTDOC_LINTS: Lazy<Vec<&'static Lint>> = Lazy::new(|| {
     vec![
         BROKEN_INTRA_DOC_LINKS,
@@ -197,6 +209,7 @@ macro_rules! declare_rustdoc_lint {
         BARE_URLS,
         MISSING_CRATE_LEVEL_DOCS,
         UNESCAPED_BACKTICKS,
+        REFORMATTED_MACRO_MATCHERS,
     ]
 });
 
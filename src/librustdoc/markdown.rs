@@ -153,7 +153,13 @@ pub(crate) fn test(options: Options) -> Result<(), String> {
     let codes = ErrorCodes::from(options.unstable_features.is_nightly_build());
 
     find_testable_code(&input_str, &mut collector, codes, options.enable_per_target_ignores, None);
+    collector.write_persisted_manifest();
 
-    crate::doctest::run_tests(options.test_args, options.nocapture, collector.tests);
+    crate::doctest::run_tests(
+        options.test_args,
+        options.nocapture,
+        collector.tests,
+        &collector.item_paths,
+    );
     Ok(())
 }
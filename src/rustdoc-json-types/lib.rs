@@ -8,7 +8,7 @@
 use std::path::PathBuf;
 
 /// rustdoc format-version.
-pub const FORMAT_VERSION: u32 = 26;
+pub const FORMAT_VERSION: u32 = 34;
 
 /// A `Crate` is the root of the emitted JSON blob. It contains all type/documentation information
 /// about the language items in the local crate, as well as info about external items to allow
@@ -252,7 +252,7 @@ pub enum ItemEnum {
     ForeignType,
 
     /// Declarative macro_rules! macro
-    Macro(String),
+    Macro(Macro),
     ProcMacro(ProcMacro),
 
     Primitive(Primitive),
@@ -678,6 +678,113 @@ pub struct Import {
     pub glob: bool,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Macro {
+    /// The declaration of the macro, with the bodies of its arms elided.
+    pub source: String,
+    /// The signature the author documents the macro with instead of its declaration,
+    /// from `#[doc(macro_signature = "...")]`.
+    pub signature: Option<String>,
+    /// The matchers of the macro, in the order of its arms.
+    pub matchers: Vec<MacroMatcher>,
+    /// Whether the macro is `#[macro_export]`, and so at the root of its crate.
+    pub macro_export: bool,
+    /// Whether the macro is `#[macro_export(local_inner_macros)]`.
+    pub local_inner_macros: bool,
+    pub transparency: MacroTransparency,
+    /// The macro that generated this one, if it was generated by a macro, in which case
+    /// its matchers are pretty-printed rather than copied from the source code.
+    pub generated_by: Option<MacroGenerator>,
+}
+
+/// The macro a macro was generated by, like `make_macro` for the `a` of `make_macro!(a)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MacroGenerator {
+    pub name: String,
+    /// The ID of the generating macro, if it is in the `index` or the `paths`, which it is
+    /// if the generating macro is documented.
+    pub id: Option<Id>,
+}
+
+/// How the identifiers a macro expands to are resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroTransparency {
+    /// At the call site, as with `#[rustc_macro_transparency = "transparent"]`.
+    Transparent,
+    /// At the definition site for local variables and labels, and at the call site
+    /// otherwise. The default for `macro_rules!` macros.
+    SemiTransparent,
+    /// At the definition site. The default for macros 2.0.
+    Opaque,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MacroMatcher {
+    /// The matcher, as it appears in the declaration.
+    pub text: String,
+    /// Whether `text` was copied from the source code of the matcher, rather than
+    /// pretty-printed from its tokens.
+    pub from_source: bool,
+    /// Where the matcher is in the source code. `None` if it is `synthetic`, or not in a
+    /// local file.
+    pub span: Option<Span>,
+    /// Whether the matcher was generated by a macro, which its span would point into.
+    pub synthetic: bool,
+    /// The tokens of the matcher, from its opening delimiter to its closing one.
+    pub tokens: Vec<MacroMatcherToken>,
+    /// The metavariables the matcher binds, in the order they appear in it.
+    pub bindings: Vec<MacroBinding>,
+    /// The matcher in a canonical form, for comparing matchers, as across versions of a
+    /// crate. It is on a single line, with a space between two tokens only where they
+    /// would be lexed as other tokens without one, doc comments written as the
+    /// `#[doc = "..."]` attributes they stand for, and literals of the same value written
+    /// the same, as `31` for `0x1F` or `"a\\b"` for `r"a\b"`.
+    pub normalized: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MacroMatcherToken {
+    pub kind: MacroMatcherTokenKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroMatcherTokenKind {
+    /// A metavariable, like `$x` in `$x:expr`.
+    Metavariable,
+    /// The fragment specifier of a metavariable, like `expr` in `$x:expr`.
+    FragmentSpecifier,
+    /// The `*`, `+` or `?` ending a repetition, like `*` in `$($x:expr),*`.
+    RepetitionOperator,
+    /// Any other token, matched as it is. This includes delimiters, `$crate`,
+    /// and the `$` and separator of repetitions.
+    Token,
+}
+
+/// A metavariable bound by a macro matcher, like `$x:expr`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MacroBinding {
+    /// The name of the metavariable, without its `$`.
+    pub name: String,
+    /// The fragment specifier of the metavariable, like `expr` in `$x:expr`. `None` if
+    /// it has none, which is only accepted with the `missing_fragment_specifier` lint allowed.
+    pub fragment: Option<String>,
+    /// The repetitions the metavariable is nested in, from the outermost one. Their number
+    /// is the depth of the metavariable.
+    pub repetitions: Vec<MacroRepetition>,
+}
+
+/// A repetition in a macro matcher, like `$($x:expr),*`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MacroRepetition {
+    /// The token between the repetitions, like `,` in `$($x:expr),*`.
+    pub separator: Option<String>,
+    /// The Kleene operator of the repetition: `*`, `+` or `?`.
+    pub operator: String,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ProcMacro {
     pub kind: MacroKind,
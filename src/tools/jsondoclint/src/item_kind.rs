@@ -133,6 +133,9 @@ pub fn is_trait_or_alias(self) -> bool {
     pub fn is_type(self) -> bool {
         matches!(self, Kind::Struct | Kind::Enum | Kind::Union | Kind::Typedef)
     }
+    pub fn is_macro(self) -> bool {
+        matches!(self, Kind::Macro | Kind::ProcMacro)
+    }
 
     pub fn from_item(i: &Item) -> Self {
         use Kind::*;
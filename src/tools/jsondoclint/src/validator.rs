@@ -3,9 +3,10 @@
 
 use rustdoc_json_types::{
     Constant, Crate, DynTrait, Enum, FnDecl, Function, FunctionPointer, GenericArg, GenericArgs,
-    GenericBound, GenericParamDef, Generics, Id, Impl, Import, ItemEnum, ItemSummary, Module,
-    OpaqueTy, Path, Primitive, ProcMacro, Static, Struct, StructKind, Term, Trait, TraitAlias,
-    Type, TypeBinding, TypeBindingKind, Typedef, Union, Variant, VariantKind, WherePredicate,
+    GenericBound, GenericParamDef, Generics, Id, Impl, Import, ItemEnum, ItemSummary, Macro,
+    Module, OpaqueTy, Path, Primitive, ProcMacro, Static, Struct, StructKind, Term, Trait,
+    TraitAlias, Type, TypeBinding, TypeBindingKind, Typedef, Union, Variant, VariantKind,
+    WherePredicate,
 };
 use serde_json::Value;
 
@@ -239,8 +240,10 @@ fn check_static(&mut self, x: &'a Static) {
         self.check_type(&x.type_);
     }
 
-    fn check_macro(&mut self, _: &'a str) {
-        // nop
+    fn check_macro(&mut self, x: &'a Macro) {
+        if let Some(id) = x.generated_by.as_ref().and_then(|generator| generator.id.as_ref()) {
+            self.add_macro_id(id);
+        }
     }
 
     fn check_proc_macro(&mut self, _: &'a ProcMacro) {
@@ -453,6 +456,10 @@ fn add_type_id(&mut self, id: &'a Id) {
         self.add_id_checked(id, Kind::is_type, "Type (Struct, Enum, Union or Typedef)");
     }
 
+    fn add_macro_id(&mut self, id: &'a Id) {
+        self.add_id_checked(id, Kind::is_macro, "Macro (or ProcMacro)");
+    }
+
     /// Add an Id that appeared in a trait
     fn add_trait_item_id(&mut self, id: &'a Id) {
         self.add_id_checked(id, Kind::can_appear_in_trait, "Trait inner item");
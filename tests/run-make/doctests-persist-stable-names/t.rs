@@ -0,0 +1,17 @@
+/// Fungle the foople.
+/// ```
+/// t::foople();
+/// ```
+pub fn foople() {}
+
+/// Flomble the florp
+/// ```
+/// t::florp();
+/// ```
+///
+/// Or twice:
+/// ```
+/// t::florp();
+/// t::florp();
+/// ```
+pub fn florp() {}
@@ -0,0 +1,30 @@
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+pub fn passes() {}
+
+/// ```
+/// println!("about to fail");
+/// panic!("oh no");
+/// ```
+pub fn fails() {}
+
+/// ```ignore
+/// assert!(false);
+/// ```
+pub fn ignored() {}
+
+/// ```compile_fail
+/// let x: u8 = "not a number";
+/// ```
+pub fn fails_to_compile() {}
+
+/// ```
+/// let x: u8 = "not a number";
+/// ```
+pub fn compile_error() {}
+
+/// ```compile_fail
+/// let x: u8 = 1;
+/// ```
+pub fn unexpected_compile_pass() {}
@@ -0,0 +1,39 @@
+#!/usr/bin/env python
+
+import sys
+import json
+
+events = [json.loads(line) for line in sys.stdin]
+results = {}
+for event in events:
+    if event["type"] == "test" and event["event"] != "started":
+        # Doctests are named after the file, the item and the line of their code block.
+        name = event["name"]
+        assert name.startswith("t.rs - ") and name.endswith(")"), name
+        results[name.split(" ")[2]] = event
+
+
+def check(item, outcome, message=None, stdout=None):
+    event = results[item]
+    assert event["event"] == outcome, event
+    assert event.get("message") == message, event
+    if stdout is not None:
+        assert stdout in event["stdout"], event
+    if outcome != "ignored":
+        assert "exec_time" in event, event
+
+
+check("passes", "ok")
+check("fails", "failed", "Test executable failed (exit status: 101).", "oh no")
+check("ignored", "ignored")
+check("fails_to_compile", "ok")
+check("compile_error", "failed", "Couldn't compile the test.", "error[E0308]")
+check(
+    "unexpected_compile_pass",
+    "failed",
+    "Test compiled successfully, but it's marked `compile_fail`.",
+)
+
+suite = events[-1]
+assert suite["type"] == "suite" and suite["event"] == "failed", suite
+assert (suite["passed"], suite["failed"], suite["ignored"]) == (2, 3, 1), suite
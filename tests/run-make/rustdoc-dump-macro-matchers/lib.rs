@@ -0,0 +1,11 @@
+#![feature(decl_macro)]
+
+#[macro_export]
+macro_rules! exported {
+    ($x:expr) => {};
+    ($($t:tt)*) => {};
+}
+
+pub mod inner {
+    pub macro two_point_oh($name:ident) {}
+}
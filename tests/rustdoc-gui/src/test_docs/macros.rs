@@ -2,3 +2,54 @@
 macro_rules! a{ () => {}}
 #[macro_export]
 macro_rules! b{ () => {}}
+
+#[macro_export]
+macro_rules! with_matchers {
+    ($name:ident { $($field:ident: $ty:ty),* }) => {};
+}
+
+#[macro_export]
+macro_rules! two_arms {
+    () => {};
+    ($x:expr) => {};
+}
+
+#[macro_export]
+macro_rules! twenty_arms {
+    (arm0 $x:expr) => {};
+    (arm1 $x:expr) => {};
+    (arm2 $x:expr) => {};
+    (arm3 $x:expr) => {};
+    (arm4 $x:expr) => {};
+    (arm5 $x:expr) => {};
+    (arm6 $x:expr) => {};
+    (arm7 $x:expr) => {};
+    (arm8 $x:expr) => {};
+    (arm9 $x:expr) => {};
+    (arm10 $x:expr) => {};
+    (arm11 $x:expr) => {};
+    (arm12 $x:expr) => {};
+    (arm13 $x:expr) => {};
+    (arm14 $x:expr) => {};
+    (arm15 $x:expr) => {};
+    (arm16 $x:expr) => {};
+    (arm17 $x:expr) => {};
+    (arm18 $x:expr) => {};
+    (arm19 $x:expr) => {};
+}
+
+#[macro_export]
+macro_rules! twelve_arms {
+    (arm0 $x:expr) => {};
+    (arm1 $x:expr) => {};
+    (arm2 $x:expr) => {};
+    (arm3 $x:expr) => {};
+    (arm4 $x:expr) => {};
+    (arm5 $x:expr) => {};
+    (arm6 $x:expr) => {};
+    (arm7 $x:expr) => {};
+    (arm8 $x:expr) => {};
+    (arm9 $x:expr) => {};
+    (arm10 $x:expr) => {};
+    (arm11 $x:expr) => {};
+}
@@ -0,0 +1,39 @@
+// How a macro is exported and its transparency are part of the JSON output.
+
+// compile-flags: --document-private-items
+
+#![feature(decl_macro, rustc_attrs)]
+
+// @is "$.index[*][?(@.name=='exported')].inner.macro.macro_export" true
+// @is "$.index[*][?(@.name=='exported')].inner.macro.local_inner_macros" false
+// @is "$.index[*][?(@.name=='exported')].inner.macro.transparency" '"semi_transparent"'
+#[macro_export]
+macro_rules! exported {
+    () => {};
+}
+
+// @is "$.index[*][?(@.name=='not_exported')].inner.macro.macro_export" false
+// @is "$.index[*][?(@.name=='not_exported')].inner.macro.local_inner_macros" false
+macro_rules! not_exported {
+    () => {};
+}
+
+// @is "$.index[*][?(@.name=='inner_macros')].inner.macro.macro_export" true
+// @is "$.index[*][?(@.name=='inner_macros')].inner.macro.local_inner_macros" true
+#[macro_export(local_inner_macros)]
+macro_rules! inner_macros {
+    () => {
+        exported!()
+    };
+}
+
+// @is "$.index[*][?(@.name=='transparent')].inner.macro.transparency" '"transparent"'
+#[macro_export]
+#[rustc_macro_transparency = "transparent"]
+macro_rules! transparent {
+    () => {};
+}
+
+// @is "$.index[*][?(@.name=='opaque')].inner.macro.macro_export" false
+// @is "$.index[*][?(@.name=='opaque')].inner.macro.transparency" '"opaque"'
+pub macro opaque() {}
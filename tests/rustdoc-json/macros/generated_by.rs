@@ -0,0 +1,35 @@
+// A macro generated by another one comes with the name and ID of its generator.
+
+#[macro_export]
+macro_rules! make_macros {
+    ($($name:ident),*) => {
+        $(
+            #[macro_export]
+            macro_rules! $name {
+                ($a:expr) => {};
+            }
+        )*
+    };
+}
+
+// @set make_macros = "$.index[*][?(@.name=='make_macros')].id"
+// @is "$.index[*][?(@.name=='make_macros')].inner.macro.generated_by" null
+// @is "$.index[*][?(@.name=='first')].inner.macro.generated_by.name" '"make_macros"'
+// @is "$.index[*][?(@.name=='first')].inner.macro.generated_by.id" $make_macros
+// @is "$.index[*][?(@.name=='second')].inner.macro.generated_by.id" $make_macros
+// @is "$.index[*][?(@.name=='third')].inner.macro.generated_by.id" $make_macros
+make_macros!(first, second, third);
+
+// An undocumented generator is only known by its name.
+macro_rules! make_private_macro {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            () => {};
+        }
+    };
+}
+
+// @is "$.index[*][?(@.name=='fourth')].inner.macro.generated_by.name" '"make_private_macro"'
+// @is "$.index[*][?(@.name=='fourth')].inner.macro.generated_by.id" null
+make_private_macro!(fourth);
@@ -0,0 +1,33 @@
+// ignore-tidy-linelength
+
+// Each matcher lists the metavariables it binds, with their fragment specifiers
+// and the repetitions they are nested in.
+
+#![allow(missing_fragment_specifier)]
+
+// @count "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[*]" 3
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[0].name" '"name"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[0].fragment" '"ident"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[0].repetitions" []
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[1].name" '"key"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[1].fragment" '"expr"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[1].repetitions" '[{"separator": ",", "operator": "*"}]'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[2].name" '"value"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[2].fragment" '"ty"'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].bindings[2].repetitions" '[{"separator": ",", "operator": "*"}, {"separator": ";", "operator": "+"}]'
+#[macro_export]
+macro_rules! nested {
+    ($name:ident { $($key:expr => $($value:ty);+),* }) => {};
+}
+
+// Metavariables only used in the body are not bound by the matcher, and a
+// metavariable without a fragment specifier has a `null` one.
+// @count "$.index[*][?(@.name=='unspecified')].inner.macro.matchers[0].bindings[*]" 1
+// @is "$.index[*][?(@.name=='unspecified')].inner.macro.matchers[0].bindings[0].name" '"x"'
+// @is "$.index[*][?(@.name=='unspecified')].inner.macro.matchers[0].bindings[0].fragment" null
+#[macro_export]
+macro_rules! unspecified {
+    ($x) => {
+        $y
+    };
+}
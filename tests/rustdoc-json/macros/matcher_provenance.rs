@@ -0,0 +1,25 @@
+// Whether each matcher is shown as written in the source or pretty-printed from
+// its tokens is part of the JSON output.
+
+// @is "$.index[*][?(@.name=='written')].inner.macro.matchers[0].text" '"($a:expr ,$b:expr)"'
+// @is "$.index[*][?(@.name=='written')].inner.macro.matchers[0].from_source" true
+// @is "$.index[*][?(@.name=='written')].inner.macro.matchers[1].text" '"(@other)"'
+// @is "$.index[*][?(@.name=='written')].inner.macro.matchers[1].from_source" true
+#[macro_export]
+macro_rules! written {
+    ($a:expr ,$b:expr) => {};
+    (@other) => {};
+}
+
+macro_rules! make_macro {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            ($a:expr ,$b:expr) => {};
+        }
+    };
+}
+
+// @is "$.index[*][?(@.name=='generated')].inner.macro.matchers[0].text" '"($a:expr, $b:expr)"'
+// @is "$.index[*][?(@.name=='generated')].inner.macro.matchers[0].from_source" false
+make_macro!(generated);
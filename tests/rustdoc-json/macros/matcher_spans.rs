@@ -0,0 +1,30 @@
+// Each matcher has the span of its own arm, unless it was generated by a macro.
+
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[0].span.begin" "[14, 4]"
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[0].span.end" "[14, 6]"
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[0].synthetic" false
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[1].span.begin" "[15, 4]"
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[1].span.end" "[15, 13]"
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[2].span.begin" "[16, 4]"
+// @is "$.index[*][?(@.name=='three_arms')].inner.macro.matchers[2].span.end" "[18, 5]"
+// @is "$.index[*][?(@.name=='generated')].inner.macro.matchers[0].span" null
+// @is "$.index[*][?(@.name=='generated')].inner.macro.matchers[0].synthetic" true
+#[macro_export]
+macro_rules! three_arms {
+    () => {};
+    ($x:expr) => {};
+    (
+        $($y:tt)*
+    ) => {};
+}
+
+macro_rules! make_macro {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            ($x:expr) => {};
+        }
+    };
+}
+
+make_macro!(generated);
@@ -0,0 +1,47 @@
+// ignore-tidy-linelength
+
+// Each matcher comes with the tokens it is made of, telling metavariables,
+// fragment specifiers and repetition operators apart from the other tokens.
+
+// @count "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[*]" 10
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[0]" '{"kind": "token", "text": "("}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[1]" '{"kind": "token", "text": "$"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[2]" '{"kind": "token", "text": "("}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[3]" '{"kind": "metavariable", "text": "$x"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[4]" '{"kind": "token", "text": ":"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[5]" '{"kind": "fragment_specifier", "text": "expr"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[6]" '{"kind": "token", "text": ")"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[7]" '{"kind": "token", "text": ","}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[8]" '{"kind": "repetition_operator", "text": "*"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[0].tokens[9]" '{"kind": "token", "text": ")"}'
+// @is "$.index[*][?(@.name=='repetition')].inner.macro.matchers[1].tokens[8]" '{"kind": "repetition_operator", "text": "?"}'
+#[macro_export]
+macro_rules! repetition {
+    ($($x:expr),*) => {};
+    [$(@ $y:ident)?] => {};
+}
+
+// @count "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[*]" 11
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[0]" '{"kind": "token", "text": "{"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[1]" '{"kind": "token", "text": "["}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[2]" '{"kind": "token", "text": "("}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[3]" '{"kind": "metavariable", "text": "$a"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[5]" '{"kind": "fragment_specifier", "text": "tt"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[6]" '{"kind": "token", "text": ")"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[7]" '{"kind": "token", "text": "]"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[8]" '{"kind": "token", "text": "{"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[9]" '{"kind": "token", "text": "}"}'
+// @is "$.index[*][?(@.name=='nested')].inner.macro.matchers[0].tokens[10]" '{"kind": "token", "text": "}"}'
+#[macro_export]
+macro_rules! nested {
+    {[($a:tt)] {}} => {};
+}
+
+// @count "$.index[*][?(@.name=='dollar_crate')].inner.macro.matchers[0].tokens[*]" 5
+// @is "$.index[*][?(@.name=='dollar_crate')].inner.macro.matchers[0].tokens[1]" '{"kind": "token", "text": "$crate"}'
+// @is "$.index[*][?(@.name=='dollar_crate')].inner.macro.matchers[0].tokens[2]" '{"kind": "token", "text": "::"}'
+// @is "$.index[*][?(@.name=='dollar_crate')].inner.macro.matchers[0].tokens[3]" '{"kind": "token", "text": "Item"}'
+#[macro_export]
+macro_rules! dollar_crate {
+    ($crate::Item) => {};
+}
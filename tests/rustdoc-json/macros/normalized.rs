@@ -0,0 +1,32 @@
+// ignore-tidy-linelength
+
+// Each matcher comes with a canonical form of itself, which is only spaced out
+// where its tokens would run together and writes literals the same way for the
+// same value.
+
+// @is "$.index[*][?(@.name=='spaced')].inner.macro.matchers[0].normalized" '"($x:expr,$y:expr)"'
+// @is "$.index[*][?(@.name=='spaced')].inner.macro.matchers[1].normalized" '"[$($a:ident::$b:ident)=>*]"'
+// @is "$.index[*][?(@.name=='spaced')].inner.macro.matchers[2].normalized" '"{< < - > = >}"'
+#[macro_export]
+macro_rules! spaced {
+    ( $x:expr , $y:expr ) => {};
+    [$( $a:ident :: $b:ident )=>*] => {};
+    {< < - > = >} => {};
+}
+
+// @is "$.index[*][?(@.name=='literals')].inner.macro.matchers[0].normalized" '"(31 1000u8 12.5\"a\\\\b\")"'
+// @is "$.index[*][?(@.name=='literals')].inner.macro.matchers[1].normalized" '"(31 1000u8 12.5\"a\\\\b\")"'
+#[macro_export]
+macro_rules! literals {
+    (0x1F 1_000u8 1_2.5 r"a\b") => {};
+    (31 1000u8 12.5 "a\\b") => {};
+}
+
+// @is "$.index[*][?(@.name=='documented')].inner.macro.matchers[0].normalized" '"(#[doc=\" Docs\"]$x:ident)"'
+#[macro_export]
+macro_rules! documented {
+    (
+        /// Docs
+        $x:ident
+    ) => {};
+}
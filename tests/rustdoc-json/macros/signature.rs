@@ -0,0 +1,19 @@
+// The signature given with `#[doc(macro_signature)]` is part of the JSON output,
+// next to the declaration and matchers of the macro.
+
+#![feature(doc_macro_signature)]
+
+// @is "$.index[*][?(@.name=='munch')].inner.macro.signature" '"munch!($(KEY => VALUE),*)"'
+// @is "$.index[*][?(@.name=='munch')].inner.macro.matchers[0].text" '"(@acc [$($done:tt)*])"'
+#[doc(macro_signature = "munch!($(KEY => VALUE),*)")]
+#[macro_export]
+macro_rules! munch {
+    (@acc [$($done:tt)*]) => {};
+    ($($rest:tt)*) => {};
+}
+
+// @is "$.index[*][?(@.name=='plain')].inner.macro.signature" null
+#[macro_export]
+macro_rules! plain {
+    ($a:expr) => {};
+}
@@ -0,0 +1,27 @@
+// Check that every error code of a `compile_fail` doctest has to be emitted by the compiler.
+
+// compile-flags:--test --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// failure-status: 101
+
+/// ```compile_fail,E0308
+/// let x: () = 5i32;
+/// ```
+pub struct Right;
+
+/// ```compile_fail,E0308,E0425
+/// let x: () = 5i32;
+/// let y = z;
+/// ```
+pub struct Both;
+
+/// ```compile_fail,E0308,E0004
+/// let x: () = 5i32;
+/// ```
+pub struct OneMissing;
+
+/// ```compile_fail,E0308
+/// let x: i32 = 5i32;
+/// ```
+pub struct Compiles;
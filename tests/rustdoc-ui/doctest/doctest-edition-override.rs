@@ -0,0 +1,22 @@
+// Code blocks can be compiled with another edition than the one of the crate.
+
+// edition:2021
+// compile-flags:--test --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+/// `async` is only a keyword since the 2018 edition.
+///
+/// ```edition2015
+/// let async = 1;
+/// assert_eq!(async, 1);
+/// ```
+///
+/// Arrays are only iterated by value since the 2021 edition.
+///
+/// ```no_run,edition2018
+/// let array = [1, 2];
+/// let _: &i32 = array.into_iter().next().unwrap();
+/// ```
+pub struct Editions;
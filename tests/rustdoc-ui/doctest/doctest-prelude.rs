@@ -0,0 +1,38 @@
+// The statements of `#![doc(test(prelude = "..."))]` are added to every doctest that doesn't opt
+// out of them, without moving the errors of the doctests away from their lines. Doctests with a
+// `fn main` of their own don't get them.
+
+// compile-flags:--test --test-args=--test-threads=1
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// failure-status: 101
+
+#![doc(test(prelude = "use std::collections::HashMap;", prelude = "let answer = 42;"))]
+#![feature(doc_test_prelude)]
+
+/// ```
+/// let mut map = HashMap::new();
+/// map.insert("answer", answer);
+/// ```
+pub struct UsesPrelude;
+
+/// ```no_prelude
+/// struct HashMap;
+/// let answer = HashMap;
+/// ```
+pub struct OptsOut;
+
+/// ```
+/// assert_eq!(answer, 42);
+/// no
+/// ```
+pub struct Fails;
+
+/// ```
+/// fn main() {
+///     let answer = "mine";
+///     assert_eq!(answer, "mine");
+/// }
+/// ```
+pub struct OwnMain;
@@ -0,0 +1,28 @@
+// Check that doctests run in the directory given by `--doctest-run-directory`, which is another
+// name of `--test-run-directory`, or in the one of `CARGO_MANIFEST_DIR` by default, and that the
+// directory is given to them.
+
+// revisions: flag alias manifest none
+// [flag]check-pass
+// [flag]compile-flags:--test -Zunstable-options --doctest-run-directory={{src-base}}
+// [flag]unset-rustc-env:CARGO_MANIFEST_DIR
+// [alias]check-pass
+// [alias]compile-flags:--test --test-run-directory={{src-base}}/doctest
+// [alias]compile-flags:-Zunstable-options --doctest-run-directory={{src-base}}
+// [alias]unset-rustc-env:CARGO_MANIFEST_DIR
+// [manifest]check-pass
+// [manifest]compile-flags:--test
+// [manifest]rustc-env:CARGO_MANIFEST_DIR={{src-base}}
+// [none]failure-status: 101
+// [none]compile-flags:--test
+// [none]unset-rustc-env:CARGO_MANIFEST_DIR
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```
+/// let path = std::path::Path::new("doctest/doctest-run-directory.rs");
+/// assert!(path.exists());
+/// let dir = std::env::var("RUSTDOC_DOCTEST_RUN_DIRECTORY").unwrap();
+/// assert!(std::path::Path::new(&dir).join(path).exists());
+/// ```
+pub fn foo() {}
@@ -0,0 +1,35 @@
+// Doctests can be picked by the path of the item they document, or of one of its parents.
+
+// revisions: parent exact
+// check-pass
+// [parent]compile-flags:--test --test-args=item_path_filter::MyType
+// [exact]compile-flags:--test --test-args=--exact --test-args=MyType::parse
+// compile-flags:--test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+//! ```
+//! assert!(true);
+//! ```
+
+/// ```
+/// assert!(true);
+/// ```
+pub struct MyType;
+
+impl MyType {
+    /// ```
+    /// assert!(true);
+    /// ```
+    pub fn parse() {}
+
+    /// ```
+    /// assert!(true);
+    /// ```
+    pub fn parse_all() {}
+}
+
+/// ```
+/// assert!(true);
+/// ```
+pub fn other() {}
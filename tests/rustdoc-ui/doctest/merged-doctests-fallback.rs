@@ -0,0 +1,21 @@
+// ignore-windows
+// Check that doctests failing to compile together are compiled on their own, with a note saying
+// so.
+
+// check-pass
+// compile-flags:--test -Zunstable-options --merge-doctests=yes --test-args --test-threads=1
+// edition:2021
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```
+/// #[no_mangle]
+/// pub extern "C" fn clash() {}
+/// ```
+pub struct First;
+
+/// ```
+/// #[no_mangle]
+/// pub extern "C" fn clash() {}
+/// ```
+pub struct Second;
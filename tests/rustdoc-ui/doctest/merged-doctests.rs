@@ -0,0 +1,33 @@
+// ignore-windows
+// Doctests that can share a crate are compiled into a single binary. Check that they still run
+// one by one, and that a failure points back to where the doctest was written.
+
+// compile-flags:--test -Zunstable-options --merge-doctests=yes --test-args --test-threads=1
+// edition:2021
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// failure-status: 101
+
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+pub struct Passes;
+
+/// ```
+/// println!("stdout");
+/// panic!("oh no");
+/// ```
+pub struct Panics;
+
+/// ```should_panic
+/// panic!("expected");
+/// ```
+pub struct ShouldPanic;
+
+// Compiled on its own, because of the crate attribute.
+/// ```
+/// #![deny(dead_code)]
+/// fn main() {}
+/// ```
+pub struct CrateAttribute;
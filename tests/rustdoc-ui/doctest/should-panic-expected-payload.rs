@@ -0,0 +1,22 @@
+// Check that the message expected by `should_panic="..."` doctests is found whichever of the two
+// string types the payload of the panic is.
+
+// check-pass
+// compile-flags:--test --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```should_panic="oh no"
+/// std::panic::panic_any("oh no");
+/// ```
+pub struct StaticStr;
+
+/// ```should_panic="oh no"
+/// std::panic::panic_any(String::from("oh no"));
+/// ```
+pub struct OwnedString;
+
+/// ```should_panic="no 5"
+/// panic!("oh no {}", 5);
+/// ```
+pub struct Formatted;
@@ -0,0 +1,28 @@
+// Check that `should_panic="..."` doctests fail when they panic with another message.
+
+// compile-flags:--test --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// normalize-stdout-test "TypeId \{ t: \d+ \}" -> "TypeId { t: $$ID }"
+// failure-status: 101
+
+/// ```should_panic="out of bounds"
+/// let v: Vec<u8> = Vec::new();
+/// v[0];
+/// ```
+pub struct Matches;
+
+/// ```should_panic="overflow"
+/// panic!("oh no");
+/// ```
+pub struct Mismatch;
+
+/// ```should_panic="oh no"
+/// std::panic::panic_any(vec![0xffu8]);
+/// ```
+pub struct NotAString;
+
+/// ```should_panic="oh no"
+/// let x = 5;
+/// ```
+pub struct NoPanic;
@@ -0,0 +1,20 @@
+// Check that rustdoc warns about `should_panic="..."` doctests whose panic message it can't
+// check, and that these still have to panic.
+
+// check-pass
+// compile-flags:--test --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```should_panic="oh no"
+/// fn main() {
+///     panic!("something else");
+/// }
+/// ```
+pub struct OwnMain;
+
+/// ```test_harness,should_panic="oh no"
+/// #[test]
+/// fn oh_no() { panic!("something else") }
+/// ```
+pub struct TestHarness;
@@ -0,0 +1,25 @@
+// ignore-windows
+// Check that `standalone_crate` doctests are compiled and run on their own while the others are
+// merged.
+
+// check-pass
+// compile-flags:--test -Zunstable-options --merge-doctests=yes --test-args --test-threads=1
+// edition:2021
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```
+/// assert!(std::env::var_os("RUSTDOC_MERGED_DOCTEST").is_some());
+/// ```
+pub struct Merged;
+
+/// ```
+/// assert!(std::env::var_os("RUSTDOC_MERGED_DOCTEST").is_some());
+/// ```
+pub struct AlsoMerged;
+
+/// ```standalone_crate
+/// assert!(std::env::var_os("RUSTDOC_MERGED_DOCTEST").is_none());
+/// assert!(std::env::current_exe().unwrap().ends_with("rust_out"));
+/// ```
+pub struct Standalone;
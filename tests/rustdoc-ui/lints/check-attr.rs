@@ -39,3 +39,13 @@ pub fn foobar() {}
 /// boo
 /// ```
 pub fn b() {}
+
+/// e
+//~^ ERROR
+//~^^ ERROR
+//~^^^ ERROR
+///
+/// ```edition2020,edition,edition21
+/// boo
+/// ```
+pub fn e() {}
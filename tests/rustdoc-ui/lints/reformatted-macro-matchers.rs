@@ -0,0 +1,16 @@
+// compile-flags: -Z unstable-options --macro-matcher-snippet-limit 16
+
+#![deny(rustdoc::reformatted_macro_matchers)]
+
+#[macro_export]
+macro_rules! reformatted {
+    ($a:expr) => {};
+    ($a:expr, $b:expr, $c:expr) => {};
+    //~^ ERROR matcher is documented pretty-printed instead of as written
+}
+
+#[allow(rustdoc::reformatted_macro_matchers)]
+#[macro_export]
+macro_rules! allowed {
+    ($a:expr, $b:expr, $c:expr) => {};
+}
@@ -0,0 +1,35 @@
+// check-pass
+// Reparsing the source snippet of a macro matcher is speculative: whatever goes
+// wrong while doing it must not show up as a diagnostic, and must not stop the
+// documentation from being generated.
+
+#![crate_type = "lib"]
+
+macro_rules! make_macro {
+    ($name:ident $($matcher:tt)*) => {
+        #[macro_export]
+        macro_rules! $name {
+            ($($matcher)* r##"not "# the end"##) => {};
+        }
+    }
+}
+
+make_macro!(generated /* comment */ $x:ident);
+
+#[macro_export]
+macro_rules! plain {
+    (r#"raw"# /* comment */ $x:ident) => {};
+}
+
+// Comments inside matchers are dropped by the lexer, so the generated matcher
+// and the source it was written in don't have to line up at all.
+macro_rules! make_commented_macro {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            (/* outer /* inner */ $x:ident */ $y:ident) => {};
+        }
+    }
+}
+
+make_commented_macro!(commented);
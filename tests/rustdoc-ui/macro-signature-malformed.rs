@@ -0,0 +1,26 @@
+// Malformed `#[doc(macro_signature)]` attributes are errors pointing into the
+// attribute, and the macro is then documented with its declaration.
+
+#![crate_type = "lib"]
+#![feature(doc_macro_signature)]
+
+#[doc(macro_signature = "mismatched!(a])")]
+//~^ ERROR macro signature is not a valid token stream
+#[macro_export]
+macro_rules! mismatched {
+    ($a:ident) => {};
+}
+
+#[doc(macro_signature = "unclosed!(a, b")]
+//~^ ERROR macro signature is not a valid token stream
+#[macro_export]
+macro_rules! unclosed {
+    ($a:ident, $b:ident) => {};
+}
+
+#[doc(macro_signature = 1)]
+//~^ ERROR `macro_signature` expects a string
+#[macro_export]
+macro_rules! not_a_string {
+    () => {};
+}
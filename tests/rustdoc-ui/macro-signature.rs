@@ -0,0 +1,21 @@
+// check-pass
+// A `#[doc(macro_signature)]` that lexes is accepted without any diagnostic,
+// however odd the tokens in it are.
+
+#![crate_type = "lib"]
+#![feature(doc_macro_signature)]
+
+#[doc(macro_signature = "munch!($(KEY => VALUE),* ; { ... })")]
+#[macro_export]
+macro_rules! munch {
+    (@acc [$($done:tt)*]) => {};
+    (@acc [$($done:tt)*] $key:ident => $value:expr, $($rest:tt)*) => {};
+    ($($rest:tt)*) => {};
+}
+
+#[doc = "Documented with a signature written as a raw string."]
+#[doc(macro_signature = r#"quoted!("literal", 'c', $x)"#)]
+#[macro_export]
+macro_rules! quoted {
+    ($($tt:tt)*) => {};
+}
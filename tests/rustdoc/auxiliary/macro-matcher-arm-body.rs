@@ -0,0 +1,3 @@
+{
+    let _ = ($a, $b);
+}
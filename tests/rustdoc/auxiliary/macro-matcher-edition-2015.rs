@@ -0,0 +1,6 @@
+// edition:2015
+
+#[macro_export]
+macro_rules! old {
+    (async $dyn:ident ,dyn) => {};
+}
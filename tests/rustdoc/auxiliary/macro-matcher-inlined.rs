@@ -0,0 +1,5 @@
+#[macro_export]
+macro_rules! inlined {
+    ($a:expr ,$b:expr) => {};
+    (@rest $($c:tt) ,*) => {};
+}
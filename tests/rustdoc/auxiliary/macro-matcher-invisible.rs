@@ -0,0 +1,31 @@
+// force-host
+// no-prefer-dynamic
+// compile-flags: --crate-type proc-macro
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+/// Defines the macro `$name`, with a single arm whose matcher is made of the
+/// token trees following the name, with the brackets of the bracketed ones
+/// replaced by invisible delimiters.
+#[proc_macro]
+pub fn invisible_matcher(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let name = input.next().unwrap();
+    let trees = input.map(|tree| match tree {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => {
+            TokenTree::Group(Group::new(Delimiter::None, group.stream()))
+        }
+        tree => tree,
+    });
+    let matcher = Group::new(Delimiter::Parenthesis, trees.collect());
+
+    let mut arm = TokenStream::from(TokenTree::Group(matcher));
+    arm.extend("=> {};".parse::<TokenStream>().unwrap());
+    let mut out: TokenStream = "#[macro_export] macro_rules!".parse().unwrap();
+    out.extend([name, TokenTree::Group(Group::new(Delimiter::Brace, arm))]);
+    out
+}
@@ -0,0 +1,28 @@
+// force-host
+// no-prefer-dynamic
+// compile-flags: --crate-type proc-macro
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_span)]
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+/// Defines the macro `$name`, with a single arm whose matcher contains the
+/// groups following the name, and whose span is the one of these groups.
+#[proc_macro]
+pub fn joined_matcher(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let name = input.next().unwrap();
+    let groups: Vec<TokenTree> = input.collect();
+    let span = groups[0].span().join(groups[groups.len() - 1].span()).unwrap();
+    let mut matcher = Group::new(Delimiter::Parenthesis, groups.into_iter().collect());
+    matcher.set_span(span);
+
+    let mut arm = TokenStream::from(TokenTree::Group(matcher));
+    arm.extend("=> {};".parse::<TokenStream>().unwrap());
+    let mut out: TokenStream = "#[macro_export] macro_rules!".parse().unwrap();
+    out.extend([name, TokenTree::Group(Group::new(Delimiter::Brace, arm))]);
+    out
+}
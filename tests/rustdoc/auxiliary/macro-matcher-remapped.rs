@@ -0,0 +1,6 @@
+// compile-flags:--remap-path-prefix={{src-base}}=/does-not-exist
+
+#[macro_export]
+macro_rules! remapped {
+    ($a:expr ,$b:expr) => {};
+}
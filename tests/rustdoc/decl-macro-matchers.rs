@@ -0,0 +1,29 @@
+// Macros 2.0 go through the same matcher renderer as `macro_rules!` macros:
+// matchers are shown as written when their source is available, and
+// pretty-printed otherwise.
+
+#![feature(decl_macro)]
+#![crate_name = "foo"]
+
+// @has 'foo/macro.shorthand.html'
+// @has - '//pre' 'pub macro shorthand($a:expr ,$b:expr) {'
+pub macro shorthand($a:expr ,$b:expr) {}
+
+// @has 'foo/macro.multi.html'
+// @has - '//pre' 'pub macro multi {'
+// @has - '//pre' '($a:expr ,$b:expr) => { ... },'
+// @has - '//pre' '[$c:tt] => { ... },'
+pub macro multi {
+    ($a:expr ,$b:expr) => {},
+    [$c:tt] => {},
+}
+
+macro_rules! make_decl_macro {
+    ($name:ident) => {
+        pub macro $name($a:expr ,$b:expr) {}
+    };
+}
+
+// @has 'foo/macro.generated.html'
+// @has - '//pre' 'pub macro generated($a:expr, $b:expr) {'
+make_decl_macro!(generated);
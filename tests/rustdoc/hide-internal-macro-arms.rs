@@ -0,0 +1,40 @@
+// compile-flags: -Z unstable-options --hide-internal-macro-arms
+
+// The arms of a macro whose matcher starts with `@` and an identifier are internal
+// to it by convention, and are left out of its declaration with
+// `--hide-internal-macro-arms`.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.count.html'
+// @has - '//pre' '() => { ... };'
+// @has - '//pre' '($head:tt $($tail:tt)*) => { ... };'
+// @has - '//pre' '/* 2 internal arms hidden */'
+// @!has - '//pre' '@munch'
+// @!has - '//pre' '@done'
+// @count - '//pre//span[@class="macro-arm"]' 2
+#[macro_export]
+macro_rules! count {
+    () => { 0 };
+    (@munch $acc:expr; $head:tt $($tail:tt)*) => { count!(@munch $acc + 1; $($tail)*) };
+    ($head:tt $($tail:tt)*) => { count!(@munch 1; $($tail)*) };
+    (@done $acc:expr) => { $acc };
+}
+
+// Arms starting with a lone `@` are not internal ones.
+// @has 'foo/macro.at.html'
+// @has - '//pre' '(@ $x:expr) => { ... };'
+// @!has - '//pre' 'internal arm'
+#[macro_export]
+macro_rules! at {
+    (@ $x:expr) => {};
+}
+
+// A macro with only internal arms has nothing else to show.
+// @has 'foo/macro.only_internal.html'
+// @has - '//pre' '(@step $x:expr) => { ... };'
+// @!has - '//pre' 'internal arm'
+#[macro_export]
+macro_rules! only_internal {
+    (@step $x:expr) => {};
+}
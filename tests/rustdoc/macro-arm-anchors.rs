@@ -0,0 +1,30 @@
+// Each arm of a macro gets its own anchor in the declaration, numbered in the
+// order the arms are declared.
+
+#![feature(decl_macro)]
+#![crate_name = "foo"]
+
+// @has 'foo/macro.two_arms.html'
+// @has - '//pre/span[@id="arm-0"][@class="macro-arm"]' '() => { ... };'
+// @has - '//pre/span[@id="arm-1"][@class="macro-arm"]' '($x:expr) => { ... };'
+#[macro_export]
+macro_rules! two_arms {
+    () => {};
+    ($x:expr) => {};
+}
+
+// Identical arms are shown once, but every one of them can still be linked to.
+// @has 'foo/macro.same_arms.html'
+// @has - '//pre/span[@id="arm-0"]/span[@id="arm-1"]'
+// @has - '//pre/span[@id="arm-2"]' '($x:ident) => { ... };'
+#[macro_export]
+macro_rules! same_arms {
+    ($x:expr) => { 1 };
+    ($x:expr) => { 2 };
+    ($x:ident) => {};
+}
+
+// The shorthand form of a macros 2.0 item has no arms to link to.
+// @has 'foo/macro.shorthand.html'
+// @count - '//pre//span[@class="macro-arm"]' 0
+pub macro shorthand($x:expr) {}
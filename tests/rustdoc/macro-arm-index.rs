@@ -0,0 +1,44 @@
+// A macro with more arms than `--macro-arm-index-threshold` gets an index of
+// them, linking to each of them in the declaration.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.twenty_arms.html'
+// @count - '//ul[@class="macro-arm-index"]/li' 20
+// @has - '//ul[@class="macro-arm-index"]/li[1]/a[@href="#arm-0"]/code' '(a0 $x0:expr)'
+// @has - '//ul[@class="macro-arm-index"]/li[8]/a[@href="#arm-7"]/code' '(a7 $x7:expr)'
+// @has - '//pre//span[@id="arm-7"]' '(a7 $x7:expr) => { ... };'
+// The summary of an arm is cut short at the end of a metavariable.
+// @has - '//ul[@class="macro-arm-index"]/li[20]/a[@href="#arm-19"]/code' '$i:expr, ...)'
+// @has - '//pre//span[@id="arm-19"]' '$j:expr'
+#[macro_export]
+macro_rules! twenty_arms {
+    (a0 $x0:expr) => {};
+    (a1 $x1:expr) => {};
+    (a2 $x2:expr) => {};
+    (a3 $x3:expr) => {};
+    (a4 $x4:expr) => {};
+    (a5 $x5:expr) => {};
+    (a6 $x6:expr) => {};
+    (a7 $x7:expr) => {};
+    (a8 $x8:expr) => {};
+    (a9 $x9:expr) => {};
+    (a10 $x10:expr) => {};
+    (a11 $x11:expr) => {};
+    (a12 $x12:expr) => {};
+    (a13 $x13:expr) => {};
+    (a14 $x14:expr) => {};
+    (a15 $x15:expr) => {};
+    (a16 $x16:expr) => {};
+    (a17 $x17:expr) => {};
+    (a18 $x18:expr) => {};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr, $h:expr, $i:expr, $j:expr) => {};
+}
+
+// @has 'foo/macro.few_arms.html'
+// @count - '//ul[@class="macro-arm-index"]' 0
+#[macro_export]
+macro_rules! few_arms {
+    () => {};
+    ($x:expr) => {};
+}
@@ -0,0 +1,33 @@
+// Each arm of a macro links to the line of the source it starts at, or to the
+// whole macro if it was generated by another one.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.spread.html'
+// @count - '//pre/a[@class="macro-arm-src"]' 2
+// @has - '//pre/a[@href="../src/foo/macro-arm-src-links.rs.html#12"]'
+// @has - '//pre/a[@href="../src/foo/macro-arm-src-links.rs.html#16"]'
+#[macro_export]
+macro_rules! spread {
+    () => {};
+
+    // Far away.
+
+    (
+        $x:expr
+    ) => {};
+}
+
+macro_rules! make_macro {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            () => {};
+            ($x:expr) => {};
+        }
+    };
+}
+
+// @has 'foo/macro.generated.html'
+// @count - '//pre/a[@href="../src/foo/macro-arm-src-links.rs.html#33"]' 2
+make_macro!(generated);
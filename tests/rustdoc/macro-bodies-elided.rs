@@ -0,0 +1,13 @@
+// Without `--document-macro-bodies`, what each rule expands to is elided.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.square.html
+// @has - '//pre' '($x:expr) => { ... };'
+// @has - '//pre' '() => { ... };'
+// @!has - '//pre' '$x * $x'
+#[macro_export]
+macro_rules! square {
+    ($x:expr) => { $x * $x };
+    () => {};
+}
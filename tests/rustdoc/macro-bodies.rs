@@ -0,0 +1,15 @@
+// compile-flags: -Z unstable-options --document-macro-bodies
+// With `--document-macro-bodies`, what each rule expands to is shown after its
+// matcher, instead of `{ ... }`.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.square.html
+// @has - '//pre' '($x:expr) => { $x * $x };'
+// @has - '//pre' '() => {};'
+// @!has - '//pre' '{ ... }'
+#[macro_export]
+macro_rules! square {
+    ($x:expr) => { $x * $x };
+    () => {};
+}
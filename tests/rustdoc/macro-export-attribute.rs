@@ -0,0 +1,52 @@
+// ignore-tidy-linelength
+
+// The declaration of a macro shows how it is exported, and its transparency
+// unless it is the default one.
+
+// compile-flags: --document-private-items
+
+#![feature(decl_macro, rustc_attrs)]
+#![crate_name = "foo"]
+
+// @has 'foo/macro.exported.html'
+// @has - '//pre[@class="rust item-decl"]' '#[macro_export] macro_rules! exported {'
+// @!has - '//pre[@class="rust item-decl"]' 'local_inner_macros'
+// @!has - '//pre[@class="rust item-decl"]' 'rustc_macro_transparency'
+#[macro_export]
+macro_rules! exported {
+    () => {};
+}
+
+// @has 'foo/macro.not_exported.html'
+// @has - '//pre[@class="rust item-decl"]' 'macro_rules! not_exported {'
+// @!has - '//pre[@class="rust item-decl"]' '#['
+macro_rules! not_exported {
+    () => {};
+}
+
+// @has 'foo/macro.inner_macros.html'
+// @has - '//pre[@class="rust item-decl"]' '#[macro_export(local_inner_macros)] macro_rules! inner_macros {'
+#[macro_export(local_inner_macros)]
+macro_rules! inner_macros {
+    () => {
+        exported!()
+    };
+}
+
+// @has 'foo/macro.transparent.html'
+// @has - '//pre[@class="rust item-decl"]' '#[macro_export] #[rustc_macro_transparency = "transparent"] macro_rules! transparent {'
+#[macro_export]
+#[rustc_macro_transparency = "transparent"]
+macro_rules! transparent {
+    () => {};
+}
+
+// @has 'foo/macro.opaque.html'
+// @has - '//pre[@class="rust item-decl"]' 'pub macro opaque() {'
+// @!has - '//pre[@class="rust item-decl"]' '#['
+pub macro opaque() {}
+
+// @has 'foo/macro.semitransparent.html'
+// @has - '//pre[@class="rust item-decl"]' '#[rustc_macro_transparency = "semitransparent"] pub macro semitransparent() {'
+#[rustc_macro_transparency = "semitransparent"]
+pub macro semitransparent() {}
@@ -0,0 +1,16 @@
+// ignore-tidy-linelength
+
+// Fragment specifiers in macro declarations link to what they match in the
+// Rust Reference.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.fragments.html'
+// @has - '//pre[@class="rust item-decl"]//a[@class="macro-fragment"][@href="https://doc.rust-lang.org/reference/expressions.html"]' 'expr'
+// @has - '//pre[@class="rust item-decl"]//a[@class="macro-fragment"][@href="https://doc.rust-lang.org/reference/macros.html#macro-invocation"]' 'tt'
+// @has - '//pre[@class="rust item-decl"]//a[@class="macro-fragment"][@href="https://doc.rust-lang.org/reference/visibility-and-privacy.html"]' 'vis'
+// @count - '//pre[@class="rust item-decl"]//a[@class="macro-fragment"]' 3
+#[macro_export]
+macro_rules! fragments {
+    ($e:expr, $t:tt, $v:vis) => {};
+}
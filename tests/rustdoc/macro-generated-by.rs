@@ -0,0 +1,41 @@
+// ignore-tidy-linelength
+
+// A macro generated by another one tells which one, since its declaration is
+// nowhere to be found in the source code, and links to it if it is documented.
+
+#![crate_name = "foo"]
+
+#[macro_export]
+macro_rules! make_macros {
+    ($($name:ident),*) => {
+        $(
+            #[macro_export]
+            macro_rules! $name {
+                ($a:expr) => {};
+            }
+        )*
+    };
+}
+
+// @has 'foo/macro.first.html' '//*[@class="stab macro-generated"]' 'Generated by the make_macros! macro.'
+// @has - '//*[@class="stab macro-generated"]/a[@href="macro.make_macros.html"]/code' 'make_macros!'
+// @has 'foo/macro.second.html' '//*[@class="stab macro-generated"]/a[@href="macro.make_macros.html"]/code' 'make_macros!'
+// @has 'foo/macro.third.html' '//*[@class="stab macro-generated"]/a[@href="macro.make_macros.html"]/code' 'make_macros!'
+make_macros!(first, second, third);
+
+// @has 'foo/macro.make_macros.html'
+// @!has - '//*[@class="stab macro-generated"]' ''
+
+macro_rules! make_macro_privately {
+    ($name:ident) => {
+        #[macro_export]
+        macro_rules! $name {
+            () => {};
+        }
+    };
+}
+
+// The generator is not documented, so there is nothing to link to.
+// @has 'foo/macro.fourth.html' '//*[@class="stab macro-generated"]/code' 'make_macro_privately!'
+// @count - '//*[@class="stab macro-generated"]//a' 0
+make_macro_privately!(fourth);
@@ -37,3 +37,36 @@ macro_rules! $macro_name {
     "space between keyword and paren": return (a,) & for x in (..)
     "some special case keywords": pub(crate), fn() -> u8, Self(0, 0)
 );
+
+// The matcher of a macro-generated macro is pretty-printed even when the source
+// of the generating macro contains the very same tokens, as that source is not
+// where the matcher is written.
+macro_rules! make_fixed_macro {
+    ($macro_name:ident) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($a:expr ,$b:expr) => {};
+            (@inner   $c:tt) => {};
+        }
+    }
+}
+
+// @has macro_generated_macro/macro.fixed.html //pre 'macro_rules! fixed {'
+// @has - //pre '($a:expr, $b:expr) => { ... };'
+// @has - //pre '(@ inner $c:tt) => { ... };'
+make_fixed_macro!(fixed);
+
+// Fragments the generating macro was called with are spliced into the matcher
+// whole, and printed like the tokens they were parsed from.
+macro_rules! make_typed_macro {
+    ($macro_name:ident, $name:ident, $t:ty) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($name: $t) => {};
+        }
+    }
+}
+
+// @has macro_generated_macro/macro.typed.html //pre 'macro_rules! typed {'
+// @has - //pre '(value: Vec<u8>) => { ... };'
+make_typed_macro!(typed, value, Vec<u8>);
@@ -0,0 +1,43 @@
+// compile-flags: -Z unstable-options --macro-visible-arms 2
+
+// Only the first `--macro-visible-arms` arms of a macro are shown at first, and
+// the others are put behind a toggle.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.four_arms.html'
+// @count - '//pre/span[@class="macro-arm"]' 2
+// @has - '//pre/span[@id="arm-1"]' '(b) => { ... };'
+// @count - '//pre/details[@class="toggle type-contents-toggle"]/span[@class="macro-arm"]' 2
+// @has - '//pre/details/summary' 'Show 2 more arms'
+// @has - '//pre/details/span[@id="arm-2"]' '(c) => { ... };'
+// @has - '//pre/details/span[@id="arm-3"]' '(d) => { ... };'
+#[macro_export]
+macro_rules! four_arms {
+    (a) => {};
+    (b) => {};
+    (c) => {};
+    (d) => {};
+}
+
+// Identical arms are shown once, and only count as one.
+// @has 'foo/macro.same_arms.html'
+// @count - '//pre/span[@class="macro-arm"]' 2
+// @has - '//pre/details/summary' 'Show 1 more arm'
+// @has - '//pre/details/span[@id="arm-3"]/span[@id="arm-4"]'
+#[macro_export]
+macro_rules! same_arms {
+    (a) => { 1 };
+    (a) => { 2 };
+    (b) => {};
+    (c) => { 1 };
+    (c) => { 2 };
+}
+
+// @has 'foo/macro.two_arms.html'
+// @count - '//pre/details' 0
+#[macro_export]
+macro_rules! two_arms {
+    (a) => {};
+    (b) => {};
+}
@@ -0,0 +1,15 @@
+// Consecutive arms with the same matcher, which only differ in their elided
+// transcribers, are shown once.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.shared.html
+// @has - '//pre' '($x:expr) => { ... }; /* 3 identical arms */'
+// @has - '//pre' '($y:ident) => { ... };'
+#[macro_export]
+macro_rules! shared {
+    ($x:expr) => { 1 };
+    ($x:expr) => { 2 };
+    ($x:expr) => { 3 };
+    ($y:ident) => {};
+}
@@ -0,0 +1,18 @@
+// compile-flags: -Z unstable-options --show-macro-invocations
+
+// With `--show-macro-invocations`, an example invocation of each arm of a macro
+// is shown after its documentation.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.make_struct.html'
+// @has - '//h2[@id="example-invocations"]' 'Example invocations'
+// @has - '//pre[@class="rust rust-example-rendered"]' 'make_struct!(NAME)'
+// @has - '//pre[@class="rust rust-example-rendered"]' 'make_struct!(NAME, FIELD: TY, /* ... */)'
+// @has - '//pre[@class="rust rust-example-rendered"]' 'make_struct! { NAME }'
+#[macro_export]
+macro_rules! make_struct {
+    ($name:ident) => {};
+    ($name:ident, $($field:ident: $ty:ty),* $(,)?) => {};
+    { $name:ident } => {};
+}
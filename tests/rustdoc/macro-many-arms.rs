@@ -0,0 +1,34 @@
+// The source snippets of all the matchers of a crate are reparsed in a single
+// shared session. Check that every arm of a macro with many rules is still
+// displayed exactly as written.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.many_arms.html'
+// @has - '//pre' '(@a $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@b $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@c $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@d $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@e $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@f $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@g $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@h $a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@i $($a:expr) ,*) => { ... };'
+// @has - '//pre' '(@j $($a:expr) ,*) => { ... };'
+// @has - '//pre' '(@k $($a:expr) ,*) => { ... };'
+// @has - '//pre' '(@l $($a:expr) ,*) => { ... };'
+#[macro_export]
+macro_rules! many_arms {
+    (@a $a:expr ,$b:expr) => {};
+    (@b $a:expr ,$b:expr) => {};
+    (@c $a:expr ,$b:expr) => {};
+    (@d $a:expr ,$b:expr) => {};
+    (@e $a:expr ,$b:expr) => {};
+    (@f $a:expr ,$b:expr) => {};
+    (@g $a:expr ,$b:expr) => {};
+    (@h $a:expr ,$b:expr) => {};
+    (@i $($a:expr) ,*) => {};
+    (@j $($a:expr) ,*) => {};
+    (@k $($a:expr) ,*) => {};
+    (@l $($a:expr) ,*) => {};
+}
@@ -0,0 +1,21 @@
+// compile-flags: --cfg feature="x"
+// Matchers are never affected by `cfg` stripping, so whatever the configuration
+// they are shown as written, without being marked as compiled.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.with_feature.html
+// @has - '//pre' '($a:expr ,#[cfg(feature = "x")] $b:expr) => { ... };'
+// @!has - '//pre' 'as compiled'
+#[cfg(feature = "x")]
+#[macro_export]
+macro_rules! with_feature {
+    ($a:expr ,#[cfg(feature = "x")] $b:expr) => {};
+}
+
+// @!has foo/macro.without_feature.html
+#[cfg(not(feature = "x"))]
+#[macro_export]
+macro_rules! without_feature {
+    ($a:expr ,#[cfg(feature = "x")] $b:expr) => {};
+}
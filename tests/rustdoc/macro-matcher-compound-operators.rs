@@ -0,0 +1,19 @@
+// Matchers containing operators made of several punctuation characters are
+// still shown as written in the source.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.operators.html
+// @has - '//pre' '($a:ident>>$b:ident) => { ... };'
+// @has - '//pre' '($a:tt ..=$b:tt) => { ... };'
+// @has - '//pre' '($a:ident ::$b:ident) => { ... };'
+// @has - '//pre' '($a:ident&&$b:ident) => { ... };'
+// @has - '//pre' '(> >$c:ident >>= $d:ident <<=) => { ... };'
+#[macro_export]
+macro_rules! operators {
+    ($a:ident>>$b:ident) => {};
+    ($a:tt ..=$b:tt) => {};
+    ($a:ident ::$b:ident) => {};
+    ($a:ident&&$b:ident) => {};
+    (> >$c:ident >>= $d:ident <<=) => {};
+}
@@ -0,0 +1,15 @@
+// This file has CRLF line endings, which must not end up in the rendered
+// declaration of its macro.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.crlf.html
+// @!matchesraw - "\r"
+// @snapshot crlf_pre - //pre/text()
+#[macro_export]
+macro_rules! crlf {
+    (
+        $a:expr ,
+        $b:expr
+    ) => {};
+}
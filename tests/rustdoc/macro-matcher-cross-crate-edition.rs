@@ -0,0 +1,16 @@
+// aux-build:macro-matcher-edition-2015.rs
+// build-aux-docs
+// edition:2021
+
+// A macro inlined from a crate of another edition is shown as written in that
+// crate, even when its matchers use words that are keywords in the edition of
+// the documented crate.
+
+#![crate_name = "foo"]
+
+extern crate macro_matcher_edition_2015;
+
+// @has macro_matcher_edition_2015/macro.old.html '//pre' '(async $dyn:ident ,dyn) => { ... };'
+// @has foo/macro.old.html '//pre' '(async $dyn:ident ,dyn) => { ... };'
+#[doc(inline)]
+pub use macro_matcher_edition_2015::old;
@@ -0,0 +1,16 @@
+// aux-build:macro-matcher-inlined.rs
+// build-aux-docs
+
+// A macro inlined from another crate is shown with the same matchers as in the
+// documentation of that crate, copied from its source.
+
+#![crate_name = "foo"]
+
+extern crate macro_matcher_inlined;
+
+// @has macro_matcher_inlined/macro.inlined.html
+// @snapshot upstream_pre - //pre/text()
+// @has foo/macro.inlined.html
+// @snapshot inlined_pre - //pre/text()
+#[doc(inline)]
+pub use macro_matcher_inlined::inlined;
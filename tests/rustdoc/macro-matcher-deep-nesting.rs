@@ -0,0 +1,40 @@
+// Matchers are compared and pretty-printed without recursing into their
+// delimited groups, which would overflow the stack for deep enough nesting.
+
+#![crate_name = "foo"]
+#![recursion_limit = "1024"]
+
+// Wraps the tokens after the brackets in one more pair of parentheses for each
+// token between the brackets, then defines `nested` with them as its matcher.
+macro_rules! nest {
+    ([] $($t:tt)*) => {
+        #[macro_export]
+        macro_rules! nested {
+            ($($t)*) => {};
+        }
+    };
+    ([$_x:tt $($rest:tt)*] $($t:tt)*) => {
+        nest!([$($rest)*] ($($t)*));
+    };
+}
+
+// @has foo/macro.nested.html
+// @has - '//pre' '((((((((((((((((x))))))))))))))))'
+nest!([
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+    x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+] x);
@@ -0,0 +1,17 @@
+// compile-flags: -Z unstable-options --normalize-macro-matchers
+// Pretty-printed matchers keep the delimiters their arm was declared with.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.delimiters.html
+// @has - '//pre' '($a:expr) => { ... };'
+// @has - '//pre' '[$b:expr] => { ... };'
+// @has - '//pre' '{ $c:expr } => { ... };'
+// @has - '//pre' '{} => { ... };'
+#[macro_export]
+macro_rules! delimiters {
+    ($a:expr) => {};
+    [$b:expr] => {};
+    {$c:expr} => {};
+    {} => {};
+}
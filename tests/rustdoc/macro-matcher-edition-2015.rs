@@ -0,0 +1,16 @@
+// edition:2015
+
+// Matcher snippets are reparsed with the edition of the documented crate, so
+// identifiers that only became keywords in later editions don't make the
+// snippet path fail.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.old_keywords.html'
+// @has - '//pre' '(async $x:ident ,try) => { ... };'
+// @has - '//pre' '(dyn ,await $e:expr) => { ... };'
+#[macro_export]
+macro_rules! old_keywords {
+    (async $x:ident ,try) => {};
+    (dyn ,await $e:expr) => {};
+}
@@ -0,0 +1,15 @@
+// edition:2021
+
+// Matcher snippets are reparsed with the edition of the documented crate, so
+// that tokens following the 2021 lexing rules still take the snippet path.
+
+#![crate_name = "foo"]
+
+// @has 'foo/macro.prefixes.html'
+// @has - '//pre' '($p:pat_param | $q:pat ,r#try) => { ... };'
+// @has - '//pre' '(b"bytes" ,br#"raw"# ,async move) => { ... };'
+#[macro_export]
+macro_rules! prefixes {
+    ($p:pat_param | $q:pat ,r#try) => {};
+    (b"bytes" ,br#"raw"# ,async move) => {};
+}
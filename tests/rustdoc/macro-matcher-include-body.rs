@@ -0,0 +1,17 @@
+// The bodies of the arms of this macro are included from another file, which
+// doesn't prevent their matchers from being shown as written.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.included_body.html
+// @has - '//pre' '($a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@other $a:expr ,$b:expr) => { ... };'
+#[macro_export]
+macro_rules! included_body {
+    ($a:expr ,$b:expr) => {
+        include!("auxiliary/macro-matcher-arm-body.rs")
+    };
+    (@other $a:expr ,$b:expr) => {
+        include!("auxiliary/macro-matcher-arm-body.rs")
+    };
+}
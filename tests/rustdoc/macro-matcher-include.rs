@@ -0,0 +1,19 @@
+// Source files are lexed once for all the matchers they contain. Matchers of
+// macros from another file, brought in with `include!`, must be looked up in
+// that file, and not in the one the first matcher came from.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.local.html
+// @has - '//pre' '($x:ident ;$y:ident) => { ... };'
+// @has - '//pre' '(@local $($z:tt) ,*) => { ... };'
+#[macro_export]
+macro_rules! local {
+    ($x:ident ;$y:ident) => {};
+    (@local $($z:tt) ,*) => {};
+}
+
+// @has foo/macro.included.html
+// @has - '//pre' '($a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '(@rest $($c:tt) ,*) => { ... };'
+include!("auxiliary/macro-matcher-included.rs");
@@ -0,0 +1,34 @@
+// Multi-line matchers shown from their source snippet are moved to the
+// indentation of the rendered declaration, whatever the indentation of the
+// macro in its source file is.
+
+#![crate_name = "foo"]
+
+pub mod a {
+    pub mod b {
+        pub mod c {
+            // @snapshot deep_pre foo/macro.deep.html //pre/text()
+            #[macro_export]
+            macro_rules! deep {
+                (
+                    $name:ident {
+
+                        $($field:ident : $ty:ty),*
+                    }
+                ) => {};
+                ($a:expr,
+                    $b:expr) => {};
+            }
+        }
+    }
+}
+
+// @snapshot top_level_pre foo/macro.top_level.html //pre/text()
+#[macro_export]
+macro_rules! top_level {
+    (
+        $name:ident {
+            $($field:ident : $ty:ty),*
+        }
+    ) => {};
+}
@@ -0,0 +1,13 @@
+// aux-build:macro-matcher-invisible.rs
+
+// Proc macros can wrap the fragments of the matchers they generate in invisible
+// groups. These are printed as just their contents, spaced as if they were not
+// in a group at all.
+
+#![crate_name = "foo"]
+
+extern crate macro_matcher_invisible;
+
+// @has foo/macro.grouped.html
+// @has - '//pre' '($a:expr, $b:expr + f(0)) => { ... };'
+macro_matcher_invisible::invisible_matcher!(grouped [$a:expr], [$b:expr] + [f](0));
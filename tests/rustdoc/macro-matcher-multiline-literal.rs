@@ -0,0 +1,14 @@
+// ignore-tidy-tab
+// Matchers with literals spanning several lines are still shown as written,
+// with the layout of the literals untouched by the reindentation.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.literal.html
+// @matches - '//pre[@class="rust item-decl"]' '\(\$a:expr,\n {8}"first\n\t\tsecond"\)'
+#[macro_export]
+macro_rules! literal {
+    ($a:expr,
+        "first
+		second") => {};
+}
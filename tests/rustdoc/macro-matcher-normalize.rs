@@ -0,0 +1,24 @@
+// compile-flags: -Z unstable-options --normalize-macro-matchers
+// With `--normalize-macro-matchers`, matchers are pretty-printed even when
+// their source is available, so the formatting of the source doesn't matter.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.compact.html
+// @snapshot compact_pre - //pre/text()
+#[macro_export]
+macro_rules! compact {
+    ($a:expr ,$b:expr) => {};
+    (@inner  $($c:tt)*) => {};
+}
+
+// @has foo/macro.spread.html
+// @snapshot spread_pre - //pre/text()
+#[macro_export]
+macro_rules! spread {
+    (
+        $a:expr
+        , $b:expr
+    ) => {};
+    (@ inner $( $c:tt )*) => {};
+}
@@ -0,0 +1,16 @@
+// compile-flags: --remap-path-prefix={{src-base}}=/remapped
+// Remapping the paths of the documented crate doesn't change how its matchers
+// are rendered: the declaration is the same as without remapping.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.remapped.html
+// @snapshot remapped_pre - //pre/text()
+#[macro_export]
+macro_rules! remapped {
+    ($a:expr ,$b:expr) => {};
+    (
+        @multi $a:expr ,
+            $b:expr
+    ) => {};
+}
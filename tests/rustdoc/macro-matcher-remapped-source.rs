@@ -0,0 +1,13 @@
+// aux-build:macro-matcher-remapped.rs
+
+// The source of a macro from a crate compiled with remapped paths can't be
+// found, so its matchers are pretty-printed.
+
+#![crate_name = "foo"]
+
+extern crate macro_matcher_remapped;
+
+// @has foo/macro.remapped.html
+// @has - '//pre' '($a:expr, $b:expr) => { ... };'
+#[doc(inline)]
+pub use macro_matcher_remapped::remapped;
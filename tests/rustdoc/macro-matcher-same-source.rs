@@ -0,0 +1,32 @@
+// Matchers are reparsed in a throwaway source map, where each source is
+// registered under a name derived from its text. Arms and macros whose
+// matchers have the same text, or whose files do, must not get mixed up.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.first.html
+// @has - '//pre' '($a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '($a:expr ,$b:expr ,) => { ... };'
+#[macro_export]
+macro_rules! first {
+    ($a:expr ,$b:expr) => {};
+    ($a:expr ,$b:expr ,) => {};
+}
+
+// @has foo/macro.second.html
+// @has - '//pre' '($a:expr ,$b:expr) => { ... };'
+// @has - '//pre' '($a:expr ,$b:expr ,) => { ... };'
+#[macro_export]
+macro_rules! second {
+    ($a:expr ,$b:expr) => {};
+    ($a:expr ,$b:expr ,) => {};
+}
+
+pub mod inner {
+    // @has foo/macro.third.html
+    // @has - '//pre' '($a:ident ;$b:ident) => { ... };'
+    #[macro_export]
+    macro_rules! third {
+        ($a:ident ;$b:ident) => {};
+    }
+}
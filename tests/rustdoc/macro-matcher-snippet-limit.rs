@@ -0,0 +1,59 @@
+// compile-flags: -Z unstable-options --macro-matcher-snippet-limit 64
+// Matchers whose source is larger than the limit are pretty-printed from their
+// tokens without reparsing their source, however large they are.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.limited.html
+// @has - '//pre' '(x ,y) => { ... };'
+// @has - '//pre' '($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr,'
+// @!has - '//pre' '$a:expr ,$b:expr'
+// @has - '//pre' '$t0:tt $t1:tt'
+// @has - '//pre' '$t398:tt $t399:tt'
+#[macro_export]
+macro_rules! limited {
+    (x ,y) => {};
+    ($a:expr ,$b:expr ,$c:expr ,$d:expr ,$e:expr ,$f:expr ,$g:expr ,$h:expr) => {};
+    (
+        $t0:tt $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt
+        $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt $t17:tt $t18:tt $t19:tt
+        $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt
+        $t30:tt $t31:tt $t32:tt $t33:tt $t34:tt $t35:tt $t36:tt $t37:tt $t38:tt $t39:tt
+        $t40:tt $t41:tt $t42:tt $t43:tt $t44:tt $t45:tt $t46:tt $t47:tt $t48:tt $t49:tt
+        $t50:tt $t51:tt $t52:tt $t53:tt $t54:tt $t55:tt $t56:tt $t57:tt $t58:tt $t59:tt
+        $t60:tt $t61:tt $t62:tt $t63:tt $t64:tt $t65:tt $t66:tt $t67:tt $t68:tt $t69:tt
+        $t70:tt $t71:tt $t72:tt $t73:tt $t74:tt $t75:tt $t76:tt $t77:tt $t78:tt $t79:tt
+        $t80:tt $t81:tt $t82:tt $t83:tt $t84:tt $t85:tt $t86:tt $t87:tt $t88:tt $t89:tt
+        $t90:tt $t91:tt $t92:tt $t93:tt $t94:tt $t95:tt $t96:tt $t97:tt $t98:tt $t99:tt
+        $t100:tt $t101:tt $t102:tt $t103:tt $t104:tt $t105:tt $t106:tt $t107:tt $t108:tt $t109:tt
+        $t110:tt $t111:tt $t112:tt $t113:tt $t114:tt $t115:tt $t116:tt $t117:tt $t118:tt $t119:tt
+        $t120:tt $t121:tt $t122:tt $t123:tt $t124:tt $t125:tt $t126:tt $t127:tt $t128:tt $t129:tt
+        $t130:tt $t131:tt $t132:tt $t133:tt $t134:tt $t135:tt $t136:tt $t137:tt $t138:tt $t139:tt
+        $t140:tt $t141:tt $t142:tt $t143:tt $t144:tt $t145:tt $t146:tt $t147:tt $t148:tt $t149:tt
+        $t150:tt $t151:tt $t152:tt $t153:tt $t154:tt $t155:tt $t156:tt $t157:tt $t158:tt $t159:tt
+        $t160:tt $t161:tt $t162:tt $t163:tt $t164:tt $t165:tt $t166:tt $t167:tt $t168:tt $t169:tt
+        $t170:tt $t171:tt $t172:tt $t173:tt $t174:tt $t175:tt $t176:tt $t177:tt $t178:tt $t179:tt
+        $t180:tt $t181:tt $t182:tt $t183:tt $t184:tt $t185:tt $t186:tt $t187:tt $t188:tt $t189:tt
+        $t190:tt $t191:tt $t192:tt $t193:tt $t194:tt $t195:tt $t196:tt $t197:tt $t198:tt $t199:tt
+        $t200:tt $t201:tt $t202:tt $t203:tt $t204:tt $t205:tt $t206:tt $t207:tt $t208:tt $t209:tt
+        $t210:tt $t211:tt $t212:tt $t213:tt $t214:tt $t215:tt $t216:tt $t217:tt $t218:tt $t219:tt
+        $t220:tt $t221:tt $t222:tt $t223:tt $t224:tt $t225:tt $t226:tt $t227:tt $t228:tt $t229:tt
+        $t230:tt $t231:tt $t232:tt $t233:tt $t234:tt $t235:tt $t236:tt $t237:tt $t238:tt $t239:tt
+        $t240:tt $t241:tt $t242:tt $t243:tt $t244:tt $t245:tt $t246:tt $t247:tt $t248:tt $t249:tt
+        $t250:tt $t251:tt $t252:tt $t253:tt $t254:tt $t255:tt $t256:tt $t257:tt $t258:tt $t259:tt
+        $t260:tt $t261:tt $t262:tt $t263:tt $t264:tt $t265:tt $t266:tt $t267:tt $t268:tt $t269:tt
+        $t270:tt $t271:tt $t272:tt $t273:tt $t274:tt $t275:tt $t276:tt $t277:tt $t278:tt $t279:tt
+        $t280:tt $t281:tt $t282:tt $t283:tt $t284:tt $t285:tt $t286:tt $t287:tt $t288:tt $t289:tt
+        $t290:tt $t291:tt $t292:tt $t293:tt $t294:tt $t295:tt $t296:tt $t297:tt $t298:tt $t299:tt
+        $t300:tt $t301:tt $t302:tt $t303:tt $t304:tt $t305:tt $t306:tt $t307:tt $t308:tt $t309:tt
+        $t310:tt $t311:tt $t312:tt $t313:tt $t314:tt $t315:tt $t316:tt $t317:tt $t318:tt $t319:tt
+        $t320:tt $t321:tt $t322:tt $t323:tt $t324:tt $t325:tt $t326:tt $t327:tt $t328:tt $t329:tt
+        $t330:tt $t331:tt $t332:tt $t333:tt $t334:tt $t335:tt $t336:tt $t337:tt $t338:tt $t339:tt
+        $t340:tt $t341:tt $t342:tt $t343:tt $t344:tt $t345:tt $t346:tt $t347:tt $t348:tt $t349:tt
+        $t350:tt $t351:tt $t352:tt $t353:tt $t354:tt $t355:tt $t356:tt $t357:tt $t358:tt $t359:tt
+        $t360:tt $t361:tt $t362:tt $t363:tt $t364:tt $t365:tt $t366:tt $t367:tt $t368:tt $t369:tt
+        $t370:tt $t371:tt $t372:tt $t373:tt $t374:tt $t375:tt $t376:tt $t377:tt $t378:tt $t379:tt
+        $t380:tt $t381:tt $t382:tt $t383:tt $t384:tt $t385:tt $t386:tt $t387:tt $t388:tt $t389:tt
+        $t390:tt $t391:tt $t392:tt $t393:tt $t394:tt $t395:tt $t396:tt $t397:tt $t398:tt $t399:tt
+    ) => {};
+}
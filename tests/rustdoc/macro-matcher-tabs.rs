@@ -0,0 +1,17 @@
+// ignore-tidy-tab
+// Tabs in the source of matchers are expanded to spaces, both when they are
+// used for indentation and in the middle of a line.
+
+#![crate_name = "foo"]
+
+pub mod inner {
+	// @snapshot tabs_pre foo/macro.tabs.html //pre/text()
+	#[macro_export]
+	macro_rules! tabs {
+		(
+			$name:ident	=> $value:expr,
+			$a:ident	=> $b:expr
+		) => {};
+		($x:tt	$y:tt) => {};
+	}
+}
@@ -0,0 +1,15 @@
+// compile-flags: -Z unstable-options --normalize-macro-matchers --macro-matcher-token-limit 3
+// Pretty-printed matchers with more tokens than the limit are truncated, which
+// the comment after their arm tells.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.limited.html
+// @has - '//pre' '($a:expr) => { ... };'
+// @!has - '//pre' '($a:expr) => { ... }; // truncated'
+// @has - '//pre' '($a:expr, $b:expr ...) => { ... }; // truncated'
+#[macro_export]
+macro_rules! limited {
+    ($a:expr) => {};
+    ($a:expr, $b:expr, $c:expr) => {};
+}
@@ -0,0 +1,47 @@
+// ignore-tidy-end-whitespace
+// ignore-tidy-tab
+// Neither the source snippet nor the pretty-printed rendering of a matcher may
+// leave whitespace at the end of a line of the declaration.
+
+#![crate_name = "foo"]
+
+// @has foo/macro.snippet.html
+// @!matches - '//pre[@class="rust item-decl"]' '[ \t]\n'
+// @has - '//pre[@class="rust item-decl"]' '$a:expr ,'
+#[macro_export]
+macro_rules! snippet {
+    (  
+        $a:expr ,    
+        	
+        $b:expr	
+    ) => {};
+    ($($c:tt)* ;  
+    ) => {};
+}
+
+macro_rules! make_macro {
+    ($macro_name:ident $($matcher:tt)*) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($($matcher)*) => {};
+        }
+    }
+}
+
+// @has foo/macro.short.html
+// @!matches - '//pre[@class="rust item-decl"]' '[ \t]\n'
+make_macro!(short $a:expr, $b:expr);
+
+// @has foo/macro.wrapped.html
+// @!matches - '//pre[@class="rust item-decl"]' '[ \t]\n'
+make_macro!(wrapped
+    $first:expr, $second:expr, $third:expr, $fourth:expr, $fifth:expr, $sixth:expr,
+    $seventh:expr, $eighth:expr, $ninth:expr, $tenth:expr, $eleventh:expr
+);
+
+// @has foo/macro.nested.html
+// @!matches - '//pre[@class="rust item-decl"]' '[ \t]\n'
+make_macro!(nested
+    { $($key:ident => [$($value:expr),* $(,)?]);* } ( $($rest:tt)* ) $(@ $tail:literal)+
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30
+);
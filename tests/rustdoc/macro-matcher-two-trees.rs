@@ -0,0 +1,14 @@
+// aux-build:macro-matcher-joined-span.rs
+
+// The span of this matcher covers two token trees of the source, which must not
+// be taken for the matcher, wrapped in its own parentheses. The tokens of the
+// matcher are shown instead, with a note saying so.
+
+#![crate_name = "foo"]
+
+extern crate macro_matcher_joined_span;
+
+// @has foo/macro.two_trees.html
+// @has - '//pre' '((a, b) (c, d)) => { ... }; // as compiled'
+// @!has - '//pre' '(a ,b) (c ,d) => { ... };'
+macro_matcher_joined_span::joined_matcher!(two_trees (a ,b) (c ,d));
@@ -0,0 +1,28 @@
+// Macros are listed on module pages and in search results with the matcher of
+// their first arm that isn't internal to them, on a single line.
+
+#![crate_name = "foo"]
+
+// @has 'foo/index.html'
+// @has - '//code[@class="macro-short"]' '($x:expr)'
+// @has - '//code[@class="macro-short"]' '($($t:tt)*)'
+// @!has - '//code[@class="macro-short"]' '(@munch $($t:tt)*)'
+// @has - '//code[@class="macro-short"]' '($name:ident, $(…),* => $($extra:tt)*)'
+// @hasraw 'search-index.js' '<code>($x:expr)</code> Documented.'
+
+/// Documented.
+#[macro_export]
+macro_rules! documented {
+    ($x:expr) => {};
+}
+
+#[macro_export]
+macro_rules! internal_first {
+    (@munch $($t:tt)*) => {};
+    ($($t:tt)*) => {};
+}
+
+#[macro_export]
+macro_rules! long {
+    ($name:ident, $($field:ident: $ty:ty = $default:expr),* => $($extra:tt)*) => {};
+}
@@ -0,0 +1,29 @@
+// ignore-tidy-linelength
+
+// A macro documented with `#[doc(macro_signature)]` is shown with that
+// signature, and its declaration is kept behind a toggle.
+
+#![crate_name = "foo"]
+#![feature(doc_macro_signature)]
+
+// @has 'foo/macro.munch.html'
+// @has - '//pre[@class="rust item-decl"]' 'munch!($(KEY => VALUE),*)'
+// @has - '//details[@class="toggle type-contents-toggle"]/summary' 'Show declaration'
+// @has - '//details[@class="toggle type-contents-toggle"]//pre[@class="rust item-decl"]' 'macro_rules! munch {'
+// @has - '//details[@class="toggle type-contents-toggle"]//pre[@class="rust item-decl"]' '(@acc [$($done:tt)*] $key:ident => $value:expr, $($rest:tt)*) => { ... };'
+#[doc(macro_signature = "munch!($(KEY => VALUE),*)")]
+#[macro_export]
+macro_rules! munch {
+    (@acc [$($done:tt)*]) => {};
+    (@acc [$($done:tt)*] $key:ident => $value:expr, $($rest:tt)*) => {};
+    ($($rest:tt)*) => {};
+}
+
+// Other macros are shown with their declaration as usual.
+// @has 'foo/macro.plain.html'
+// @count - '//pre[@class="rust item-decl"]' 1
+// @count - '//details[@class="toggle type-contents-toggle"]' 0
+#[macro_export]
+macro_rules! plain {
+    ($a:expr) => {};
+}
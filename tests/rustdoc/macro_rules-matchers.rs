@@ -10,7 +10,8 @@
 // @hasraw - '{ () =&gt; { ... }; ($('
 // @has - '//span[@class="macro-nonterminal"]' '$'
 // @has - '//span[@class="macro-nonterminal"]' 'arg'
-// @hasraw - ':tt)+'
+// @has - '//span[@class="macro-fragment"]' 'tt'
+// @has - '//span[@class="macro-repetition"]' '+'
 // @hasraw - ') =&gt; { ... }; }'
 pub use std::todo;
 
@@ -23,8 +24,10 @@ mod mod1 {
     // @has - '//span[@class="macro-nonterminal"]' 'arg'
     // @hasraw - ':'
     // @hasraw - 'expr'
+    // @has - '//span[@class="macro-fragment"]' 'expr'
     // @hasraw - '),'
     // @hasraw - '+'
+    // @has - '//span[@class="macro-repetition"]' '+'
     // @hasraw - ') =&gt; { ... }; }'
     #[macro_export]
     macro_rules! macro1 {
@@ -0,0 +1,7 @@
+#[doc(macro_signature = "m!()")] //~ ERROR: `#[doc(macro_signature)]` is experimental
+#[macro_export]
+macro_rules! m {
+    () => {};
+}
+
+fn main() {}
@@ -0,0 +1,3 @@
+#![doc(test(prelude = "let x = 1;"))] //~ ERROR: `#![doc(test(prelude))]` is experimental
+
+fn main() {}